@@ -0,0 +1,67 @@
+//! Téléchargement des fichiers reçus en argument de commande.
+
+use std::fmt::Display;
+use serenity::model::channel::Attachment;
+
+/// Erreur lors du téléchargement d'une pièce jointe.
+#[derive(Debug)]
+pub enum DownloadError {
+    /// Le fichier dépasse la taille maximale autorisée.
+    /// 
+    /// Si le téléchargement a été interrompu, `size` est la taille reçue jusque là.
+    TooLarge {
+        size: u64,
+        max_size: u64,
+    },
+    /// Le téléchargement a échoué.
+    Http(serenity::Error),
+    /// Le fichier n'est pas un texte UTF-8.
+    NotText(String),
+}
+
+impl Display for DownloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadError::TooLarge { size, max_size } => write!(f, "Le fichier est trop volumineux ({} octets, {} octets maximum).", size, max_size),
+            DownloadError::Http(e) => write!(f, "Impossible de télécharger le fichier : {}", e),
+            DownloadError::NotText(filename) => write!(f, "Le fichier {} n'est pas un fichier texte.", filename),
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+impl From<reqwest::Error> for DownloadError {
+    fn from(e: reqwest::Error) -> Self {
+        DownloadError::Http(e.into())
+    }
+}
+
+/// Télécharge une pièce jointe si sa taille ne dépasse pas `max_size` octets.
+/// 
+/// La taille annoncée par Discord est vérifiée avant le téléchargement. Le contenu est
+/// ensuite reçu par morceaux et le téléchargement est interrompu dès que `max_size` est dépassé.
+pub async fn download(attachment: &Attachment, max_size: u64) -> Result<Vec<u8>, DownloadError> {
+    if attachment.size > max_size {
+        return Err(DownloadError::TooLarge { size: attachment.size, max_size });
+    }
+    let mut response = reqwest::get(&attachment.url).await?.error_for_status()?;
+    if let Some(size) = response.content_length().filter(|size| *size > max_size) {
+        return Err(DownloadError::TooLarge { size, max_size });
+    }
+    let mut content = Vec::with_capacity(attachment.size as usize);
+    while let Some(chunk) = response.chunk().await? {
+        let size = (content.len() + chunk.len()) as u64;
+        if size > max_size {
+            return Err(DownloadError::TooLarge { size, max_size });
+        }
+        content.extend_from_slice(&chunk);
+    }
+    Ok(content)
+}
+
+/// Télécharge une pièce jointe et la décode en texte UTF-8.
+pub async fn download_text(attachment: &Attachment, max_size: u64) -> Result<String, DownloadError> {
+    let content = download(attachment, max_size).await?;
+    String::from_utf8(content).map_err(|_| DownloadError::NotText(attachment.filename.clone()))
+}
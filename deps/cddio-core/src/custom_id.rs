@@ -0,0 +1,34 @@
+//! Découpage des custom_id paramétrés des composants de message.
+//! 
+//! Un motif tel que `appeal:{case_id}` est découpé par `cddio-macros` en textes fixes
+//! (`["appeal:", ""]`) encadrant les valeurs capturées.
+
+/// Extrait les valeurs capturées de `custom_id` selon les textes fixes du motif.
+/// 
+/// `literals` contient le texte précédant chaque capture, puis le texte final :
+/// il y a donc une capture de moins que de textes fixes.
+/// Retourne `None` si `custom_id` ne correspond pas au motif ou si une capture est vide.
+pub fn split<'a>(custom_id: &'a str, literals: &[&str]) -> Option<Vec<&'a str>> {
+    let (first, others) = literals.split_first()?;
+    let mut rest = custom_id.strip_prefix(first)?;
+    let mut captures = Vec::with_capacity(others.len());
+    for (i, literal) in others.iter().enumerate() {
+        let capture = if i + 1 == others.len() {
+            let capture = rest.strip_suffix(literal)?;
+            rest = "";
+            capture
+        } else {
+            let (capture, next) = rest.split_once(literal)?;
+            rest = next;
+            capture
+        };
+        if capture.is_empty() {
+            return None;
+        }
+        captures.push(capture);
+    }
+    if !rest.is_empty() {
+        return None;
+    }
+    Some(captures)
+}
@@ -1,10 +1,16 @@
 use std::{slice::Iter, fmt::Display};
 
 use serenity::{
-    model::application::command::CommandOptionType, 
-    builder::{CreateApplicationCommands, CreateApplicationCommandOption, CreateApplicationCommand}
+    model::{
+        application::{command::{CommandOptionType, CommandType}, interaction::application_command::{CommandDataOption, CommandDataOptionValue}},
+        channel::ChannelType,
+        permissions::Permissions
+    }, 
+    builder::{CreateApplicationCommands, CreateApplicationCommandOption, CreateApplicationCommand},
+    json::{json, Value}
 };
 use crate::message::{self, ToMessage};
+use crate::locale::Lang;
 
 /// The component declaration trait.
 /// 
@@ -17,6 +23,11 @@ pub trait ComponentDeclarative{
         None
     }
 }
+/// Translations of a name or a description, as `(locale, text)` pairs.
+/// 
+/// See [`locale::EN_LOCALES`](crate::locale::EN_LOCALES) for the locales used by `cddio-macros`.
+pub type Localizations = &'static [(&'static str, &'static str)];
+
 /// Node of the component declaration.
 /// 
 /// The component declaration is a tree of nodes. 
@@ -25,7 +36,9 @@ pub trait ComponentDeclarative{
 /// `children` and `commands` shoudl be declared const/static in the program and per component.
 pub struct Node {
     pub children: &'static [ChildNode],
-    pub commands: &'static [Command]
+    pub commands: &'static [Command],
+    /// Context menu commands. Only used on the root node.
+    pub context_menus: &'static [ContextMenu],
 }
 impl Node {
     pub fn add_application_command(&self, commands: &mut CreateApplicationCommands) {
@@ -35,6 +48,23 @@ impl Node {
         for command in self.commands {
             commands.add_application_command(command.into());
         }
+        for context_menu in self.context_menus {
+            commands.add_application_command(context_menu.into());
+        }
+    }
+    /// Application commands of the root node, registered globally if `global` is true,
+    /// on each guild otherwise.
+    pub fn application_commands(&self, global: bool) -> Vec<CreateApplicationCommand> {
+        let children = self.children.iter()
+            .filter(|child| child.global == global)
+            .map(CreateApplicationCommand::from);
+        let commands = self.commands.iter()
+            .filter(|command| command.global == global)
+            .map(CreateApplicationCommand::from);
+        let context_menus = self.context_menus.iter()
+            .filter(|context_menu| context_menu.global == global)
+            .map(CreateApplicationCommand::from);
+        children.chain(commands).chain(context_menus).collect()
     }
     pub fn iter_flat(&'static self) -> IterFlatNode {
         IterFlatNode::new(self)
@@ -60,8 +90,23 @@ pub struct ChildNode {
     pub name: &'static str,
     /// The node description.
     pub description: &'static str,
+    /// Translations of the name.
+    pub name_localizations: Localizations,
+    /// Translations of the description.
+    pub description_localizations: Localizations,
     /// The node children.
     pub node: Node,
+    /// Permissions required to use the commands of the node.
+    /// 
+    /// Only sent to Discord (`default_member_permissions`) for root nodes,
+    /// but always checked when a command is dispatched.
+    pub permissions: Permissions,
+    /// Whether the commands of the node are available in direct messages.
+    pub dm: bool,
+    /// Whether the node is registered globally instead of on each guild.
+    /// 
+    /// Only used for root nodes.
+    pub global: bool,
 }
 impl ChildNode {
     /// Iterate over the node to extract only commands.
@@ -76,6 +121,12 @@ impl From<&ChildNode> for CreateApplicationCommandOption {
             .kind(CommandOptionType::SubCommandGroup)
             .name(group.name)
             .description(group.description);
+        for (locale, name) in group.name_localizations {
+            app_cmd.name_localized(locale, name);
+        }
+        for (locale, description) in group.description_localizations {
+            app_cmd.description_localized(locale, description);
+        }
         for grp in group.node.children {
             app_cmd.add_sub_option(grp.into());
         }
@@ -90,6 +141,16 @@ impl From<&ChildNode> for CreateApplicationCommand {
         let mut app_cmd = CreateApplicationCommand::default();
         app_cmd.name(group.name);
         app_cmd.description(group.description);
+        app_cmd.dm_permission(group.dm);
+        for (locale, name) in group.name_localizations {
+            app_cmd.name_localized(locale, name);
+        }
+        for (locale, description) in group.description_localizations {
+            app_cmd.description_localized(locale, description);
+        }
+        if !group.permissions.is_empty() {
+            app_cmd.default_member_permissions(group.permissions);
+        }
         for grp in group.node.children {
             app_cmd.add_option(grp.into());
         }
@@ -132,8 +193,23 @@ pub struct Command {
     pub name: &'static str,
     /// The command description.
     pub description: &'static str,
+    /// Translations of the name.
+    pub name_localizations: Localizations,
+    /// Translations of the description.
+    pub description_localizations: Localizations,
     /// The command arguments. Can be empty.
     pub args: &'static [Argument],
+    /// Permissions required to use the command.
+    /// 
+    /// Only sent to Discord (`default_member_permissions`) for root commands,
+    /// but always checked when the command is dispatched.
+    pub permissions: Permissions,
+    /// Whether the command is available in direct messages.
+    pub dm: bool,
+    /// Whether the command is registered globally instead of on each guild.
+    /// 
+    /// Only used for root commands.
+    pub global: bool,
 }
 
 impl Command {
@@ -156,6 +232,12 @@ impl From<&Command> for CreateApplicationCommandOption {
             .kind(CommandOptionType::SubCommand)
             .name(command.name)
             .description(command.description);
+        for (locale, name) in command.name_localizations {
+            app_cmd.name_localized(locale, name);
+        }
+        for (locale, description) in command.description_localizations {
+            app_cmd.description_localized(locale, description);
+        }
         for arg in command.args {
             app_cmd.add_sub_option(arg.into());
         }
@@ -167,7 +249,17 @@ impl From<&Command> for CreateApplicationCommand {
         let mut app_cmd = CreateApplicationCommand::default();
         app_cmd
             .name(command.name)
-            .description(command.description);
+            .description(command.description)
+            .dm_permission(command.dm);
+        for (locale, name) in command.name_localizations {
+            app_cmd.name_localized(locale, name);
+        }
+        for (locale, description) in command.description_localizations {
+            app_cmd.description_localized(locale, description);
+        }
+        if !command.permissions.is_empty() {
+            app_cmd.default_member_permissions(command.permissions);
+        }
         for arg in command.args {
             app_cmd.add_option(arg.into());
         }
@@ -201,6 +293,42 @@ impl message::ToMessage for Command {
         message::Message { message: String::new(), embeds: vec![embed], ephemeral: false }
     }
 }
+/// Context menu command description data
+/// 
+/// Context menu commands are displayed when right-clicking a user or a message.
+/// They have no description nor arguments.
+pub struct ContextMenu {
+    /// The name of the command, displayed in the menu.
+    pub name: &'static str,
+    /// Translations of the name.
+    pub name_localizations: Localizations,
+    /// [`CommandType::User`] or [`CommandType::Message`].
+    pub kind: CommandType,
+    /// Permissions required to use the command.
+    pub permissions: Permissions,
+    /// Whether the command is available in direct messages.
+    pub dm: bool,
+    /// Whether the command is registered globally instead of on each guild.
+    pub global: bool,
+}
+
+impl From<&ContextMenu> for CreateApplicationCommand {
+    fn from(context_menu: &ContextMenu) -> Self {
+        let mut app_cmd = CreateApplicationCommand::default();
+        app_cmd
+            .name(context_menu.name)
+            .kind(context_menu.kind)
+            .dm_permission(context_menu.dm);
+        for (locale, name) in context_menu.name_localizations {
+            app_cmd.name_localized(locale, name);
+        }
+        if !context_menu.permissions.is_empty() {
+            app_cmd.default_member_permissions(context_menu.permissions);
+        }
+        app_cmd
+    }
+}
+
 /// Argument description data
 pub struct Argument {
     /// The name of the argument.
@@ -209,14 +337,92 @@ pub struct Argument {
     pub type_: CommandOptionType,
     /// The argument description.
     pub description: &'static str,
+    /// Translations of the name.
+    pub name_localizations: Localizations,
+    /// Translations of the description.
+    pub description_localizations: Localizations,
     /// Whether the argument is optional to the command.
     pub optional: bool,
+    /// Whether Discord asks the component for choices while the argument is typed.
+    pub autocomplete: bool,
+    /// The values the argument is restricted to. Empty if any value is accepted.
+    pub choices: &'static [Choice],
+    /// Minimum value of an integer or number argument.
+    pub min: Option<f64>,
+    /// Maximum value of an integer or number argument.
+    pub max: Option<f64>,
+    /// Minimum length of a string argument.
+    pub min_length: Option<u16>,
+    /// Maximum length of a string argument.
+    pub max_length: Option<u16>,
+    /// The channel types accepted by a channel argument. Empty if any channel is accepted.
+    pub channel_types: &'static [ChannelType],
 }
 impl Argument {
     pub fn to_markdown(&'static self) -> String {
         let opt_str = if self.optional { " (optionnel)" } else { "" };
         format!("**{}**{}: {}", self.name, opt_str, self.description)
     }
+    /// Name of the argument as displayed to a client in `lang`.
+    pub fn localized_name(&self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::Fr => self.name,
+            Lang::En => self.name_localizations.iter()
+                .find(|(locale, _)| locale.starts_with("en"))
+                .map_or(self.name, |(_, name)| name),
+        }
+    }
+    /// Check that the value of the argument respects its constraints.
+    /// 
+    /// Discord already applies the constraints sent when registering the command,
+    /// this check protects against outdated registrations.
+    /// The error is a message intended to the user, written in `lang`.
+    pub fn check(&self, option: &CommandDataOption, lang: Lang) -> Result<(), String> {
+        let name = self.localized_name(lang);
+        let number = match &option.resolved {
+            Some(CommandDataOptionValue::Integer(value)) => Some(*value as f64),
+            Some(CommandDataOptionValue::Number(value)) => Some(*value),
+            _ => None,
+        };
+        if let Some(number) = number {
+            if let Some(min) = self.min.filter(|min| number < *min) {
+                return Err(match lang {
+                    Lang::Fr => format!("L'argument \"{}\" doit être supérieur ou égal à {}.", name, min),
+                    Lang::En => format!("The argument \"{}\" must be greater than or equal to {}.", name, min),
+                });
+            }
+            if let Some(max) = self.max.filter(|max| number > *max) {
+                return Err(match lang {
+                    Lang::Fr => format!("L'argument \"{}\" doit être inférieur ou égal à {}.", name, max),
+                    Lang::En => format!("The argument \"{}\" must be less than or equal to {}.", name, max),
+                });
+            }
+        }
+        if let Some(CommandDataOptionValue::String(value)) = &option.resolved {
+            let length = value.chars().count();
+            if let Some(min_length) = self.min_length.filter(|min_length| length < *min_length as usize) {
+                return Err(match lang {
+                    Lang::Fr => format!("L'argument \"{}\" doit contenir au moins {} caractères.", name, min_length),
+                    Lang::En => format!("The argument \"{}\" must contain at least {} characters.", name, min_length),
+                });
+            }
+            if let Some(max_length) = self.max_length.filter(|max_length| length > *max_length as usize) {
+                return Err(match lang {
+                    Lang::Fr => format!("L'argument \"{}\" doit contenir au plus {} caractères.", name, max_length),
+                    Lang::En => format!("The argument \"{}\" must contain at most {} characters.", name, max_length),
+                });
+            }
+        }
+        if let Some(CommandDataOptionValue::Channel(channel)) = &option.resolved {
+            if !self.channel_types.is_empty() && !self.channel_types.contains(&channel.kind) {
+                return Err(match lang {
+                    Lang::Fr => format!("Le salon \"{}\" n'est pas du bon type.", name),
+                    Lang::En => format!("The channel \"{}\" is not of the right type.", name),
+                });
+            }
+        }
+        Ok(())
+    }
 }
 impl From<&Argument> for CreateApplicationCommandOption {
     fn from(argument: &Argument) -> Self {
@@ -225,11 +431,67 @@ impl From<&Argument> for CreateApplicationCommandOption {
             .kind(argument.type_)
             .name(argument.name)
             .required(!argument.optional)
+            .set_autocomplete(argument.autocomplete)
             .description(argument.description);
+        for (locale, name) in argument.name_localizations {
+            app_cmd.name_localized(locale, name);
+        }
+        for (locale, description) in argument.description_localizations {
+            app_cmd.description_localized(locale, description);
+        }
+        match (argument.type_, argument.min) {
+            (CommandOptionType::Integer, Some(min)) => { app_cmd.min_int_value(min as i64); },
+            (_, Some(min)) => { app_cmd.min_number_value(min); },
+            _ => (),
+        }
+        match (argument.type_, argument.max) {
+            (CommandOptionType::Integer, Some(max)) => { app_cmd.max_int_value(max as i64); },
+            (_, Some(max)) => { app_cmd.max_number_value(max); },
+            _ => (),
+        }
+        if let Some(min_length) = argument.min_length {
+            app_cmd.min_length(min_length);
+        }
+        if let Some(max_length) = argument.max_length {
+            app_cmd.max_length(max_length);
+        }
+        if !argument.channel_types.is_empty() {
+            app_cmd.channel_types(argument.channel_types);
+        }
+        for choice in argument.choices {
+            match *choice {
+                Choice::String(name, value) => { app_cmd.add_string_choice(name, value); },
+                // `add_int_choice` only accepts i32 values.
+                Choice::Integer(name, value) => {
+                    let choices = app_cmd.0.entry("choices").or_insert_with(|| Value::from(Vec::<Value>::new()));
+                    if let Some(choices) = choices.as_array_mut() {
+                        choices.push(json!({"name": name, "value": value}));
+                    }
+                },
+            }
+        }
         app_cmd
     }
 }
 
+/// Choice of an argument.
+/// 
+/// Discord displays the name of the choice and sends its value.
+pub enum Choice {
+    String(&'static str, &'static str),
+    Integer(&'static str, i64),
+}
+
+/// Type usable as a command argument restricted to a list of choices.
+/// 
+/// Implemented on fieldless enums with `#[derive(CommandChoices)]` from `cddio-macros`.
+pub trait CommandChoices: Sized {
+    /// The choices sent to Discord.
+    const CHOICES: &'static [Choice];
+    /// Convert the value of a choice back to the type.
+    fn from_choice(value: &str) -> Option<Self>;
+}
+
 impl Display for Argument {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}: {}", self.name, self.description)
@@ -4,15 +4,19 @@ use serenity::{
         application::{
             interaction::{
                 InteractionResponseType,
-                application_command::{ApplicationCommandInteraction, CommandDataOption, CommandData}
+                application_command::{ApplicationCommandInteraction, CommandDataOption, CommandData},
+                autocomplete::AutocompleteInteraction,
+                modal::ModalSubmitInteraction
             },
+            component::ActionRowComponent,
             command::CommandOptionType
-        }
+        },
+        permissions::Permissions
     }, 
     client::Context, 
-    builder::EditInteractionResponse
+    builder::{EditInteractionResponse, CreateInteractionResponse}
 };
-use crate::message::Message;
+use crate::{message::{self, Message}, declarative::Argument, locale::Lang};
 
 /// Helper to parse an application command.
 #[derive(Clone)]
@@ -43,7 +47,13 @@ impl<'a> CommandType<'a> {
 pub struct DelayedResponse<'a> {
     pub message: Option<Message>,
     ctx: &'a Context,
-    app_cmd: ApplicationCommandEmbed<'a>
+    interaction: DelayedInteraction<'a>
+}
+
+/// Interaction à laquelle répond un [`DelayedResponse`].
+enum DelayedInteraction<'a> {
+    Command(&'a ApplicationCommandInteraction),
+    Modal(&'a ModalSubmitInteraction),
 }
 
 impl<'a> DelayedResponse<'a> {
@@ -51,7 +61,14 @@ impl<'a> DelayedResponse<'a> {
     /// 
     /// Send a delayed response to the application command
     pub async fn new(ctx: &'a Context, app_cmd: ApplicationCommandEmbed<'a>, ephemeral: bool) -> serenity::Result<DelayedResponse<'a>> {
-        Self::send_new_response(ctx, app_cmd.0, ephemeral).await.or_else(|e| {
+        Self::with_interaction(ctx, DelayedInteraction::Command(app_cmd.0), ephemeral).await
+    }
+    /// Create a new delayed response to a modal submission
+    pub async fn from_modal(ctx: &'a Context, modal: ModalSubmitEmbed<'a>, ephemeral: bool) -> serenity::Result<DelayedResponse<'a>> {
+        Self::with_interaction(ctx, DelayedInteraction::Modal(modal.0), ephemeral).await
+    }
+    async fn with_interaction(ctx: &'a Context, interaction: DelayedInteraction<'a>, ephemeral: bool) -> serenity::Result<DelayedResponse<'a>> {
+        Self::send_new_response(ctx, &interaction, ephemeral).await.or_else(|e| {
             eprintln!("Cannot create response: {}", e);
             Err(e)
         })?;
@@ -59,7 +76,7 @@ impl<'a> DelayedResponse<'a> {
         Ok(DelayedResponse {
             message: None,
             ctx,
-            app_cmd
+            interaction
         })
     }
     /// Returns the embedded message. If the message is not yet created, it will be created.
@@ -74,7 +91,7 @@ impl<'a> DelayedResponse<'a> {
     }
     /// Consume the response and send it to edit the interaction
     pub async fn send(mut self) -> serenity::Result<()> {
-        let result = Self::edit_response(self.ctx, self.app_cmd.0, &self.message).await.or_else(|e| {
+        let result = Self::edit_response(self.ctx, &self.interaction, &self.message).await.or_else(|e| {
             eprintln!("Cannot create response: {}", e);
             Err(e)
         });
@@ -90,25 +107,29 @@ impl<'a> DelayedResponse<'a> {
     pub async fn edit_and_send<F>(self, f: F) -> serenity::Result<()> where
         F: FnOnce(&mut EditInteractionResponse) -> &mut EditInteractionResponse
         {
-        self.app_cmd.0.edit_original_interaction_response(self.ctx, f).await.and(Ok(()))
+        match self.interaction {
+            DelayedInteraction::Command(app_cmd) => app_cmd.edit_original_interaction_response(self.ctx, f).await.and(Ok(())),
+            DelayedInteraction::Modal(modal) => modal.edit_original_interaction_response(self.ctx, f).await.and(Ok(())),
+        }
     }
-    async fn send_new_response(ctx: &Context, app_cmd: &ApplicationCommandInteraction, ephemeral: bool) -> serenity::Result<()> {
-        app_cmd.create_interaction_response(ctx, |resp|{
-            resp
-                .kind(InteractionResponseType::DeferredChannelMessageWithSource)
-                .interaction_response_data(|data| {
-                    data.ephemeral(ephemeral)
-                })
-        }).await
+    async fn send_new_response(ctx: &Context, interaction: &DelayedInteraction<'_>, ephemeral: bool) -> serenity::Result<()> {
+        let mut deferred = CreateInteractionResponse::default();
+        deferred
+            .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+            .interaction_response_data(|data| {
+                data.ephemeral(ephemeral)
+            });
+        match interaction {
+            DelayedInteraction::Command(app_cmd) => app_cmd.create_interaction_response(ctx, |resp| {*resp = deferred; resp}).await,
+            DelayedInteraction::Modal(modal) => modal.create_interaction_response(ctx, |resp| {*resp = deferred; resp}).await,
+        }
     }
-    async fn edit_response(ctx: &Context, app_cmd: &ApplicationCommandInteraction, msg: &Option<Message>) -> serenity::Result<()> {
-        app_cmd.edit_original_interaction_response(ctx, |resp|{
-            match msg {
-                Some(msg) => *resp = msg.into(),
-                None => ()
-            };
-            resp
-        }).await.and(Ok(()))
+    async fn edit_response(ctx: &Context, interaction: &DelayedInteraction<'_>, msg: &Option<Message>) -> serenity::Result<()> {
+        let edit = msg.as_ref().map(EditInteractionResponse::from).unwrap_or_default();
+        match interaction {
+            DelayedInteraction::Command(app_cmd) => app_cmd.edit_original_interaction_response(ctx, |resp| {*resp = edit; resp}).await.and(Ok(())),
+            DelayedInteraction::Modal(modal) => modal.edit_original_interaction_response(ctx, |resp| {*resp = edit; resp}).await.and(Ok(())),
+        }
     }
 }
 
@@ -154,14 +175,7 @@ impl<'a> ApplicationCommandEmbed<'a> {
         ApplicationCommandEmbed(interaction, command)
     }
     pub fn fullname_vec<'b>(&'b self) -> Vec<&'b str> {
-        let mut names = vec![self.0.data.name.as_str()];
-        let mut cmd = self.0.data.options.first();
-        // s'inspirer de la fonction get_command pour produire le nom
-        while let Some(&CommandDataOption{ref name, ref options, kind: CommandOptionType::SubCommandGroup | CommandOptionType::SubCommand, ..}) = cmd {
-            names.push(name.as_str());
-            cmd = options.first();
-        }
-        names
+        fullname_vec(&self.0.data)
     }
     /// Retourne le nom de la commande complète.
     /// 
@@ -173,6 +187,10 @@ impl<'a> ApplicationCommandEmbed<'a> {
     pub fn get_guild_id(&self) -> Option<GuildId> {
         self.0.guild_id
     }
+    /// Retourne la langue du client Discord de l'utilisateur.
+    pub fn lang(&self) -> Lang {
+        Lang::from_locale(&self.0.locale)
+    }
     /// Cherche et retourne l'argument `name`.
     pub fn get_argument(&'a self, name: &str) -> Option<&'a CommandDataOption> {
         self.1.get_argument(name)
@@ -191,6 +209,172 @@ impl<'a> ApplicationCommandEmbed<'a> {
         }).await
     }
 
+    /// Vérifie que les arguments reçus respectent leurs contraintes.
+    /// 
+    /// Si un argument est invalide, un message d'erreur est envoyé.
+    pub async fn check_arguments(&self, ctx: &Context, arguments: &[Argument]) -> bool {
+        let error = arguments.iter()
+            .filter_map(|argument| self.get_argument(argument.name).map(|option| argument.check(option, self.lang())))
+            .find_map(Result::err);
+        let Some(error) = error else {
+            return true;
+        };
+        if let Err(e) = self.direct_response(ctx, message::error_lang(self.lang(), error).set_ephemeral(true)).await {
+            eprintln!("Cannot create response: {}", e);
+        }
+        false
+    }
+    /// Vérifie que la commande peut être utilisée par son auteur.
+    /// 
+    /// `default_member_permissions` n'est envoyé à Discord que pour les commandes racines
+    /// et peut être modifié par les administrateurs du serveur : les permissions sont donc
+    /// vérifiées à nouveau ici. Si la commande est refusée, un message d'erreur est envoyé.
+    pub async fn check_permissions(&self, ctx: &Context, permissions: Permissions, dm: bool) -> bool {
+        let lang = self.lang();
+        let error = match (self.0.guild_id, &self.0.member) {
+            (None, _) if !dm => lang.pick("Cette commande n'est pas disponible en message privé.", "This command is not available in direct messages."),
+            (None, _) => return true,
+            _ if permissions.is_empty() => return true,
+            (Some(_), Some(member)) if member.permissions.is_some_and(|p| p.administrator() || p.contains(permissions)) => return true,
+            _ => lang.pick("Vous n'avez pas les permissions nécessaires pour utiliser cette commande.", "You do not have the permissions required to use this command."),
+        };
+        if let Err(e) = self.direct_response(ctx, message::error_lang(lang, error).set_ephemeral(true)).await {
+            eprintln!("Cannot create response: {}", e);
+        }
+        false
+    }
+}
+
+/// # Conteneur d'autocomplétion
+/// 
+/// Lorsqu'un utilisateur remplit un argument déclaré avec l'autocomplétion, Discord envoie
+/// une interaction contenant la commande et l'argument en cours de saisie. Cette structure
+/// permet de retrouver cet argument et de répondre avec les propositions.
+#[derive(Clone)]
+pub struct AutocompleteEmbed<'a>(pub &'a AutocompleteInteraction);
+
+impl<'a> AutocompleteEmbed<'a> {
+    pub fn new(interaction: &'a AutocompleteInteraction) -> Self {
+        AutocompleteEmbed(interaction)
+    }
+    /// Retourne le nom de la commande complète, tel que `groupe.sous_groupe.commande`.
+    pub fn fullname(&self) -> String {
+        fullname_vec(&self.0.data).join(".")
+    }
+    /// Retourne l'id du serveur sur lequel la commande est saisie.
+    pub fn get_guild_id(&self) -> Option<GuildId> {
+        self.0.guild_id
+    }
+    /// Retourne la langue du client Discord de l'utilisateur.
+    pub fn lang(&self) -> Lang {
+        Lang::from_locale(&self.0.locale)
+    }
+    /// Vérifie que la commande peut être utilisée par l'auteur de la saisie.
+    /// 
+    /// Les règles sont celles de [`ApplicationCommandEmbed::check_permissions`]. Aucun message
+    /// n'est envoyé : si la commande est refusée, aucune proposition ne doit être faite.
+    pub fn check_permissions(&self, permissions: Permissions, dm: bool) -> bool {
+        match (self.0.guild_id, &self.0.member) {
+            (None, _) => dm,
+            _ if permissions.is_empty() => true,
+            (Some(_), Some(member)) => member.permissions.is_some_and(|p| p.administrator() || p.contains(permissions)),
+            _ => false,
+        }
+    }
+    /// Retourne l'argument en cours de saisie.
+    pub fn focused(&self) -> Option<&'a CommandDataOption> {
+        let mut options = &self.0.data.options;
+        loop {
+            if let Some(option) = options.iter().find(|option| option.focused) {
+                return Some(option);
+            }
+            match options.iter().find(|option| option.kind == CommandOptionType::SubCommand || option.kind == CommandOptionType::SubCommandGroup) {
+                Some(cmd) => options = &cmd.options,
+                None => return None,
+            }
+        }
+    }
+    /// Retourne le nom de l'argument en cours de saisie.
+    pub fn focused_name(&self) -> Option<&'a str> {
+        self.focused().map(|option| option.name.as_str())
+    }
+    /// Retourne le texte saisi par l'utilisateur dans l'argument en cours.
+    pub fn value(&self) -> &'a str {
+        self.focused()
+            .and_then(|option| option.value.as_ref())
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+    }
+    /// Envoie les propositions à Discord.
+    /// 
+    /// Discord n'accepte que 25 propositions, les suivantes sont ignorées.
+    pub async fn respond(&self, ctx: &Context, choices: Vec<String>) -> serenity::Result<()> {
+        self.0.create_autocomplete_response(ctx, |resp| {
+            for choice in choices.iter().take(25) {
+                resp.add_string_choice(choice, choice);
+            }
+            resp
+        }).await
+    }
+}
+
+/// # Conteneur de formulaire
+/// 
+/// Lorsqu'un utilisateur valide un formulaire (modal), cette structure permet de lire
+/// les champs saisis par leur nom et de répondre à l'interaction.
+#[derive(Clone)]
+pub struct ModalSubmitEmbed<'a>(pub &'a ModalSubmitInteraction);
+
+impl<'a> ModalSubmitEmbed<'a> {
+    pub fn new(interaction: &'a ModalSubmitInteraction) -> Self {
+        ModalSubmitEmbed(interaction)
+    }
+    /// Retourne le `custom_id` du formulaire.
+    pub fn custom_id(&self) -> &'a str {
+        self.0.data.custom_id.as_str()
+    }
+    /// Retourne l'id du serveur sur lequel le formulaire a été envoyé.
+    pub fn get_guild_id(&self) -> Option<GuildId> {
+        self.0.guild_id
+    }
+    /// Retourne la langue du client Discord de l'utilisateur.
+    pub fn lang(&self) -> Lang {
+        Lang::from_locale(&self.0.locale)
+    }
+    /// Retourne la valeur du champ texte `name`.
+    /// 
+    /// Un champ facultatif laissé vide est reçu comme une chaîne vide.
+    pub fn get_input(&self, name: &str) -> Option<&'a str> {
+        self.0.data.components.iter()
+            .flat_map(|row| row.components.iter())
+            .find_map(|component| match component {
+                ActionRowComponent::InputText(input) if input.custom_id == name => Some(input.value.as_str()),
+                _ => None
+            })
+    }
+
+    pub async fn delayed_response<'b>(&'b self, ctx: &'b Context, ephemeral: bool) -> serenity::Result<DelayedResponse<'b>> {
+        DelayedResponse::from_modal(ctx, (*self).clone(), ephemeral).await
+    }
+
+    pub async fn direct_response(&self, ctx: &Context, msg: Message) -> serenity::Result<()> {
+        self.0.create_interaction_response(ctx, |resp|{
+            *resp = msg.into();
+            resp
+        }).await
+    }
+}
+
+/// Noms de la commande et des sous groupes.
+fn fullname_vec(data: &CommandData) -> Vec<&str> {
+    let mut names = vec![data.name.as_str()];
+    let mut cmd = data.options.first();
+    // s'inspirer de la fonction get_command pour produire le nom
+    while let Some(&CommandDataOption{ref name, ref options, kind: CommandOptionType::SubCommandGroup | CommandOptionType::SubCommand, ..}) = cmd {
+        names.push(name.as_str());
+        cmd = options.first();
+    }
+    names
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
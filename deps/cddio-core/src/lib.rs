@@ -33,12 +33,15 @@ pub mod event;
 pub mod container;
 pub mod embed;
 pub mod message;
+pub mod attachment;
+pub mod custom_id;
+pub mod locale;
 use std::sync::Arc;
 
 pub use declarative::ComponentDeclarative;
 pub use event::ComponentEvent;
 pub use container::ComponentContainer;
-pub use embed::ApplicationCommandEmbed;
+pub use embed::{ApplicationCommandEmbed, AutocompleteEmbed, ModalSubmitEmbed};
 
 pub trait Component: ComponentDeclarative + ComponentEvent {}
 pub type Components = Vec<Arc<dyn Component>>;
@@ -0,0 +1,48 @@
+//! Traduction des textes selon la langue du client Discord.
+//! 
+//! Le bot est rédigé en français. Les utilisateurs dont le client Discord est en anglais
+//! reçoivent les réponses en anglais lorsqu'une traduction est fournie.
+
+/// Locales Discord utilisées pour les traductions anglaises des noms et descriptions de commandes.
+pub const EN_LOCALES: &[&str] = &["en-US", "en-GB"];
+
+/// Traductions anglaises d'un nom ou d'une description, une par locale de [`EN_LOCALES`].
+pub const fn en_localizations(text: &'static str) -> [(&'static str, &'static str); EN_LOCALES.len()] {
+    let mut localizations = [("", text); EN_LOCALES.len()];
+    let mut i = 0;
+    while i < EN_LOCALES.len() {
+        localizations[i].0 = EN_LOCALES[i];
+        i += 1;
+    }
+    localizations
+}
+
+/// Langue d'une réponse
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    /// Français, langue par défaut du bot
+    #[default]
+    Fr,
+    /// Anglais
+    En,
+}
+
+impl Lang {
+    /// Langue correspondant à une locale Discord (`fr`, `en-US`, `en-GB`...).
+    /// 
+    /// Les locales autres que l'anglais utilisent le français.
+    pub fn from_locale(locale: &str) -> Self {
+        if locale.starts_with("en") {
+            Lang::En
+        } else {
+            Lang::Fr
+        }
+    }
+    /// Choisit la traduction correspondant à la langue.
+    pub fn pick<T>(self, fr: T, en: T) -> T {
+        match self {
+            Lang::Fr => fr,
+            Lang::En => en,
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use serenity::builder::{CreateEmbed, CreateInteractionResponse, EditInteractionResponse, CreateMessage};
 use serenity::utils::Colour;
+use serenity::model::application::component::InputTextStyle;
+use crate::locale::Lang;
 pub use serenity::builder::CreateEmbed as Embed;
 
 /// Trait to create a message from the current object.
@@ -105,21 +107,174 @@ impl From<Message> for EditInteractionResponse {
         response
     }
 }
+/// Interface de création de formulaire (modal)
+/// 
+/// Un formulaire est envoyé en réponse à une interaction. Les valeurs saisies sont
+/// reçues dans une interaction `ModalSubmit` ayant le même `custom_id`.
+#[derive(Debug, Clone)]
+pub struct Modal {
+    pub custom_id: String,
+    pub title: String,
+    pub inputs: Vec<ModalInput>,
+}
+
+impl Modal {
+    /// Création d'un formulaire vide
+    /// 
+    /// Le titre est tronqué à 45 caractères, la limite de Discord.
+    pub fn new<S1: ToString, S2: ToString>(custom_id: S1, title: S2) -> Self {
+        Modal {
+            custom_id: custom_id.to_string(),
+            title: title.to_string().chars().take(45).collect(),
+            inputs: Vec::new(),
+        }
+    }
+    /// Ajoute un champ texte au formulaire
+    /// 
+    /// `custom_id` est le nom du champ utilisé pour lire sa valeur à la réception du formulaire.
+    pub fn add_input<S1, S2>(&mut self, custom_id: S1, label: S2, callback: impl FnOnce(&mut ModalInput) -> &mut ModalInput) -> &mut Self
+        where
+        S1: ToString,
+        S2: ToString,
+    {
+        let mut input = ModalInput::new(custom_id, label);
+        callback(&mut input);
+        self.inputs.push(input);
+        self
+    }
+}
+
+/// Champ texte d'un formulaire
+#[derive(Debug, Clone)]
+pub struct ModalInput {
+    pub custom_id: String,
+    pub label: String,
+    pub style: InputTextStyle,
+    pub required: bool,
+    pub placeholder: Option<String>,
+    pub value: Option<String>,
+    pub min_length: Option<u64>,
+    pub max_length: Option<u64>,
+}
+
+impl ModalInput {
+    /// Création d'un champ texte court et obligatoire
+    pub fn new<S1: ToString, S2: ToString>(custom_id: S1, label: S2) -> Self {
+        ModalInput {
+            custom_id: custom_id.to_string(),
+            label: label.to_string(),
+            style: InputTextStyle::Short,
+            required: true,
+            placeholder: None,
+            value: None,
+            min_length: None,
+            max_length: None,
+        }
+    }
+    /// Texte court ou sur plusieurs lignes
+    pub fn style(&mut self, style: InputTextStyle) -> &mut Self {
+        self.style = style;
+        self
+    }
+    /// Rend le champ obligatoire ou facultatif
+    pub fn required(&mut self, required: bool) -> &mut Self {
+        self.required = required;
+        self
+    }
+    /// Texte affiché lorsque le champ est vide
+    pub fn placeholder<S: ToString>(&mut self, placeholder: S) -> &mut Self {
+        self.placeholder = Some(placeholder.to_string());
+        self
+    }
+    /// Valeur pré-remplie
+    pub fn value<S: ToString>(&mut self, value: S) -> &mut Self {
+        self.value = Some(value.to_string());
+        self
+    }
+    /// Longueur minimale de la saisie
+    pub fn min_length(&mut self, min_length: u64) -> &mut Self {
+        self.min_length = Some(min_length);
+        self
+    }
+    /// Longueur maximale de la saisie
+    pub fn max_length(&mut self, max_length: u64) -> &mut Self {
+        self.max_length = Some(max_length);
+        self
+    }
+}
+
+impl From<Modal> for CreateInteractionResponse<'_> {
+    fn from(modal: Modal) -> Self {
+        use serenity::model::application::interaction::InteractionResponseType;
+        let mut response = CreateInteractionResponse::default();
+        response.kind(InteractionResponseType::Modal);
+        response.interaction_response_data(|data| {
+            data
+                .custom_id(modal.custom_id)
+                .title(modal.title)
+                .components(|comps| {
+                    for input in modal.inputs {
+                        comps.create_action_row(|row| {
+                            row.create_input_text(|text| {
+                                text
+                                    .custom_id(input.custom_id)
+                                    .label(input.label)
+                                    .style(input.style)
+                                    .required(input.required);
+                                if let Some(placeholder) = input.placeholder {
+                                    text.placeholder(placeholder);
+                                }
+                                if let Some(value) = input.value {
+                                    text.value(value);
+                                }
+                                if let Some(min_length) = input.min_length {
+                                    text.min_length(min_length);
+                                }
+                                if let Some(max_length) = input.max_length {
+                                    text.max_length(max_length);
+                                }
+                                text
+                            })
+                        });
+                    }
+                    comps
+                })
+        });
+        response
+    }
+}
+
 /// Génère un message d'erreur
 pub fn error<S: ToString>(error_message: S) -> Message {
-    custom_embed("Erreur", error_message, COLOR_ERROR)
+    error_lang(Lang::Fr, error_message)
 }
 /// Génère un message d'avertissement
 pub fn warn<S: ToString>(warn_message: S) -> Message {
-    custom_embed("Attention", warn_message, COLOR_WARN)
+    warn_lang(Lang::Fr, warn_message)
 }
 /// Génère un message de succès
 pub fn success<S: ToString>(success_message: S) -> Message {
-    custom_embed("Effectué", success_message, COLOR_SUCCESS)
+    success_lang(Lang::Fr, success_message)
 }
 /// Génère un message d'information
 pub fn info<S: ToString>(info_message: S) -> Message {
-    custom_embed("Information", info_message, COLOR_INFO)
+    info_lang(Lang::Fr, info_message)
+}
+/// Génère un message d'erreur dont le titre est dans la langue indiquée
+pub fn error_lang<S: ToString>(lang: Lang, error_message: S) -> Message {
+    custom_embed(lang.pick("Erreur", "Error"), error_message, COLOR_ERROR)
+}
+/// Génère un message d'avertissement dont le titre est dans la langue indiquée
+pub fn warn_lang<S: ToString>(lang: Lang, warn_message: S) -> Message {
+    custom_embed(lang.pick("Attention", "Warning"), warn_message, COLOR_WARN)
+}
+/// Génère un message de succès dont le titre est dans la langue indiquée
+pub fn success_lang<S: ToString>(lang: Lang, success_message: S) -> Message {
+    custom_embed(lang.pick("Effectué", "Done"), success_message, COLOR_SUCCESS)
+}
+/// Génère un message d'information dont le titre est dans la langue indiquée
+pub fn info_lang<S: ToString>(lang: Lang, info_message: S) -> Message {
+    custom_embed(lang.pick("Information", "Information"), info_message, COLOR_INFO)
 }
 /// Génère un message personnalisé
 pub fn custom_embed<S1, S2, C>(title:S1, message: S2, color: C) -> Message
@@ -0,0 +1,56 @@
+use quote::quote;
+use proc_macro2 as pm2;
+use crate::util::*;
+
+/// Nom affiché d'une variante : `#[choice(name="...")]` ou le nom de la variante.
+fn choice_name(variant: &syn::Variant) -> syn::Result<String> {
+    use syn::*;
+    let mut name = variant.ident.to_string();
+    for attr in variant.attrs.iter().filter(|attr| attr.path.is_ident("choice")) {
+        let args = parse2::<ParenValue<MacroArgs>>(attr.tokens.clone())?;
+        for arg in args.value.args.into_iter() {
+            match (arg.name.to_string().as_str(), arg.value.into_lit()?) {
+                ("name", Lit::Str(s)) => name = s.value(),
+                ("name", v) => return Err(Error::new_spanned(v, "String literal attendu")),
+                _ => return Err(Error::new_spanned(arg.name, "Argument inconnu.")),
+            }
+        }
+    }
+    Ok(name)
+}
+
+pub fn expand_command_choices(input: pm2::TokenStream) -> syn::Result<pm2::TokenStream> {
+    use syn::*;
+    let input: DeriveInput = parse2(input)?;
+    let ident = &input.ident;
+    let variants = match &input.data {
+        Data::Enum(DataEnum { variants, .. }) => variants,
+        _ => return Err(Error::new_spanned(ident, "CommandChoices ne s'applique qu'aux enums."))
+    };
+    if variants.len() > 25 {
+        return Err(Error::new_spanned(ident, "Discord n'accepte que 25 choix."));
+    }
+    let mut choices = vec![];
+    let mut matches = vec![];
+    for variant in variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(variant, "Les variantes ne doivent pas avoir de champ."));
+        }
+        let variant_ident = &variant.ident;
+        let name = choice_name(variant)?;
+        let value = variant_ident.to_string();
+        choices.push(quote! { cddio_core::declarative::Choice::String(#name, #value) });
+        matches.push(quote! { #value => Some(Self::#variant_ident) });
+    }
+    Ok(quote! {
+        impl cddio_core::declarative::CommandChoices for #ident {
+            const CHOICES: &'static [cddio_core::declarative::Choice] = &[#(#choices),*];
+            fn from_choice(value: &str) -> Option<Self> {
+                match value {
+                    #(#matches,)*
+                    _ => None
+                }
+            }
+        }
+    })
+}
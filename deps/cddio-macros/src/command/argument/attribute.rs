@@ -4,18 +4,73 @@ use crate::util::*;
 pub struct ArgumentAttribute {
     pub name: Option<String>,
     pub description: String,
+    /// Nom affiché aux clients Discord en anglais.
+    pub name_en: Option<String>,
+    /// Description affichée aux clients Discord en anglais.
+    pub description_en: Option<String>,
+    /// Méthode du composant fournissant les propositions d'autocomplétion.
+    pub autocomplete: Option<syn::Ident>,
+    /// Valeurs autorisées pour l'argument.
+    pub choices: Vec<syn::Lit>,
+    /// Valeur minimale d'un nombre.
+    pub min: Option<f64>,
+    /// Valeur maximale d'un nombre.
+    pub max: Option<f64>,
+    /// Longueur minimale d'une chaîne de caractères.
+    pub min_length: Option<u16>,
+    /// Longueur maximale d'une chaîne de caractères.
+    pub max_length: Option<u16>,
+    /// Types de salon acceptés, convertis en variantes de `ChannelType`.
+    pub channel_types: Vec<syn::Ident>,
+    /// Le type est une enum implémentant `CommandChoices`.
+    pub is_enum: bool,
 }
 
 impl ArgumentAttribute {
     pub fn from_attr(attr: syn::Attribute) -> syn::Result<Self> {
         let mut name = None;
         let mut description = None;
+        let mut name_en = None;
+        let mut description_en = None;
+        let mut autocomplete = None;
+        let mut choices = Vec::new();
+        let mut min = None;
+        let mut max = None;
+        let mut min_length = None;
+        let mut max_length = None;
+        let mut channel_types = Vec::new();
+        let mut is_enum = false;
         let arg_span = attr.span();
         let args = syn::parse2::<ParenValue<MacroArgs>>(attr.tokens)?;
         for arg in args.value.args.into_iter() {
-            match (arg.name.to_string().as_str(), arg.value) {
+            let value = match (arg.name.to_string().as_str(), arg.value) {
+                ("choices", MacroValue::Array(_, values)) => {
+                    choices = values.into_iter().map(|value| match value {
+                        syn::Expr::Lit(syn::ExprLit { lit, .. }) => Ok(lit),
+                        v => Err(syn::Error::new_spanned(v, "Literal attendu")),
+                    }).collect::<syn::Result<_>>()?;
+                    continue;
+                },
+                ("channel_types", MacroValue::Array(_, values)) => {
+                    channel_types = values.iter().map(Self::channel_type).collect::<syn::Result<_>>()?;
+                    continue;
+                },
+                (_, value) => value.into_lit()?,
+            };
+            match (arg.name.to_string().as_str(), value) {
                 ("name", syn::Lit::Str(s)) => name = Some(s.value()),
                 ("description", syn::Lit::Str(s)) => description = Some(s.value()),
+                ("name_en", syn::Lit::Str(s)) => name_en = Some(check_name(&s, true)?),
+                ("description_en", syn::Lit::Str(s)) => description_en = Some(s.value()),
+                ("autocomplete", syn::Lit::Str(s)) => autocomplete = Some(s.parse::<syn::Ident>()?),
+                ("min", syn::Lit::Int(i)) => min = Some(i.base10_parse::<f64>()?),
+                ("min", syn::Lit::Float(f)) => min = Some(f.base10_parse::<f64>()?),
+                ("max", syn::Lit::Int(i)) => max = Some(i.base10_parse::<f64>()?),
+                ("max", syn::Lit::Float(f)) => max = Some(f.base10_parse::<f64>()?),
+                ("min_length", syn::Lit::Int(i)) => min_length = Some(i.base10_parse::<u16>()?),
+                ("max_length", syn::Lit::Int(i)) => max_length = Some(i.base10_parse::<u16>()?),
+                ("enum", syn::Lit::Bool(b)) => is_enum = b.value,
+                ("min"|"max"|"min_length"|"max_length", v) => return Err(syn::Error::new_spanned(v, "Nombre attendu")),
                 _ => return Err(syn::Error::new_spanned(arg.name, "Argument inconnu.")),
             }
         }
@@ -25,6 +80,61 @@ impl ArgumentAttribute {
         Ok(ArgumentAttribute {
             name,
             description: description.unwrap(),
+            name_en,
+            description_en,
+            autocomplete,
+            choices,
+            min,
+            max,
+            min_length,
+            max_length,
+            channel_types,
+            is_enum,
         })
     }
-}
\ No newline at end of file
+    /// Convertit un type de salon tel que `text` ou `public_thread` en variante de `ChannelType`.
+    fn channel_type(value: &syn::Expr) -> syn::Result<syn::Ident> {
+        let ident = match value {
+            syn::Expr::Path(syn::ExprPath { path, .. }) => path.get_ident(),
+            _ => None,
+        };
+        let Some(ident) = ident else {
+            return Err(syn::Error::new_spanned(value, "Type de salon attendu (text, voice, category...)"));
+        };
+        let variant = ident.to_string()
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                    None => String::new(),
+                }
+            })
+            .collect::<String>();
+        Ok(syn::Ident::new(&variant, ident.span()))
+    }
+    /// Vérifie que les contraintes correspondent au type de l'argument.
+    pub fn check_type(&self, ty: &syn::Path) -> syn::Result<()> {
+        let ty_name = match ty.segments.last() {
+            Some(segment) => segment.ident.to_string(),
+            None => return Err(syn::Error::new_spanned(ty, "Type incomplet."))
+        };
+        let is_number = matches!(ty_name.as_str(), "u64" | "u32" | "u16" | "u8" | "i64" | "i32" | "i16" | "i8" | "f64" | "f32");
+        if (self.min.is_some() || self.max.is_some()) && !is_number {
+            return Err(syn::Error::new_spanned(ty, "min et max ne s'appliquent qu'aux nombres."));
+        }
+        if (self.min_length.is_some() || self.max_length.is_some()) && ty_name != "String" {
+            return Err(syn::Error::new_spanned(ty, "min_length et max_length ne s'appliquent qu'aux String."));
+        }
+        if self.autocomplete.is_some() && (ty_name != "String" || self.is_enum) {
+            return Err(syn::Error::new_spanned(ty, "autocomplete ne s'applique qu'aux String."));
+        }
+        if self.autocomplete.is_some() && !self.choices.is_empty() {
+            return Err(syn::Error::new_spanned(ty, "autocomplete et choices ne peuvent pas être utilisés ensemble."));
+        }
+        if !self.channel_types.is_empty() && !matches!(ty_name.as_str(), "PartialChannel" | "ChannelId") {
+            return Err(syn::Error::new_spanned(ty, "channel_types ne s'applique qu'aux salons."));
+        }
+        Ok(())
+    }
+}
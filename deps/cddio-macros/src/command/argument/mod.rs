@@ -9,6 +9,7 @@ pub use attribute::*;
 use std::fmt;
 
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum ArgumentType {
     Parameter{
         call_variable: pm2::TokenStream,
@@ -77,7 +78,9 @@ impl Argument {
                             None => var_name.to_string()
                         };
                         let call_variable = &var_name;
-                        let value_decoded = Reader::argument_decode(&arg_name, &inner_ty)?;
+                        attribute.check_type(inner_ty)?;
+                        let value_decoded = Reader::argument_decode(&arg_name, &inner_ty, attribute.is_enum)?
+                            .with_choices(inner_ty, &attribute.choices)?;
                         Ok(Argument {
                             arg_type: ArgumentType::Parameter{
                                 call_variable: quote!{#call_variable},
@@ -119,7 +122,9 @@ impl Argument {
                             Some(name) => name,
                             None => var_name.to_string()
                         };
-                        let value_decoded = Reader::argument_decode(&arg_name, &ty)?;
+                        attribute.check_type(&ty)?;
+                        let value_decoded = Reader::argument_decode(&arg_name, &ty, attribute.is_enum)?
+                            .with_choices(&ty, &attribute.choices)?;
                         let error_msg = format!("Argument \"{}\" manquant.", arg_name);
                         Ok(Argument {
                             arg_type: ArgumentType::Parameter{
@@ -159,30 +164,62 @@ impl Argument {
     pub fn get_type(&self) -> &ArgumentType {
         &self.arg_type
     }
-    pub fn get_declarative(&self) -> Option<pm2::TokenStream> {
-        let (attr, optional, option_type) = match &self.arg_type {
-            ArgumentType::Parameter{attribute, optional, reader, ..} => (attribute, optional, &reader.option_type),
+    /// Nom de l'argument de la commande Discord.
+    pub fn get_name(&self) -> Option<String> {
+        let attr = match &self.arg_type {
+            ArgumentType::Parameter{attribute, ..} => attribute,
             _ => return None
         };
-        let name = attr.name.clone();
-        let name = match (name, &self.base) {
-            (Some(name), _) => name,
-            (None, syn::FnArg::Typed(syn::PatType{ref pat, ..})) => {
-                let name = match &pat.as_ref() {
-                    syn::Pat::Ident(syn::PatIdent{ref ident, ..}) => ident.to_string(),
-                    _ => return None
-                };
-                name
+        match (attr.name.clone(), &self.base) {
+            (Some(name), _) => Some(name),
+            (None, syn::FnArg::Typed(syn::PatType{ref pat, ..})) => match &pat.as_ref() {
+                syn::Pat::Ident(syn::PatIdent{ref ident, ..}) => Some(ident.to_string()),
+                _ => None
             },
+            _ => None
+        }
+    }
+    /// Méthode d'autocomplétion de l'argument, si elle est déclarée.
+    pub fn get_autocomplete(&self) -> Option<&syn::Ident> {
+        match &self.arg_type {
+            ArgumentType::Parameter{attribute, ..} => attribute.autocomplete.as_ref(),
+            _ => None
+        }
+    }
+    pub fn get_declarative(&self) -> Option<pm2::TokenStream> {
+        let (attr, optional, option_type, choices) = match &self.arg_type {
+            ArgumentType::Parameter{attribute, optional, reader, ..} => (attribute, optional, &reader.option_type, &reader.choices),
             _ => return None
         };
+        let name = self.get_name()?;
         let description = &attr.description;
+        let name_localizations = localizations_tokens(attr.name_en.as_ref());
+        let description_localizations = localizations_tokens(attr.description_en.as_ref());
+        let autocomplete = attr.autocomplete.is_some();
+        let to_option = |value: Option<pm2::TokenStream>| match value {
+            Some(value) => quote! { Some(#value) },
+            None => quote! { None },
+        };
+        let min = to_option(attr.min.map(|v| quote! { #v }));
+        let max = to_option(attr.max.map(|v| quote! { #v }));
+        let min_length = to_option(attr.min_length.map(|v| quote! { #v }));
+        let max_length = to_option(attr.max_length.map(|v| quote! { #v }));
+        let channel_types = &attr.channel_types;
         Some(quote! {
             cddio_core::declarative::Argument{
                 name: #name,
                 type_: #option_type,
                 description: #description,
+                name_localizations: #name_localizations,
+                description_localizations: #description_localizations,
                 optional: #optional,
+                autocomplete: #autocomplete,
+                choices: #choices,
+                min: #min,
+                max: #max,
+                min_length: #min_length,
+                max_length: #max_length,
+                channel_types: &[#(serenity::model::channel::ChannelType::#channel_types),*],
             }
         })
     }
@@ -5,6 +5,7 @@ use quote::quote;
 pub struct Reader {
     pub read_expr: pm2::TokenStream,
     pub option_type: pm2::TokenStream,
+    pub choices: pm2::TokenStream,
 }
 
 macro_rules! to_decl {
@@ -16,9 +17,19 @@ macro_rules! to_decl {
 }
 
 impl Reader {
-    pub fn argument_decode(name: &str, ty: &syn::Path) -> syn::Result<Reader> {
+    /// Lecture de l'argument selon son type.
+    /// 
+    /// Avec `is_enum` (`#[argument(enum=true)]`), le type doit implémenter `CommandChoices`.
+    pub fn argument_decode(name: &str, ty: &syn::Path, is_enum: bool) -> syn::Result<Reader> {
         use syn::*;
         
+        if is_enum {
+            return Ok(Reader {
+                read_expr: Self::custom_reader(name, quote! {String(ref s)}, quote! { <#ty as cddio_core::declarative::CommandChoices>::from_choice(s) }),
+                option_type: to_decl! {String},
+                choices: quote! { <#ty as cddio_core::declarative::CommandChoices>::CHOICES },
+            });
+        }
         let (ident, ty_name) = match ty.segments.last() {
             Some(segment) => (&segment.ident, segment.ident.to_string()),
             None => return Err(Error::new_spanned(ty, "Type incomplet."))
@@ -27,54 +38,103 @@ impl Reader {
             "String" => Reader {
                 read_expr: Self::reader(name, quote! {String}),
                 option_type: to_decl! {String},
+                choices: quote! {&[]},
             },
             "str" => return Err(syn::Error::new_spanned(ty, "Utilisez String à la place.")),
             "u64" | "u32" | "u16" | "u8" 
             | "i64" | "i32" | "i16" | "i8" => Reader {
                 read_expr: Self::custom_reader(name, quote! {Integer(ref s)},quote! { Some(*s as #ident) } ),
                 option_type: to_decl! {Integer},
+                choices: quote! {&[]},
             },
             "bool" => Reader {
                 read_expr: Self::reader(name, quote! {Boolean}),
                 option_type: to_decl! {Boolean},
+                choices: quote! {&[]},
             },
             "User" => Reader {
                 read_expr: Self::custom_reader(name, quote! {User(s, _)}, quote! { Some(s) }),
                 option_type: to_decl! {User},
+                choices: quote! {&[]},
             },
             "UserId" => Reader {
                 read_expr: Self::custom_reader(name, quote! {User(s, _)}, quote! { Some(s.id) }),
                 option_type: to_decl! {User},
+                choices: quote! {&[]},
             },
             "Role" => Reader {
                 read_expr: Self::reader(name, quote! {Role}),
                 option_type: to_decl! {Role},
+                choices: quote! {&[]},
             },
             "RoleId" => Reader {
                 read_expr: Self::custom_reader(name, quote! {Role(s)}, quote! { Some(s.id) }),
                 option_type: to_decl! {Role},
+                choices: quote! {&[]},
             },
             "Mentionable" => Reader {
                 read_expr: Self::mentionable_reader(name),
                 option_type: to_decl! {Mentionable},
+                choices: quote! {&[]},
             },
             "PartialChannel" => Reader{
                 read_expr: Self::reader(name, quote! {Channel}),
                 option_type: to_decl! {Channel},
+                choices: quote! {&[]},
             },
             "ChannelId" => Reader {
                 read_expr: Self::custom_reader(name, quote! {Channel(s)}, quote! { Some(s.id) }),
                 option_type: to_decl! {Channel},
+                choices: quote! {&[]},
+            },
+            "Attachment" => Reader {
+                read_expr: Self::reader(name, quote! {Attachment}),
+                option_type: to_decl! {Attachment},
+                choices: quote! {&[]},
             },
             "f64" | "f32" => Reader {
                 read_expr: Self::custom_reader(name, quote! {Number(s)}, quote! { Some(*s as #ty) } ),
                 option_type: to_decl! {Number},
+                choices: quote! {&[]},
             } ,
-            _ => return Err(Error::new_spanned(ty, "Type d'argument incompatible.")),
+            _ => return Err(Error::new_spanned(ty, "Type d'argument incompatible. Pour une enum dérivant CommandChoices, ajoutez enum=true à l'attribut argument.")),
         })
     }
-    fn new(expr: pm2::TokenStream,declarative: pm2::TokenStream) -> Reader {
-        Reader { read_expr: expr, option_type: declarative }
+    /// Restreint l'argument aux valeurs de `#[argument(choices=[...])]`.
+    /// 
+    /// Seuls les arguments de type String et entier peuvent avoir des choix.
+    pub fn with_choices(self, ty: &syn::Path, choices: &[syn::Lit]) -> syn::Result<Reader> {
+        use syn::*;
+        if choices.is_empty() {
+            return Ok(self);
+        }
+        if choices.len() > 25 {
+            return Err(Error::new_spanned(&choices[25], "Discord n'accepte que 25 choix."));
+        }
+        let ty_name = match ty.segments.last() {
+            Some(segment) => segment.ident.to_string(),
+            None => return Err(Error::new_spanned(ty, "Type incomplet."))
+        };
+        let choices = match ty_name.as_str() {
+            "String" => choices.iter().map(|choice| match choice {
+                Lit::Str(s) => Ok(quote! { cddio_core::declarative::Choice::String(#s, #s) }),
+                _ => Err(Error::new_spanned(choice, "String literal attendu")),
+            }).collect::<Result<Vec<_>>>()?,
+            "u64" | "u32" | "u16" | "u8" 
+            | "i64" | "i32" | "i16" | "i8" => choices.iter().map(|choice| match choice {
+                Lit::Int(i) => {
+                    let value = i.base10_parse::<i64>()?;
+                    let name = value.to_string();
+                    Ok(quote! { cddio_core::declarative::Choice::Integer(#name, #value) })
+                },
+                _ => Err(Error::new_spanned(choice, "Entier attendu")),
+            }).collect::<Result<Vec<_>>>()?,
+            _ => return Err(Error::new_spanned(ty, "Les choix ne sont possibles que pour les arguments String ou entiers.")),
+        };
+        Ok(Reader {
+            choices: quote! { &[#(#choices),*] },
+            ..self
+        })
     }
     fn custom_reader(name: &str, ty: pm2::TokenStream, expr: pm2::TokenStream) -> pm2::TokenStream {
         quote! {
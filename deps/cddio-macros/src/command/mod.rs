@@ -1,5 +1,5 @@
 mod argument;
-use crate::util::{ParenValue, MacroArgs};
+use crate::util::{ParenValue, MacroArgs, parse_permissions, permissions_tokens, localizations_tokens, check_name};
 use proc_macro2 as pm2;
 use syn::spanned::Spanned;
 use std::fmt;
@@ -9,11 +9,30 @@ use self::argument::{ArgumentType, Argument};
 use super::Function;
 
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct CommandAttribute {
     pub name: Option<String>,
     pub description: String,
-    pub group: Option<String>
+    pub name_en: Option<String>,
+    pub description_en: Option<String>,
+    pub group: Option<String>,
+    pub permissions: Vec<syn::Ident>,
+    pub dm: bool,
+    pub global: bool,
+}
+impl Default for CommandAttribute {
+    fn default() -> Self {
+        CommandAttribute {
+            name: None,
+            description: String::new(),
+            name_en: None,
+            description_en: None,
+            group: None,
+            permissions: Vec::new(),
+            dm: true,
+            global: false,
+        }
+    }
 }
 impl CommandAttribute {
     fn from_attr(attr: syn::Attribute) -> syn::Result<Self> {
@@ -22,17 +41,26 @@ impl CommandAttribute {
         let arg_span = attr.span();
         let args = parse2::<ParenValue<MacroArgs>>(attr.tokens)?;
         for arg in args.value.args.into_iter() {
-            match (arg.name.to_string().as_str(), arg.value) {
+            match (arg.name.to_string().as_str(), arg.value.into_lit()?) {
                 ("name", Lit::Str(s)) => result.name = Some(s.value()),
                 ("description", Lit::Str(s)) => result.description = s.value(),
+                ("name_en", Lit::Str(s)) => result.name_en = Some(check_name(&s, true)?),
+                ("description_en", Lit::Str(s)) => result.description_en = Some(s.value()),
                 ("group", Lit::Str(s)) => result.group = Some(s.value()),
-                ("name"|"description"|"group", v) => return Err(syn::Error::new_spanned(v, "String literal attendu")),
+                ("permissions", Lit::Str(s)) => result.permissions = parse_permissions(&s)?,
+                ("dm", Lit::Bool(b)) => result.dm = b.value,
+                ("global", Lit::Bool(b)) => result.global = b.value,
+                ("name"|"description"|"name_en"|"description_en"|"group"|"permissions", v) => return Err(syn::Error::new_spanned(v, "String literal attendu")),
+                ("dm"|"global", v) => return Err(syn::Error::new_spanned(v, "Booléen attendu")),
                 _ => return Err(Error::new_spanned(arg.name, "Argument inconnu.")),
             }
         }
         if result.description.is_empty() {
             return Err(Error::new(arg_span, "missing description argument"));
         }
+        if result.global && result.group.is_some() {
+            return Err(Error::new(arg_span, "Une commande dans un groupe est enregistrée avec son groupe racine, global n'est pas autorisé."));
+        }
         Ok(result)
     }
 }
@@ -61,14 +89,24 @@ impl Command {
             None => self.name().to_string(),
         }; 
         let description = &self.attr.description;
+        let permissions = permissions_tokens(&self.attr.permissions);
+        let dm = self.attr.dm;
+        let global = self.attr.global;
+        let name_localizations = localizations_tokens(self.attr.name_en.as_ref());
+        let description_localizations = localizations_tokens(self.attr.description_en.as_ref());
         Some(
             quote! {
                 cddio_core::declarative::Command {
                     name: #name,
                     description: #description,
+                    name_localizations: #name_localizations,
+                    description_localizations: #description_localizations,
                     args: &[
                         #(#arguments),*
                     ],
+                    permissions: #permissions,
+                    dm: #dm,
+                    global: #global,
                 }
             }
         )
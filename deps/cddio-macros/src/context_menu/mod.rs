@@ -0,0 +1,210 @@
+use quote::ToTokens;
+use syn::spanned::Spanned;
+use std::fmt;
+use quote::quote;
+use proc_macro2 as pm2;
+use crate::util::{MacroArgs, ParenValue, parse_permissions, permissions_tokens, localizations_tokens, check_name};
+
+use crate::function::Function;
+
+/// Type de menu contextuel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextMenuKind {
+    /// Clic droit sur un utilisateur
+    User,
+    /// Clic droit sur un message
+    Message,
+}
+
+impl ContextMenuKind {
+    fn from_attr(attr: &syn::Attribute) -> syn::Result<Self> {
+        if attr.path.is_ident("user_command") {
+            Ok(ContextMenuKind::User)
+        } else if attr.path.is_ident("message_command") {
+            Ok(ContextMenuKind::Message)
+        } else {
+            Err(syn::Error::new_spanned(&attr.path, "user_command ou message_command attendu."))
+        }
+    }
+    fn command_type(&self) -> pm2::TokenStream {
+        match self {
+            ContextMenuKind::User => quote!(serenity::model::application::command::CommandType::User),
+            ContextMenuKind::Message => quote!(serenity::model::application::command::CommandType::Message),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ContextMenuAttribute {
+    pub name: String,
+    pub name_en: Option<String>,
+    pub permissions: Vec<syn::Ident>,
+    pub dm: bool,
+    pub global: bool,
+}
+
+impl ContextMenuAttribute {
+    fn from_attr(attr: syn::Attribute) -> syn::Result<Self> {
+        use syn::*;
+        let attr_span = attr.span();
+        let mut result = ContextMenuAttribute {
+            name: String::new(),
+            name_en: None,
+            permissions: Vec::new(),
+            dm: true,
+            global: false,
+        };
+        let args = parse2::<ParenValue<MacroArgs>>(attr.tokens)?;
+        for arg in args.value.args.into_iter() {
+            match (arg.name.to_string().as_str(), arg.value.into_lit()?) {
+                ("name", Lit::Str(s)) => result.name = s.value(),
+                ("name_en", Lit::Str(s)) => result.name_en = Some(check_name(&s, false)?),
+                ("permissions", Lit::Str(s)) => result.permissions = parse_permissions(&s)?,
+                ("dm", Lit::Bool(b)) => result.dm = b.value,
+                ("global", Lit::Bool(b)) => result.global = b.value,
+                ("name"|"name_en"|"permissions", v) => return Err(syn::Error::new_spanned(v, "String literal attendu")),
+                ("dm"|"global", v) => return Err(syn::Error::new_spanned(v, "Booléen attendu")),
+                _ => return Err(Error::new_spanned(arg.name, "Argument inconnu.")),
+            }
+        }
+        if result.name.is_empty() {
+            return Err(Error::new(attr_span, "missing name argument"));
+        }
+        if result.name.chars().count() > 32 {
+            return Err(Error::new(attr_span, "Le nom d'un menu contextuel ne doit pas dépasser 32 caractères."));
+        }
+        Ok(result)
+    }
+}
+
+/// Paramètre d'une fonction de menu contextuel
+enum ContextMenuParameter {
+    /// Utilisateur ou message ciblé. `by_ref` indique si la fonction attend une référence.
+    Target {
+        by_ref: bool,
+    },
+    Context,
+    AppCommand,
+    SelfArg,
+}
+
+impl ContextMenuParameter {
+    fn new(arg: &syn::FnArg, kind: ContextMenuKind) -> syn::Result<Self> {
+        use syn::*;
+        let arg = match arg {
+            FnArg::Receiver(_) => return Ok(ContextMenuParameter::SelfArg),
+            FnArg::Typed(arg) => arg,
+        };
+        let (ty, by_ref) = match arg.ty.as_ref() {
+            Type::Path(TypePath { path, .. }) => (path, false),
+            Type::Reference(TypeReference { elem, .. }) => match elem.as_ref() {
+                Type::Path(TypePath { path, .. }) => (path, true),
+                _ => return Err(Error::new_spanned(&arg.ty, "Type d'argument innatendu."))
+            },
+            _ => return Err(Error::new_spanned(&arg.ty, "Type d'argument innatendu."))
+        };
+        let ty_last = match ty.segments.last() {
+            Some(segment) => segment,
+            None => return Err(Error::new_spanned(ty, "Type incomplet."))
+        };
+        match (ty_last.ident.to_string().as_str(), kind) {
+            ("Context", _) => Ok(ContextMenuParameter::Context),
+            ("ApplicationCommandEmbed", _) => Ok(ContextMenuParameter::AppCommand),
+            ("User", ContextMenuKind::User) | ("Message", ContextMenuKind::Message) => Ok(ContextMenuParameter::Target { by_ref }),
+            (_, ContextMenuKind::User) => Err(Error::new_spanned(ty, "Un menu contextuel utilisateur attend un argument de type User.")),
+            (_, ContextMenuKind::Message) => Err(Error::new_spanned(ty, "Un menu contextuel message attend un argument de type Message.")),
+        }
+    }
+}
+
+pub struct ContextMenu {
+    pub attr: ContextMenuAttribute,
+    pub kind: ContextMenuKind,
+    impl_fn: syn::ImplItemMethod,
+    params: Vec<ContextMenuParameter>,
+}
+
+impl ContextMenu {
+    pub fn new(attr: syn::Attribute, impl_fn: syn::ImplItemMethod) -> syn::Result<Self> {
+        let kind = ContextMenuKind::from_attr(&attr)?;
+        let attr = ContextMenuAttribute::from_attr(attr)?;
+        let params = impl_fn.sig.inputs.iter().map(|arg| ContextMenuParameter::new(arg, kind)).collect::<syn::Result<Vec<_>>>()?;
+        Ok(ContextMenu {
+            attr,
+            kind,
+            impl_fn,
+            params,
+        })
+    }
+    pub fn get_declarative(&self) -> pm2::TokenStream {
+        let name = &self.attr.name;
+        let kind = self.kind.command_type();
+        let permissions = permissions_tokens(&self.attr.permissions);
+        let dm = self.attr.dm;
+        let global = self.attr.global;
+        let name_localizations = localizations_tokens(self.attr.name_en.as_ref());
+        quote! {
+            cddio_core::declarative::ContextMenu {
+                name: #name,
+                name_localizations: #name_localizations,
+                kind: #kind,
+                permissions: #permissions,
+                dm: #dm,
+                global: #global,
+            }
+        }
+    }
+}
+
+impl Function for ContextMenu {
+    fn name(&self) -> pm2::TokenStream {
+        let name = &self.impl_fn.sig.ident;
+        quote! { #name }
+    }
+
+    fn event_handle(&self) -> syn::Result<pm2::TokenStream> {
+        let func_name = self.name();
+        let name = &self.attr.name;
+        let kind = self.kind.command_type();
+        let permissions = permissions_tokens(&self.attr.permissions);
+        let dm = self.attr.dm;
+        let target = match self.kind {
+            ContextMenuKind::User => quote!(serenity::model::application::interaction::application_command::ResolvedTarget::User(target, _)),
+            ContextMenuKind::Message => quote!(serenity::model::application::interaction::application_command::ResolvedTarget::Message(target)),
+        };
+        let call = self.params.iter().filter_map(|param| match param {
+            ContextMenuParameter::Target { by_ref: true } => Some(quote! { &target }),
+            ContextMenuParameter::Target { by_ref: false } => Some(quote! { target }),
+            ContextMenuParameter::Context => Some(quote! { ctx }),
+            ContextMenuParameter::AppCommand => Some(quote! { app_command }),
+            ContextMenuParameter::SelfArg => None,
+        });
+        let target_value = match self.kind {
+            ContextMenuKind::User => quote!(target),
+            ContextMenuKind::Message => quote!(*target),
+        };
+        Ok(quote! {
+            #name if orig_app_command.data.kind == #kind => if app_command.check_permissions(ctx, #permissions, #dm).await {
+                if let Some(#target) = orig_app_command.data.target() {
+                    let target = #target_value;
+                    self.#func_name(#(#call),*).await
+                }
+            }
+        })
+    }
+}
+
+impl ToTokens for ContextMenu {
+    fn to_tokens(&self, tokens: &mut pm2::TokenStream) {
+        self.impl_fn.to_tokens(tokens);
+    }
+}
+
+impl fmt::Debug for ContextMenu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContextMenu")
+            .field("name", &self.attr.name)
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
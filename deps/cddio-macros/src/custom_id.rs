@@ -0,0 +1,130 @@
+//! custom_id paramétré, partagé par les composants de message et les formulaires.
+
+use std::fmt;
+use quote::{quote, format_ident};
+use proc_macro2 as pm2;
+
+/// custom_id découpé en textes fixes et en captures.
+///
+/// `literals` contient une entrée de plus que `captures` : le texte
+/// précédant chaque capture, puis le texte final.
+#[derive(Debug, Clone, Default)]
+pub struct CustomIdPattern {
+    literals: Vec<String>,
+    captures: Vec<String>,
+}
+
+impl CustomIdPattern {
+    pub fn parse(lit: &syn::LitStr) -> syn::Result<Self> {
+        let value = lit.value();
+        let mut result = CustomIdPattern {
+            literals: vec![String::new()],
+            captures: Vec::new(),
+        };
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' => {
+                    if result.literals.last().is_some_and(|lit| lit.is_empty()) && !result.captures.is_empty() {
+                        return Err(syn::Error::new_spanned(lit, "Deux captures doivent être séparées par un texte fixe."));
+                    }
+                    let name: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                    if syn::parse_str::<syn::Ident>(&name).is_err() {
+                        return Err(syn::Error::new_spanned(lit, format!("Nom de capture invalide : \"{}\".", name)));
+                    }
+                    if result.captures.contains(&name) {
+                        return Err(syn::Error::new_spanned(lit, format!("Capture \"{}\" déclarée plusieurs fois.", name)));
+                    }
+                    result.captures.push(name);
+                    result.literals.push(String::new());
+                },
+                '}' => return Err(syn::Error::new_spanned(lit, "Accolade fermante sans accolade ouvrante.")),
+                c => result.literals.last_mut().unwrap().push(c),
+            }
+        }
+        if !result.captures.is_empty() && result.literals[0].is_empty() {
+            return Err(syn::Error::new_spanned(lit, "Un custom_id paramétré doit commencer par un texte fixe."));
+        }
+        Ok(result)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.captures.is_empty() && self.literals[0].is_empty()
+    }
+    pub fn captures(&self) -> &[String] {
+        &self.captures
+    }
+    /// Vérifie que chaque capture correspond à un paramètre de la fonction
+    /// et retourne les paramètres dans l'ordre des captures.
+    pub fn capture_params<'a>(&self, span: pm2::Span, params: &[(&'a syn::Ident, &'a syn::Type)]) -> syn::Result<Vec<(&'a syn::Ident, &'a syn::Type)>> {
+        self.captures.iter().map(|capture| {
+            params.iter()
+                .find(|(var_name, _)| *var_name == capture)
+                .copied()
+                .ok_or_else(|| syn::Error::new(span, format!("Aucun paramètre de la fonction ne correspond à la capture \"{}\".", capture)))
+        }).collect()
+    }
+    /// Fonction associée construisant le custom_id à partir des valeurs capturées.
+    ///
+    /// Elle est nommée d'après la fonction, suffixée de `_custom_id`.
+    pub fn builder(&self, fn_name: &syn::Ident, captures: &[(&syn::Ident, &syn::Type)]) -> pm2::TokenStream {
+        let builder_name = format_ident!("{}_custom_id", fn_name);
+        let doc = format!("custom_id de `{}` : `{}`", fn_name, self);
+        let params = captures.iter().map(|(var_name, ty)| quote! { #var_name: &#ty });
+        let first = &self.literals[0];
+        let pushes = captures.iter().zip(self.literals.iter().skip(1)).map(|((var_name, _), literal)| {
+            quote! {
+                custom_id.push_str(&#var_name.to_string());
+                custom_id.push_str(#literal);
+            }
+        });
+        quote! {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            pub fn #builder_name(#(#params),*) -> String {
+                let mut custom_id = String::from(#first);
+                #(#pushes)*
+                custom_id
+            }
+        }
+    }
+    /// Garde vérifiant que `custom_id` correspond au motif, et décodage des captures.
+    ///
+    /// Le motif complet est vérifié dans la garde pour laisser la main aux fonctions suivantes.
+    /// Une capture qui ne peut pas être convertie est journalisée et la fonction n'est pas appelée.
+    pub fn matcher(&self, custom_id: pm2::TokenStream, captures: &[(&syn::Ident, &syn::Type)]) -> (pm2::TokenStream, pm2::TokenStream) {
+        if self.captures.is_empty() {
+            let literal = &self.literals[0];
+            return (quote! { #custom_id == #literal }, quote! {});
+        }
+        let literals = &self.literals;
+        let pattern_str = self.to_string();
+        let decode = captures.iter().enumerate().map(|(i, (var_name, ty))| {
+            let capture = var_name.to_string();
+            quote! {
+                let #var_name = match <#ty as std::str::FromStr>::from_str(captures[#i]) {
+                    Ok(v) => v,
+                    Err(_) => {
+                        eprintln!("Malformed custom_id \"{}\": cannot parse {} (pattern \"{}\")", #custom_id, #capture, #pattern_str);
+                        return;
+                    }
+                };
+            }
+        });
+        let guard = quote! { cddio_core::custom_id::split(&#custom_id, &[#(#literals),*]).is_some() };
+        let decode = quote! {
+            let captures = cddio_core::custom_id::split(&#custom_id, &[#(#literals),*]).unwrap_or_default();
+            #(#decode)*
+        };
+        (guard, decode)
+    }
+}
+
+impl fmt::Display for CustomIdPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.literals[0])?;
+        for (capture, literal) in self.captures.iter().zip(self.literals.iter().skip(1)) {
+            write!(f, "{{{}}}{}", capture, literal)?;
+        }
+        Ok(())
+    }
+}
@@ -7,6 +7,8 @@ use super::util::*;
 use super::command::Command;
 use super::event::Event;
 use super::message_component::Interaction;
+use super::modal::Modal;
+use super::context_menu::ContextMenu;
 
 pub trait Function : ToTokens + std::fmt::Debug {
     fn name(&self) -> pm2::TokenStream;
@@ -32,6 +34,7 @@ impl ToTokens for NoSpecial {
 #[derive(Debug)]
 pub enum FunctionType {
     Command(Command),
+    ContextMenu(ContextMenu),
     Event(Box<dyn Function>),
     NoSpecial(NoSpecial),
 }
@@ -42,6 +45,7 @@ impl Function for FunctionType {
     fn name(&self) -> pm2::TokenStream {
         match self {
             FunctionType::Command(c) => c.name(),
+            FunctionType::ContextMenu(c) => c.name(),
             FunctionType::Event(e) => e.name(),
             FunctionType::NoSpecial(n) => n.name(),
         }
@@ -49,6 +53,7 @@ impl Function for FunctionType {
     fn event_handle(&self) -> syn::Result<pm2::TokenStream> {
         match self {
             FunctionType::Command(c) => c.event_handle(),
+            FunctionType::ContextMenu(c) => c.event_handle(),
             FunctionType::Event(e) => e.event_handle(),
             FunctionType::NoSpecial(n) => n.event_handle(),
         }
@@ -58,6 +63,7 @@ impl ToTokens for FunctionType {
     fn to_tokens(&self, tokens: &mut pm2::TokenStream) {
         match self {
             FunctionType::Command(c) => c.to_tokens(tokens),
+            FunctionType::ContextMenu(c) => c.to_tokens(tokens),
             FunctionType::Event(e) => e.to_tokens(tokens),
             FunctionType::NoSpecial(n) => n.to_tokens(tokens),
         }
@@ -93,6 +99,9 @@ macro_rules! to_event {
     ($evt:ident, _result => Command) => {
         $evt
     };
+    ($evt:ident, _result => ContextMenu) => {
+        $evt
+    };
     ($evt:ident, _result => Event) => {
         Box::new($evt)
     };
@@ -103,8 +112,11 @@ impl FunctionType {
     pub fn new(mut impl_fn: syn::ImplItemMethod) -> syn::Result<Self> {
         to_event!(impl_fn, 
             ("command", Command, Command),
+            ("user_command", ContextMenu, ContextMenu),
+            ("message_command", ContextMenu, ContextMenu),
             ("event", Event, Event),
-            ("message_component", Interaction, Event)
+            ("message_component", Interaction, Event),
+            ("modal", Modal, Event)
         )
     }
     pub fn new_rc(impl_fn: syn::ImplItemMethod) -> syn::Result<RefFunction> {
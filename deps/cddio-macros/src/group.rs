@@ -6,11 +6,30 @@ use proc_macro2 as pm2;
 
 use crate::util::*;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct GroupAttribute {
     name: String,
     description: String,
-    parent: Option<String>
+    name_en: Option<String>,
+    description_en: Option<String>,
+    parent: Option<String>,
+    permissions: Vec<syn::Ident>,
+    dm: bool,
+    global: bool,
+}
+impl Default for GroupAttribute {
+    fn default() -> Self {
+        GroupAttribute {
+            name: String::new(),
+            description: String::new(),
+            name_en: None,
+            description_en: None,
+            parent: None,
+            permissions: Vec::new(),
+            dm: true,
+            global: false,
+        }
+    }
 }
 impl GroupAttribute {
     fn from_attr(attr: syn::Attribute) -> syn::Result<Self> {
@@ -19,11 +38,17 @@ impl GroupAttribute {
         let arg_span = attr.span();
         let args = parse2::<ParenValue<MacroArgs>>(attr.tokens)?;
         for arg in args.value.args.into_iter() {
-            match (arg.name.to_string().as_str(), arg.value) {
+            match (arg.name.to_string().as_str(), arg.value.into_lit()?) {
                 ("name", Lit::Str(s)) => result.name = s.value(),
                 ("description", Lit::Str(s)) => result.description = s.value(),
+                ("name_en", Lit::Str(s)) => result.name_en = Some(check_name(&s, true)?),
+                ("description_en", Lit::Str(s)) => result.description_en = Some(s.value()),
                 ("parent", Lit::Str(s)) => result.parent = Some(s.value()),
-                ("name"|"description"|"parent", v) => return Err(syn::Error::new_spanned(v, "String literal attendu")),
+                ("permissions", Lit::Str(s)) => result.permissions = parse_permissions(&s)?,
+                ("dm", Lit::Bool(b)) => result.dm = b.value,
+                ("global", Lit::Bool(b)) => result.global = b.value,
+                ("name"|"description"|"name_en"|"description_en"|"parent"|"permissions", v) => return Err(syn::Error::new_spanned(v, "String literal attendu")),
+                ("dm"|"global", v) => return Err(syn::Error::new_spanned(v, "Booléen attendu")),
                 _ => return Err(Error::new_spanned(arg.name, "Argument inconnu.")),
             }
         }
@@ -33,6 +58,9 @@ impl GroupAttribute {
         if result.name.is_empty() {
             return Err(Error::new(arg_span, "missing name argument"));
         }
+        if result.global && result.parent.is_some() {
+            return Err(Error::new(arg_span, "Un sous-groupe est enregistré avec son groupe racine, global n'est pas autorisé."));
+        }
         Ok(result)
     }
 }
@@ -41,6 +69,7 @@ pub struct Group {
     attr: Option<GroupAttribute>,
     children: Vec<RefGroup>,
     functions: Vec<RefFunction>,
+    context_menus: Vec<RefFunction>,
     parent: Option<RefGroup>
 }
 type RefGroup = Rc<RefCell<Group>>;
@@ -51,12 +80,16 @@ impl Group {
             attr: Some(attr),
             children: Vec::new(),
             functions: Vec::new(),
+            context_menus: Vec::new(),
             parent
         }))
     }
     pub fn add_function(&mut self, function: RefFunction) {
         self.functions.push(function);
     }
+    pub fn add_context_menu(&mut self, function: RefFunction) {
+        self.context_menus.push(function);
+    }
     pub fn get_fullname(&self) -> String {
         let mut result = self.attr.as_ref().unwrap().name.clone();
         if let Some(parent) = self.parent.as_ref() {
@@ -64,6 +97,22 @@ impl Group {
         }
         result
     }
+    /// Permissions requises par le groupe et ses parents.
+    pub fn get_permissions(&self) -> Vec<syn::Ident> {
+        let mut result = match self.parent.as_ref() {
+            Some(parent) => parent.borrow().get_permissions(),
+            None => Vec::new(),
+        };
+        if let Some(attr) = self.attr.as_ref() {
+            result.extend(attr.permissions.iter().cloned());
+        }
+        result
+    }
+    /// Indique si le groupe et ses parents sont disponibles en message privé.
+    pub fn get_dm(&self) -> bool {
+        let dm = self.attr.as_ref().is_none_or(|attr| attr.dm);
+        dm && self.parent.as_ref().is_none_or(|parent| parent.borrow().get_dm())
+    }
     pub fn get_declarative(&self) -> pm2::TokenStream {
         let it_commands = self.functions.iter().map(|f| {
             let f_borrow = f.borrow();
@@ -73,21 +122,39 @@ impl Group {
             }
         });
         let it_children = self.children.iter().map(|f| f.borrow().get_declarative());
+        let it_context_menus = self.context_menus.iter().map(|f| {
+            let f_borrow = f.borrow();
+            match &*f_borrow {
+                FunctionType::ContextMenu(c) => c.get_declarative(),
+                _ => unreachable!()
+            }
+        });
         let node = quote! {
             cddio_core::declarative::Node {
                 commands: &[#(#it_commands), *],
-                children: &[#(#it_children), *]
+                children: &[#(#it_children), *],
+                context_menus: &[#(#it_context_menus), *],
             }
         };
 
         if let Some(attr) = &self.attr {
             let name = &attr.name;
             let description = &attr.description;
+            let permissions = permissions_tokens(&attr.permissions);
+            let dm = attr.dm;
+            let global = attr.global;
+            let name_localizations = localizations_tokens(attr.name_en.as_ref());
+            let description_localizations = localizations_tokens(attr.description_en.as_ref());
             quote!(
                 cddio_core::declarative::ChildNode {
                     name: #name,
                     description: #description,
-                    node: #node
+                    name_localizations: #name_localizations,
+                    description_localizations: #description_localizations,
+                    node: #node,
+                    permissions: #permissions,
+                    dm: #dm,
+                    global: #global,
                 }
             )
         } else {
@@ -101,6 +168,7 @@ impl Default for Group {
             attr: None,
             children: Vec::new(),
             functions: Vec::new(),
+            context_menus: Vec::new(),
             parent: None
         }
     }
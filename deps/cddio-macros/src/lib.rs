@@ -63,6 +63,8 @@ Cette crate ne possède que des macros de type attribut à attacher à des symbo
 
 Sauf dans le cas de l'attribut event, les arguments prennent la forme suivante: `nom_argument_1="valeur 1", nom_argument2="valeur 2", ...`. L'ordre des arguments n'importe pas.
 
+Les arguments *name_en* et *description_en* renseignent les traductions anglaises (`en-US` et `en-GB`) affichées par Discord. Les commandes reçues gardent leur nom français. Les noms traduits sont vérifiés à la compilation : 1 à 32 caractères, en minuscules et sans espace, sauf pour les menus contextuels qui acceptent espaces et majuscules. Pour traduire les réponses, `lang()` de `ApplicationCommandEmbed`, `AutocompleteEmbed` et `ModalSubmitEmbed` retourne la langue du client de l'utilisateur, utilisable avec `Lang::pick` et les fonctions `message::error_lang`, `success_lang`, etc. de cddio-core.
+
 ### `#[component]`
 
 Déclare une implémentation de structure en composant.
@@ -89,7 +91,14 @@ Par exemple pour créer la commande `ticket create`, *ticket* est un groupe de c
 |:-|:-:|:-|
 |*name*| |Nom du groupe|
 |*description*| |Description du groupe|
+|*name_en*|x|Nom du groupe pour les clients Discord en anglais|
+|*description_en*|x|Description du groupe pour les clients Discord en anglais|
 |*parent*|x|Nom du groupe sur lequel s'associer|
+|*permissions*|x|Permissions requises pour les commandes du groupe, par exemple `"MANAGE_CHANNELS \| MANAGE_ROLES"`|
+|*dm*|x|`false` pour interdire les commandes du groupe en message privé (`true` par défaut)|
+|*global*|x|`true` pour enregistrer le groupe globalement plutôt que sur chaque serveur (`false` par défaut). Réservé aux groupes sans parent|
+
+Les permissions d'un groupe s'ajoutent à celles de ses parents et de ses commandes. Discord ne permet de les déclarer (`default_member_permissions`) que sur les groupes racines, elles sont donc vérifiées à nouveau à chaque appel de commande.
 
 ```rust
 struct MyStruct;
@@ -119,7 +128,19 @@ Le nom de la commande ne doit pas contenir de caractère blanc. Une commande dé
 |:-|:-:|:-|
 |*name*|x|Nom de la commande. Utilise le nom de la fonction rust si non renseigné|
 |*description*| |Description de la commande|
+|*name_en*|x|Nom de la commande pour les clients Discord en anglais|
+|*description_en*|x|Description de la commande pour les clients Discord en anglais|
 |*group*|x|Nom du groupe sur lequel s'associer|
+|*permissions*|x|Permissions requises, noms des constantes de `serenity::model::permissions::Permissions` séparés par `\|`|
+|*dm*|x|`false` pour interdire la commande en message privé (`true` par défaut)|
+|*global*|x|`true` pour enregistrer la commande globalement plutôt que sur chaque serveur (`false` par défaut). Réservé aux commandes sans groupe|
+
+Si l'auteur de la commande n'a pas les permissions requises, un message d'erreur lui est renvoyé et la fonction n'est pas appelée.
+
+```rust
+#[command(permissions="BAN_MEMBERS", dm=false, description="Bannir un membre")]
+async fn ban(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>) {}
+```
 
 Voir l'exemple d'une commande dans l'attribut [group](#group)
 
@@ -133,6 +154,14 @@ Le nom de l'argument ne doit pas contenir de caractère blanc. Parce que la desc
 |:-|:-:|:-|
 |*name*|x|Nom de l'argument. Utilise le nom de la variable si non renseigné|
 |*description*| |Description de l'argument|
+|*name_en*|x|Nom de l'argument pour les clients Discord en anglais|
+|*description_en*|x|Description de l'argument pour les clients Discord en anglais|
+|*autocomplete*|x|Nom de la méthode du composant proposant des valeurs pendant la saisie|
+|*choices*|x|Liste des valeurs autorisées, par exemple `["jour", "semaine"]` ou `[1, 7, 30]`. Uniquement pour les arguments String ou entiers|
+|*min*, *max*|x|Valeurs minimale et maximale d'un nombre|
+|*min_length*, *max_length*|x|Longueurs minimale et maximale d'une String|
+|*channel_types*|x|Types de salon acceptés, par exemple `[text, category]`. Voir `serenity::model::channel::ChannelType` (en snake_case)|
+|*enum*|x|`true` si le type est une enum dérivant `CommandChoices`|
 
 Le type du paramètre de fonction est restreint à ce que peut recevoir une commande Discord. Voici la liste des types supportés : 
 
@@ -145,7 +174,9 @@ Le type du paramètre de fonction est restreint à ce que peut recevoir une comm
 |User*, UserId*|User|Un utilisateur|
 |Role*, RoleId*|Role|Un role|
 |PartialChannel, ChannelId|Channel|Un salon (peut etre textuel, vocal, catégorie, stage ou fil)|
+|Attachment*|Attachment|Un fichier envoyé avec la commande|
 |Mentionable**|Mentionable|Peut être un utilisateur ou un role|
+|Enum avec `#[derive(CommandChoices)]` et `enum=true`|String|Une valeur parmi les variantes de l'enum|
 
 *: Type disponible dans la crate serenity
 
@@ -153,8 +184,56 @@ Le type du paramètre de fonction est restreint à ce que peut recevoir une comm
 
 Si l'argument de la commande discord doit être optionnel, encapsulez l'un des types au dessus dans un std::Option<...> 
 
+Le contenu d'un argument `Attachment` n'est pas téléchargé automatiquement. Utilisez `cddio_core::attachment::download` (ou `download_text`) qui refuse les fichiers dépassant la taille indiquée et interrompt le téléchargement dès qu'elle est dépassée.
+
+Les contraintes *min*, *max*, *min_length*, *max_length* et *channel_types* sont envoyées à Discord puis vérifiées à nouveau à la réception de la commande. Si un argument ne les respecte pas, un message d'erreur est renvoyé et la fonction n'est pas appelée.
+
+```rust
+#[command(description="Supprime des messages")]
+async fn purge(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
+    #[argument(description="Nombre de messages", min=1, max=100)]
+    nombre: u8,
+    #[argument(description="Salon à nettoyer", channel_types=[text, news])]
+    salon: Option<ChannelId>
+) {}
+```
+
 Voir l'exemple d'un argument commande dans l'attribut [group](#group)
 
+Avec *autocomplete*, Discord interroge le bot pendant que l'utilisateur saisit l'argument. La méthode indiquée est appelée avec le contexte et un `AutocompleteEmbed<'_>` de cddio-core, qui donne accès au texte saisi via `value()`. Elle retourne la liste des propositions ; seules les 25 premières sont envoyées à Discord. Les propositions sont des chaînes de caractères, l'autocomplétion est donc réservée aux arguments de type `String`, et ne peut pas être combinée à *choices* : tout autre usage est refusé à la compilation. Les permissions et la disponibilité en message privé de la commande sont vérifiées avant d'appeler la méthode ; si l'utilisateur n'y a pas accès, aucune proposition n'est envoyée.
+
+```rust
+#[command(description="Affiche une catégorie")]
+async fn categorie(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
+    #[argument(description="Nom de la catégorie", autocomplete="complete_category")]
+    nom: String
+) {}
+
+async fn complete_category(&self, ctx: &Context, autocomplete: &AutocompleteEmbed<'_>) -> Vec<String> {
+    vec!["Support".into(), "Partenariat".into()]
+}
+```
+
+### `#[derive(CommandChoices)]`
+
+Permet d'utiliser une enum comme type d'argument de commande. Discord affiche alors une liste déroulante avec une entrée par variante. L'enum ne doit contenir que des variantes sans champ (25 au maximum) et doit implémenter `Clone`. Le nom affiché est celui de la variante, ou celui donné par l'attribut `#[choice(name="...")]`. L'argument doit être marqué par `enum=true` ; sans ce marqueur, un type inconnu est refusé.
+
+```rust
+#[derive(Clone, CommandChoices)]
+enum Unite {
+    #[choice(name="Jours")]
+    Jour,
+    #[choice(name="Semaines")]
+    Semaine,
+}
+
+#[command(description="Statistiques sur une période")]
+async fn stats(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
+    #[argument(description="Unité de la période", enum=true)]
+    unite: Unite
+) {}
+```
+
 ### `#[event()]`
 
 Déclare un événement Discord.
@@ -208,6 +287,78 @@ use serenity::model::{
 #[event(InteractionCreate(InteractionCreateEvent{interaction: MessageComponent(message_interaction), ..}) if message_interaction.data.custom_id == "button_ticket_close")]
 async fn on_button_ticket_close(&self, ctx: &Context, message_interaction: &MessageComponentInteraction) 
 {}
+```
+
+#### custom_id paramétré
+
+Le custom_id peut contenir des captures entre accolades, par exemple `appeal:{case_id}`. Chaque capture est passée au paramètre de la fonction du même nom, après conversion avec `FromStr`. Le custom_id doit commencer par un texte fixe et deux captures doivent être séparées par un texte fixe. Un custom_id qui ne correspond pas au motif est laissé aux fonctions suivantes, même s'il commence par le même texte fixe. Un custom_id dont une capture ne peut pas être convertie est journalisé et la fonction n'est pas appelée.
+
+Pour chaque fonction, une fonction associée `<nom>_custom_id` construit le custom_id à partir des captures. Le producteur et le consommateur partagent ainsi le même motif.
+
+```rust
+#[message_component(custom_id="button_ticket_rating:{channel_id}:{rating}")]
+async fn on_button_ticket_rating(&self, ctx: &Context, component: &MessageComponentInteraction, channel_id: u64, rating: i32)
+{}
+
+button.custom_id(Self::on_button_ticket_rating_custom_id(&channel_id.0, &rating));
+```
+
+### `#[modal()]`
+
+Déclare la réception d'un formulaire (*modal*) dont le `custom_id` est celui indiqué.
+
+|argument|optionnel|description|
+|:-|:-:|:-|
+|*custom_id*| |custom_id du formulaire, éventuellement paramétré|
+
+Comme pour les arguments d'une commande, les paramètres de la fonction sont lus dans les champs texte du formulaire. Le nom du champ est celui de la variable, ou celui donné par l'attribut `#[field(name="...")]`. Un paramètre `String` est obligatoire : si le champ est absent, un message d'erreur est renvoyé et la fonction n'est pas appelée. Un paramètre `Option<String>` vaut `None` si le champ est absent ou vide. Le contexte et un `ModalSubmitEmbed<'_>` de cddio-core peuvent aussi être demandés ; ce dernier permet de répondre avec `delayed_response` ou `direct_response`.
+
+Le formulaire s'envoie avec le constructeur `cddio_core::message::Modal` :
+
+```rust
+let mut modal = message::Modal::new(Self::on_modal_close_custom_id(), "Fermer le ticket");
+modal.add_input("raison", "Raison de la fermeture", |input| input.required(false));
+msg.create_interaction_response(ctx, |resp| {
+    *resp = modal.into();
+    resp
+}).await?;
+
+#[modal(custom_id="modal_ticket_close")]
+async fn on_modal_close(&self, ctx: &Context, modal: ModalSubmitEmbed<'_>,
+    #[field(name="raison")]
+    reason: Option<String>
+) {}
+```
+
+Le custom_id peut contenir des captures, comme pour un [custom_id paramétré](#custom_id-paramétré) de `#[message_component]` : chaque capture est passée au paramètre du même nom, et la fonction associée `<nom>_custom_id` construit le custom_id du formulaire.
+
+```rust
+let modal = message::Modal::new(Self::on_modal_rating_custom_id(&channel_id.0), "Merci pour ta note !");
+
+#[modal(custom_id="modal_ticket_rating:{channel_id}")]
+async fn on_modal_rating(&self, ctx: &Context, modal: ModalSubmitEmbed<'_>, channel_id: u64, comment: Option<String>) {}
+```
+
+### `#[user_command()]` et `#[message_command()]`
+
+Déclare une commande de menu contextuel, affichée lors d'un clic droit sur un utilisateur (`user_command`) ou sur un message (`message_command`).
+
+|argument|optionnel|description|
+|:-|:-:|:-|
+|*name*| |Nom affiché dans le menu (32 caractères maximum, espaces et majuscules autorisés)|
+|*name_en*|x|Nom affiché aux clients Discord en anglais|
+|*permissions*|x|Permissions requises, comme pour [command](#command)|
+|*dm*|x|Disponible en message privé (`true` par défaut)|
+|*global*|x|Enregistrée globalement plutôt que sur chaque serveur (`false` par défaut)|
+
+Ces commandes n'ont ni description ni argument. La fonction peut demander le contexte, un `ApplicationCommandEmbed<'_>` pour répondre, et la cible du clic droit : un `User` pour `user_command`, un `Message` pour `message_command`, par valeur ou par référence.
+
+```rust
+#[message_command(name="Purger l'auteur", permissions="BAN_MEMBERS", dm=false)]
+async fn menu_purge(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>, msg: &Message) {}
+
+#[user_command(name="Sanctionner", permissions="MODERATE_MEMBERS", dm=false)]
+async fn menu_sanction(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>, user: &User) {}
 ```
  */
 
@@ -215,10 +366,14 @@ mod function;
 mod command;
 mod event;
 mod message_component;
+mod custom_id;
+mod modal;
+mod context_menu;
 
 mod util;
 mod log;
 mod group;
+mod choices;
 
 use quote::quote;
 use proc_macro::TokenStream;
@@ -231,6 +386,11 @@ pub fn component(_attr: TokenStream, item: TokenStream) -> TokenStream {
     expand_commands(item.into()).unwrap_or_else(syn::Error::into_compile_error).into()
 }
 
+#[proc_macro_derive(CommandChoices, attributes(choice))]
+pub fn command_choices(item: TokenStream) -> TokenStream {
+    choices::expand_command_choices(item.into()).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
 enum MyImplItem {
     Function(RefFunction),
     Other(syn::ImplItem),
@@ -270,6 +430,7 @@ fn expand_commands(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::
         });
     let mut events: Vec<proc_macro2::TokenStream> = vec![];
     let mut commands: Vec<proc_macro2::TokenStream> = vec![];
+    let mut autocompletes: Vec<proc_macro2::TokenStream> = vec![];
     let mut impl_items: Vec<proc_macro2::TokenStream> = vec![];
 
     for interf in interfs {
@@ -296,19 +457,41 @@ fn expand_commands(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::
                 impl_items.push(quote! {
                     #command
                 });
+                let mut permissions = command.attr.permissions.clone();
+                let mut dm = command.attr.dm;
                 let name = if let Some(grp) = &command.attr.group {
                     let group_found = match groups.find_group(&grp) {
                         Some(group) => group,
                         None => return Err(syn::Error::new_spanned(&grp, "Groupe introuvable."))
                     };
                     group_found.borrow_mut().add_function(Rc::clone(&func_rc));
+                    permissions.extend(group_found.borrow().get_permissions());
+                    dm &= group_found.borrow().get_dm();
                     format!("{}.{}",group_found.borrow().get_fullname(), name)
                 } else {
                     groups.root_mut().add_function(Rc::clone(&func_rc));
                     name
                 };
+                let permissions = util::permissions_tokens(&permissions);
+                for arg in command.args.iter() {
+                    if let (Some(arg_name), Some(method)) = (arg.get_name(), arg.get_autocomplete()) {
+                        autocompletes.push(quote! {
+                            (#name, Some(#arg_name)) if autocomplete.check_permissions(#permissions, #dm) => self.#method(ctx, &autocomplete).await,
+                            (#name, Some(#arg_name)) => Vec::new()
+                        });
+                    }
+                }
+                let arguments = command.args.iter().filter_map(|arg| arg.get_declarative());
                 commands.push(quote! {
-                    #name => {#event}
+                    #name if orig_app_command.data.kind == serenity::model::application::command::CommandType::ChatInput => if app_command.check_permissions(ctx, #permissions, #dm).await
+                        && app_command.check_arguments(ctx, &[#(#arguments),*]).await {#event}
+                });
+            },
+            FunctionType::ContextMenu(context_menu) => {
+                commands.push(context_menu.event_handle()?);
+                groups.root_mut().add_context_menu(Rc::clone(&func_rc));
+                impl_items.push(quote! {
+                    #context_menu
                 });
             },
             FunctionType::NoSpecial(v) => {
@@ -316,6 +499,22 @@ fn expand_commands(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::
             },
         }
     }
+    let autocomplete_event = if autocompletes.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            serenity::model::event::Event::InteractionCreate(serenity::model::event::InteractionCreateEvent{interaction: serenity::model::application::interaction::Interaction::Autocomplete(orig_autocomplete), ..}) => {
+                let autocomplete = cddio_core::AutocompleteEmbed::new(orig_autocomplete);
+                let choices: Vec<String> = match (autocomplete.fullname().as_str(), autocomplete.focused_name()) {
+                    #(#autocompletes,)*
+                    _ => return
+                };
+                if let Err(e) = autocomplete.respond(ctx, choices).await {
+                    eprintln!("Cannot create autocomplete response: {}", e);
+                }
+            },
+        }
+    };
     let impl_event = quote! {
         #[serenity::async_trait]
         impl cddio_core::ComponentEvent for #struct_name {
@@ -329,6 +528,7 @@ fn expand_commands(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::
                             _ => ()
                         }
                     },
+                    #autocomplete_event
                     #(#events,)*
                     _ => ()
                 }
@@ -2,17 +2,32 @@ use quote::ToTokens;
 use syn::spanned::Spanned;
 use std::fmt;
 use quote::quote;
+use crate::custom_id::CustomIdPattern;
 use crate::util::{MacroArgs, ParenValue};
 
 use crate::function::Function;
 
 #[derive(Debug, Clone, Default)]
 pub struct InteractionAttribute {
-    custom_id: String,
+    custom_id: CustomIdPattern,
 }
+
+/// Paramètre d'une fonction de composant de message
+enum InteractionParameter {
+    /// Valeur capturée dans le custom_id
+    Capture {
+        var_name: syn::Ident,
+        ty: Box<syn::Type>,
+    },
+    Context,
+    Interaction,
+    SelfArg,
+}
+
 pub struct Interaction {
     attr: InteractionAttribute,
     impl_fn: syn::ImplItemMethod,
+    params: Vec<InteractionParameter>,
 }
 
 impl InteractionAttribute {
@@ -22,8 +37,8 @@ impl InteractionAttribute {
         let mut result = Self::default();
         let args = parse2::<ParenValue<MacroArgs>>(attr.tokens)?;
         for arg in args.value.args.into_iter() {
-            match (arg.name.to_string().as_str(), arg.value) {
-                ("custom_id", Lit::Str(s)) => result.custom_id = s.value(),
+            match (arg.name.to_string().as_str(), arg.value.into_lit()?) {
+                ("custom_id", Lit::Str(s)) => result.custom_id = CustomIdPattern::parse(&s)?,
                 _ => return Err(Error::new_spanned(arg.name, "Argument inconnu ou mal typé.")),
             }
         }
@@ -34,13 +49,59 @@ impl InteractionAttribute {
     }
 }
 
+impl InteractionParameter {
+    fn new(arg: &syn::FnArg, pattern: &CustomIdPattern) -> syn::Result<Self> {
+        use syn::*;
+        let arg = match arg {
+            FnArg::Receiver(_) => return Ok(InteractionParameter::SelfArg),
+            FnArg::Typed(arg) => arg,
+        };
+        if let Pat::Ident(ident) = arg.pat.as_ref() {
+            if pattern.captures().contains(&ident.ident.to_string()) {
+                return Ok(InteractionParameter::Capture {
+                    var_name: ident.ident.clone(),
+                    ty: arg.ty.clone(),
+                });
+            }
+        }
+        let ty = match arg.ty.as_ref() {
+            Type::Reference(TypeReference { elem, .. }) => match elem.as_ref() {
+                Type::Path(TypePath { path, .. }) => path,
+                _ => return Err(Error::new_spanned(&arg.ty, "Type d'argument innatendu."))
+            },
+            _ => return Err(Error::new_spanned(&arg.ty, "Référence vers le contexte ou l'interaction attendue, ou nom d'une capture du custom_id."))
+        };
+        match ty.segments.last().map(|segment| segment.ident.to_string()).as_deref() {
+            Some("Context") => Ok(InteractionParameter::Context),
+            Some("MessageComponentInteraction") => Ok(InteractionParameter::Interaction),
+            _ => Err(Error::new_spanned(ty, "Référence vers le contexte ou l'interaction attendue, ou nom d'une capture du custom_id."))
+        }
+    }
+}
+
 impl Interaction {
     pub fn new(attr: syn::Attribute, impl_fn: syn::ImplItemMethod) -> syn::Result<Self> {
+        let attr_span = attr.span();
         let attr = InteractionAttribute::from_attr(attr)?;
-        Ok(Interaction {
+        let params = impl_fn.sig.inputs.iter().map(|arg| InteractionParameter::new(arg, &attr.custom_id)).collect::<syn::Result<Vec<_>>>()?;
+        let interaction = Interaction {
             attr,
             impl_fn,
-        })
+            params,
+        };
+        interaction.attr.custom_id.capture_params(attr_span, &interaction.captures())?;
+        Ok(interaction)
+    }
+    /// Paramètres de la fonction capturés dans le custom_id
+    fn captures(&self) -> Vec<(&syn::Ident, &syn::Type)> {
+        self.params.iter().filter_map(|param| match param {
+            InteractionParameter::Capture { var_name, ty } => Some((var_name, ty.as_ref())),
+            _ => None
+        }).collect()
+    }
+    fn capture_params(&self) -> Vec<(&syn::Ident, &syn::Type)> {
+        // Les captures ont été vérifiées à la création
+        self.attr.custom_id.capture_params(proc_macro2::Span::call_site(), &self.captures()).unwrap_or_default()
     }
 }
 
@@ -52,9 +113,18 @@ impl Function for Interaction {
 
     fn event_handle(&self) -> syn::Result<proc_macro2::TokenStream> {
         let func_name = self.name();
-        let custom_id = &self.attr.custom_id;
+        let call = self.params.iter().filter_map(|param| match param {
+            InteractionParameter::Capture { var_name, .. } => Some(quote! { #var_name }),
+            InteractionParameter::Context => Some(quote! { ctx }),
+            InteractionParameter::Interaction => Some(quote! { message_interaction }),
+            InteractionParameter::SelfArg => None,
+        });
+        let (guard, decode) = self.attr.custom_id.matcher(quote! { message_interaction.data.custom_id }, &self.capture_params());
         Ok(quote!{
-            serenity::model::event::Event::InteractionCreate(serenity::model::event::InteractionCreateEvent{interaction: serenity::model::application::interaction::Interaction::MessageComponent(message_interaction), ..}) if message_interaction.data.custom_id == #custom_id => self.#func_name(ctx, message_interaction).await
+            serenity::model::event::Event::InteractionCreate(serenity::model::event::InteractionCreateEvent{interaction: serenity::model::application::interaction::Interaction::MessageComponent(message_interaction), ..}) if #guard => {
+                #decode
+                self.#func_name(#(#call),*).await
+            }
         })
     }
 }
@@ -62,6 +132,7 @@ impl Function for Interaction {
 impl ToTokens for Interaction {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         self.impl_fn.to_tokens(tokens);
+        self.attr.custom_id.builder(&self.impl_fn.sig.ident, &self.capture_params()).to_tokens(tokens);
     }
 }
 
@@ -71,4 +142,4 @@ impl fmt::Debug for Interaction {
             .field("custom_id", &self.attr.custom_id)
             .finish()
     }
-}
\ No newline at end of file
+}
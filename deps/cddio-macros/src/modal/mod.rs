@@ -0,0 +1,206 @@
+use quote::ToTokens;
+use syn::spanned::Spanned;
+use std::fmt;
+use quote::quote;
+use proc_macro2 as pm2;
+use crate::custom_id::CustomIdPattern;
+use crate::util::{MacroArgs, ParenValue, FindAndPop};
+
+use crate::function::Function;
+
+#[derive(Debug, Clone, Default)]
+pub struct ModalAttribute {
+    custom_id: CustomIdPattern,
+}
+
+impl ModalAttribute {
+    fn from_attr(attr: syn::Attribute) -> syn::Result<Self> {
+        use syn::*;
+        let attr_span = attr.span();
+        let mut result = Self::default();
+        let args = parse2::<ParenValue<MacroArgs>>(attr.tokens)?;
+        for arg in args.value.args.into_iter() {
+            match (arg.name.to_string().as_str(), arg.value.into_lit()?) {
+                ("custom_id", Lit::Str(s)) => result.custom_id = CustomIdPattern::parse(&s)?,
+                _ => return Err(Error::new_spanned(arg.name, "Argument inconnu ou mal typé.")),
+            }
+        }
+        if result.custom_id.is_empty() {
+            return Err(Error::new(attr_span, "Argument custom_id manquant"));
+        }
+        Ok(result)
+    }
+}
+
+/// Paramètre d'une fonction de formulaire
+enum ModalParameter {
+    /// Valeur capturée dans le custom_id
+    Capture {
+        var_name: syn::Ident,
+        ty: Box<syn::Type>,
+    },
+    /// Champ texte du formulaire, lu par son nom
+    Field {
+        var_name: syn::Ident,
+        name: String,
+        optional: bool,
+    },
+    Context,
+    Modal,
+    SelfArg,
+}
+
+impl ModalParameter {
+    /// Lit le paramètre et retire l'attribut `#[field]` de la fonction.
+    fn new(arg: &mut syn::FnArg, pattern: &CustomIdPattern) -> syn::Result<Self> {
+        use syn::*;
+        let arg = match arg {
+            FnArg::Receiver(_) => return Ok(ModalParameter::SelfArg),
+            FnArg::Typed(arg) => arg,
+        };
+        let var_name = match arg.pat.as_ref() {
+            Pat::Ident(ident) => ident.ident.clone(),
+            _ => return Err(Error::new_spanned(&arg.pat, "Argument de fonction attendu."))
+        };
+        if pattern.captures().contains(&var_name.to_string()) {
+            return Ok(ModalParameter::Capture {
+                var_name,
+                ty: arg.ty.clone(),
+            });
+        }
+        let (attr_field, attrs): (_, Vec<_>) = std::mem::take(&mut arg.attrs).find_and_pop(|attr| attr.path.is_ident("field"));
+        arg.attrs = attrs;
+        let ty = match arg.ty.as_ref() {
+            Type::Path(TypePath { path, .. }) => path,
+            Type::Reference(TypeReference { elem, .. }) => match elem.as_ref() {
+                Type::Path(TypePath { path, .. }) => path,
+                _ => return Err(Error::new_spanned(&arg.ty, "Type d'argument innatendu."))
+            },
+            _ => return Err(Error::new_spanned(&arg.ty, "Type d'argument innatendu."))
+        };
+        let ty_last = match ty.segments.last() {
+            Some(segment) => segment,
+            None => return Err(Error::new_spanned(ty, "Type incomplet."))
+        };
+        let optional = match ty_last.ident.to_string().as_str() {
+            "Context" => return Ok(ModalParameter::Context),
+            "ModalSubmitEmbed" => return Ok(ModalParameter::Modal),
+            "String" => false,
+            "Option" => match &ty_last.arguments {
+                PathArguments::AngleBracketed(args) if args.args.len() == 1 && args.args.first().map(|arg| quote!(#arg).to_string()) == Some("String".to_string()) => true,
+                _ => return Err(Error::new_spanned(ty, "Un champ de formulaire doit être de type String ou Option<String>."))
+            },
+            _ => return Err(Error::new_spanned(ty, "Un champ de formulaire doit être de type String ou Option<String>."))
+        };
+        let mut name = var_name.to_string();
+        if let Some(attr) = attr_field {
+            let args = parse2::<ParenValue<MacroArgs>>(attr.tokens)?;
+            for arg in args.value.args.into_iter() {
+                match (arg.name.to_string().as_str(), arg.value.into_lit()?) {
+                    ("name", Lit::Str(s)) => name = s.value(),
+                    _ => return Err(Error::new_spanned(arg.name, "Argument inconnu ou mal typé.")),
+                }
+            }
+        }
+        Ok(ModalParameter::Field { var_name, name, optional })
+    }
+}
+
+pub struct Modal {
+    attr: ModalAttribute,
+    impl_fn: syn::ImplItemMethod,
+    params: Vec<ModalParameter>,
+}
+
+impl Modal {
+    pub fn new(attr: syn::Attribute, mut impl_fn: syn::ImplItemMethod) -> syn::Result<Self> {
+        let attr_span = attr.span();
+        let attr = ModalAttribute::from_attr(attr)?;
+        let params = impl_fn.sig.inputs.iter_mut().map(|arg| ModalParameter::new(arg, &attr.custom_id)).collect::<syn::Result<Vec<_>>>()?;
+        let modal = Modal {
+            attr,
+            impl_fn,
+            params,
+        };
+        modal.attr.custom_id.capture_params(attr_span, &modal.captures())?;
+        Ok(modal)
+    }
+    /// Paramètres de la fonction capturés dans le custom_id
+    fn captures(&self) -> Vec<(&syn::Ident, &syn::Type)> {
+        self.params.iter().filter_map(|param| match param {
+            ModalParameter::Capture { var_name, ty } => Some((var_name, ty.as_ref())),
+            _ => None
+        }).collect()
+    }
+    fn capture_params(&self) -> Vec<(&syn::Ident, &syn::Type)> {
+        // Les captures ont été vérifiées à la création
+        self.attr.custom_id.capture_params(pm2::Span::call_site(), &self.captures()).unwrap_or_default()
+    }
+}
+
+impl Function for Modal {
+    fn name(&self) -> pm2::TokenStream {
+        let name = &self.impl_fn.sig.ident;
+        quote! { #name }
+    }
+
+    fn event_handle(&self) -> syn::Result<pm2::TokenStream> {
+        let func_name = self.name();
+        let mut decode = vec![];
+        let mut call = vec![];
+        for param in self.params.iter() {
+            match param {
+                ModalParameter::Field { var_name, name, optional: true } => {
+                    decode.push(quote! {
+                        let #var_name = modal.get_input(#name).filter(|v| !v.is_empty()).map(|v| v.to_string());
+                    });
+                    call.push(quote! { #var_name });
+                },
+                ModalParameter::Field { var_name, name, optional: false } => {
+                    let error_fr = format!("Champ \"{}\" manquant.", name);
+                    let error_en = format!("Missing field \"{}\".", name);
+                    decode.push(quote! {
+                        let #var_name = match modal.get_input(#name) {
+                            Some(v) => v.to_string(),
+                            None => {
+                                if let Err(e) = modal.direct_response(ctx, cddio_core::message::error_lang(modal.lang(), modal.lang().pick(#error_fr, #error_en)).set_ephemeral(true)).await {
+                                    eprintln!("Cannot create response: {}", e);
+                                }
+                                return;
+                            }
+                        };
+                    });
+                    call.push(quote! { #var_name });
+                },
+                ModalParameter::Capture { var_name, .. } => call.push(quote! { #var_name }),
+                ModalParameter::Context => call.push(quote! { ctx }),
+                ModalParameter::Modal => call.push(quote! { modal.clone() }),
+                ModalParameter::SelfArg => (),
+            }
+        }
+        let (guard, decode_captures) = self.attr.custom_id.matcher(quote! { orig_modal.data.custom_id }, &self.capture_params());
+        Ok(quote!{
+            serenity::model::event::Event::InteractionCreate(serenity::model::event::InteractionCreateEvent{interaction: serenity::model::application::interaction::Interaction::ModalSubmit(orig_modal), ..}) if #guard => {
+                #decode_captures
+                let modal = cddio_core::ModalSubmitEmbed::new(orig_modal);
+                #(#decode)*
+                self.#func_name(#(#call),*).await
+            }
+        })
+    }
+}
+
+impl ToTokens for Modal {
+    fn to_tokens(&self, tokens: &mut pm2::TokenStream) {
+        self.impl_fn.to_tokens(tokens);
+        self.attr.custom_id.builder(&self.impl_fn.sig.ident, &self.capture_params()).to_tokens(tokens);
+    }
+}
+
+impl fmt::Debug for Modal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Modal")
+            .field("custom_id", &self.attr.custom_id)
+            .finish()
+    }
+}
@@ -41,15 +41,49 @@ impl<T: syn::parse::Parse> syn::parse::Parse for ParenValue<T>  {
     }
 }
 
-/// Argument like `a=2`
+/// Value of an argument: a literal like `2` or an array like `[1, 2]` or `[text, voice]`
+pub enum MacroValue {
+    Lit(syn::Lit),
+    Array(syn::token::Bracket, syn::punctuated::Punctuated<syn::Expr, syn::Token![,]>),
+}
+impl MacroValue {
+    /// Returns the literal, or an error if the value is an array.
+    pub fn into_lit(self) -> syn::Result<syn::Lit> {
+        match self {
+            MacroValue::Lit(lit) => Ok(lit),
+            MacroValue::Array(bracket, _) => Err(syn::Error::new(bracket.span, "Tableau inattendu.")),
+        }
+    }
+}
+impl syn::parse::Parse for MacroValue {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::token::Bracket) {
+            let content;
+            let bracket = syn::bracketed!(content in input);
+            Ok(MacroValue::Array(bracket, content.parse_terminated(syn::Expr::parse)?))
+        } else {
+            Ok(MacroValue::Lit(input.parse()?))
+        }
+    }
+}
+impl quote::ToTokens for MacroValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            MacroValue::Lit(lit) => lit.to_tokens(tokens),
+            MacroValue::Array(bracket, values) => bracket.surround(tokens, |tokens| values.to_tokens(tokens)),
+        }
+    }
+}
+/// Argument like `a=2` or `a=[1, 2]`
 pub struct MacroArg {
     pub name: syn::Ident,
     pub eq: syn::Token![=],
-    pub value: syn::Lit,
+    pub value: MacroValue,
 }
 impl syn::parse::Parse for MacroArg {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let name = input.parse()?;
+        // Accepte les mots-clés comme nom, par exemple `enum=true`
+        let name = syn::ext::IdentExt::parse_any(input)?;
         let eq = input.parse()?;
         let value = input.parse()?;
         Ok(Self { name, eq, value })
@@ -73,6 +107,62 @@ impl syn::parse::Parse for MacroArgs {
     }
 }
 
+/// Lit une liste de permissions Discord telle que `"BAN_MEMBERS | KICK_MEMBERS"`.
+/// 
+/// Les noms correspondent aux constantes de `serenity::model::permissions::Permissions`.
+pub fn parse_permissions(lit: &syn::LitStr) -> syn::Result<Vec<syn::Ident>> {
+    lit.value()
+        .split(['|', ','])
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            if !name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_') {
+                return Err(syn::Error::new_spanned(lit, format!("Permission invalide : {}", name)));
+            }
+            Ok(syn::Ident::new(name, lit.span()))
+        })
+        .collect()
+}
+
+/// Vérifie qu'un nom traduit respecte les règles de Discord et le retourne.
+/// 
+/// Les noms des commandes slash, des groupes et des arguments font 1 à 32 caractères, en minuscules
+/// et sans espace. Ceux des menus contextuels (`chat_input` à `false`) acceptent les espaces et les majuscules.
+pub fn check_name(lit: &syn::LitStr, chat_input: bool) -> syn::Result<String> {
+    let name = lit.value();
+    if !(1..=32).contains(&name.chars().count()) {
+        return Err(syn::Error::new_spanned(lit, "Le nom doit contenir entre 1 et 32 caractères."));
+    }
+    if chat_input && !name.chars().all(|c| c == '-' || c == '_' || (c.is_alphanumeric() && !c.is_uppercase())) {
+        return Err(syn::Error::new_spanned(lit, "Le nom ne peut contenir que des minuscules, des chiffres, - et _."));
+    }
+    Ok(name)
+}
+
+/// Produit l'expression constante `Permissions` correspondant à la liste de permissions.
+pub fn permissions_tokens(permissions: &[syn::Ident]) -> TokenStream {
+    use quote::quote;
+    quote! {
+        serenity::model::permissions::Permissions::from_bits_truncate(
+            0 #(| serenity::model::permissions::Permissions::#permissions.bits())*
+        )
+    }
+}
+
+/// Produit les traductions anglaises d'un nom ou d'une description.
+/// 
+/// Les locales sont celles de `cddio_core::locale::EN_LOCALES`.
+pub fn localizations_tokens(en: Option<&String>) -> TokenStream {
+    use quote::quote;
+    match en {
+        Some(en) => quote! {{
+            const LOCALIZATIONS: cddio_core::declarative::Localizations = &cddio_core::locale::en_localizations(#en);
+            LOCALIZATIONS
+        }},
+        None => quote! { &[] },
+    }
+}
+
 pub fn fn_args_to_args_call(fn_args: &syn::punctuated::Punctuated<syn::FnArg, syn::Token![,]>) -> syn::Result<TokenStream> {
     use syn::*;
     use quote::quote;
@@ -0,0 +1,58 @@
+//! Sauvegarde quotidienne de la base de données
+//! 
+//! Une tâche sauvegarde la base de données puis planifie la sauvegarde suivante.
+
+use std::{path::PathBuf, sync::{Arc, Weak}};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serenity::async_trait;
+use tokio::sync::RwLock;
+
+use crate::{db, log_error};
+use super::super::utils::{task, registry_file::RegistryFile};
+
+/// Délai en secondes entre deux sauvegardes
+pub const BACKUP_INTERVAL: i64 = 24 * 3600;
+
+pub type Manager = task::TaskManager<Backup, RegistryFile<Backup>, BackupContext>;
+
+/// Données partagées par les tâches de sauvegarde
+pub struct BackupContext {
+    pub database: Arc<sea_orm::DatabaseConnection>,
+    pub directory: PathBuf,
+    /// Nombre de sauvegardes conservées
+    pub keep: usize,
+    /// Gestionnaire des tâches, pour planifier la sauvegarde suivante
+    pub manager: Weak<RwLock<Option<Manager>>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Backup;
+
+impl Backup {
+    /// Planifie la prochaine sauvegarde
+    async fn schedule_next(persistent: &BackupContext) -> Result<(), String> {
+        let manager = match persistent.manager.upgrade() {
+            Some(manager) => manager,
+            None => return Ok(()),
+        };
+        let mut manager = manager.write().await;
+        match manager.as_mut() {
+            Some(manager) => manager.add(Backup, Utc::now().timestamp() + BACKUP_INTERVAL).await.map(|_| ()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl task::DataFunc for Backup {
+    type Persistent = BackupContext;
+    async fn run(&self, persistent: &BackupContext) -> Result<(), String> {
+        // Un échec n'empêche pas la tâche d'être retirée : la sauvegarde suivante est déjà planifiée
+        if let Err(e) = db::backup::backup(&persistent.database, &persistent.directory, persistent.keep).await {
+            log_error!("Erreur lors de la sauvegarde de la base de données: {}", e);
+        }
+        Self::schedule_next(persistent).await
+    }
+}
@@ -0,0 +1,103 @@
+//! Administration du bot, réservée à ses propriétaires
+
+mod backup;
+
+use std::{path::PathBuf, sync::Arc};
+
+use crate::{log_error, db, config::Backup as ConfigBackup};
+use cddio_core::{message, ApplicationCommandEmbed};
+use cddio_macros::component;
+use serenity::{
+    client::Context,
+    model::{event::ReadyEvent, id::UserId}
+};
+use tokio::sync::RwLock;
+use super::utils::{task::Registry, registry_file::RegistryFile};
+
+pub struct Admin {
+    /// Propriétaires du bot
+    owners: Vec<UserId>,
+    database: Arc<sea_orm::DatabaseConnection>,
+    config: ConfigBackup,
+    /// Tâche de sauvegarde quotidienne
+    backups: Arc<RwLock<Option<backup::Manager>>>,
+}
+
+impl Admin {
+    pub fn new(owners: Vec<UserId>, database: Arc<sea_orm::DatabaseConnection>, config: ConfigBackup) -> Self {
+        Self {
+            owners,
+            database,
+            config,
+            backups: Arc::new(RwLock::new(None)),
+        }
+    }
+}
+
+#[component]
+#[group(name="admin", description="Administration du bot")]
+impl Admin {
+    #[event(Ready)]
+    async fn on_ready(&self, _ctx: &Context, _ready: &ReadyEvent) {
+        if self.config.daily {
+            self.start_daily_backup().await;
+        }
+    }
+    #[command(group="admin", description="Sauvegarde la base de données")]
+    async fn backup(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>) {
+        let resp = match app_cmd.delayed_response(ctx, true).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                log_error!("Erreur lors de la création de la réponse: {}", e);
+                return;
+            }
+        };
+        let msg = 'msg: {
+            if !self.owners.contains(&app_cmd.0.user.id) {
+                break 'msg message::error("Cette commande est réservée aux propriétaires du bot");
+            }
+            let directory = PathBuf::from(self.config.directory());
+            match db::backup::backup(&self.database, &directory, self.config.keep()).await {
+                Ok(path) => message::success(format!("Base de données sauvegardée dans `{}`", path.display())),
+                Err(e) => {
+                    log_error!("Erreur lors de la sauvegarde de la base de données: {}", e);
+                    message::error(format!("Erreur lors de la sauvegarde de la base de données: {}", e))
+                }
+            }
+        };
+        if let Err(e) = resp.send_message(msg).await {
+            log_error!("Erreur lors de l'envoi de la réponse: {}", e);
+        }
+    }
+}
+
+impl Admin {
+    /// Démarre la sauvegarde quotidienne de la base de données
+    async fn start_daily_backup(&self) {
+        let mut tasks = self.backups.write().await;
+        if tasks.is_some() {
+            return;
+        }
+        let registry = match RegistryFile::from_file("./data/admin_backups.json").await {
+            Ok(registry) => registry,
+            Err(e) => {
+                log_error!("Impossible de charger la tâche de sauvegarde: {}", e);
+                return;
+            }
+        };
+        let is_scheduled = !registry.get_all().await.is_empty();
+        let mut new_tasks = backup::Manager::new(registry, backup::BackupContext {
+            database: Arc::clone(&self.database),
+            directory: PathBuf::from(self.config.directory()),
+            keep: self.config.keep(),
+            manager: Arc::downgrade(&self.backups),
+        });
+        new_tasks.init().await;
+        if !is_scheduled {
+            if let Err(e) = new_tasks.add(backup::Backup, chrono::Utc::now().timestamp() + backup::BACKUP_INTERVAL).await {
+                log_error!("Impossible de planifier la sauvegarde de la base de données: {}", e);
+            }
+        }
+        *tasks = Some(new_tasks);
+    }
+}
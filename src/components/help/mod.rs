@@ -2,7 +2,7 @@
 //! Il se repose sur le noeud déclaratif de l'arbre de commandes retournée 
 //! par le trait [core::ComponentDeclarative].
 
-use cddio_core::{self as core, ApplicationCommandEmbed, message, message::ToMessage};
+use cddio_core::{self as core, ApplicationCommandEmbed, AutocompleteEmbed, message, message::ToMessage};
 use self::core::declarative::IterType;
 use cddio_macros::component;
 use serenity::client::Context;
@@ -21,16 +21,19 @@ impl Help {
 
 #[component]
 impl Help {
-    #[command(description="Affiche l'aide d'une commanded ou du bot")]
+    #[command(description="Affiche l'aide d'une commanded ou du bot", description_en="Show the help of a command or of the bot", global=true)]
     async fn help(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>, 
-        #[argument(description="Nom de la commande ou du groupe")]
+        #[argument(description="Nom de la commande ou du groupe", autocomplete="complete_command", name_en="command", description_en="Name of the command or group")]
         commande: String
     ) {
         let info = self.get_command_info(commande.as_str()).await;
         let msg = match info {
             Some((_, IterType::Command(comm))) => comm.to_message(),
             Some((_, IterType::Node(node))) => node.to_message(),
-            None => message::error("Commande inconnue"),
+            None => {
+                let lang = app_cmd.lang();
+                message::error_lang(lang, lang.pick("Commande inconnue", "Unknown command"))
+            },
         };
         match app_cmd.direct_response(ctx, msg).await {
             Err(e) => {
@@ -39,7 +42,7 @@ impl Help {
             _ => (),
         }
     }
-    #[command(description="Affiche la liste des commandes du bot")]
+    #[command(description="Affiche la liste des commandes du bot", name_en="command_list", description_en="Show the list of the bot commands", global=true)]
     async fn liste_commandes(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>) {
         let container = self.container.read().await;
         let msg = container.as_ref().iter()
@@ -54,7 +57,7 @@ impl Help {
             .map(|(fullname, iter_type)| format!("**{}**: {}", fullname, iter_type.description))
             .collect::<Vec<_>>()
             .join("\n");
-        match app_cmd.direct_response(ctx, message::success(msg)).await {
+        match app_cmd.direct_response(ctx, message::success_lang(app_cmd.lang(), msg)).await {
             Err(e) => {
                 println!("{}", e);
             }
@@ -62,6 +65,18 @@ impl Help {
         }
     }
 
+    /// Propose les commandes et groupes dont le nom contient le texte saisi.
+    async fn complete_command(&self, _ctx: &Context, autocomplete: &AutocompleteEmbed<'_>) -> Vec<String> {
+        let search = autocomplete.value().to_lowercase();
+        let container = self.container.read().await;
+        container.as_ref().iter()
+            .filter_map(|comp| comp.declarative())
+            .flat_map(|node| node.iter_flat())
+            .map(|(fullname, _)| fullname)
+            .filter(|fullname| fullname.to_lowercase().contains(&search))
+            .collect()
+    }
+
     async fn get_command_info(&self, command: &str) -> Option<(String, IterType)> {
         let container = self.container.read().await;
         container.as_ref().iter()
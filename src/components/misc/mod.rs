@@ -44,7 +44,7 @@ impl Misc {
     }
     #[command(description="Pong!")]
     async fn ping(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>) {
-        if let Err(e) = app_cmd.direct_response(ctx, message::success("Pong!")).await {
+        if let Err(e) = app_cmd.direct_response(ctx, message::success_lang(app_cmd.lang(), "Pong!")).await {
             log_error!("ping: Erreur lors de la réponse: {}", e);
         }
     }
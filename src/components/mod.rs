@@ -14,6 +14,8 @@ pub mod autobahn;
 pub use autobahn::*;
 pub mod dalle_mini;
 pub use dalle_mini::*;
+pub mod admin;
+pub use admin::*;
 
 // Fonctions utiles pour les composants
 mod utils;
\ No newline at end of file
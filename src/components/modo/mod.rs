@@ -1,34 +1,39 @@
 //! Moderation management
 
 mod sanction;
-mod registry_file;
 mod log_audit;
 
 use chrono::{Duration, Utc, DateTime};
 use crate::{log_error, log_warn, log_info};
 use tokio::sync::{RwLock, Mutex};
-use cddio_core::{ApplicationCommandEmbed, message};
+use cddio_core::{ApplicationCommandEmbed, message, locale::Lang};
 use cddio_macros::component;
 use serenity::{
     client::Context,
     model::{
         id::*,
-        event::*
+        event::*,
+        channel::Message
     }
 };
 use super::utils::{
     self, 
     task::Registry,
     time_parser as time,
-    task
-};
-use self::{
-    sanction::{Sanction, SanctionType},
-    registry_file::RegistryFile,
+    task,
+    registry_file::RegistryFile
 };
+use self::sanction::{Sanction, SanctionType};
+
+/// Durée d'un bannissement et nombre de jours d'historique du membre à supprimer
+#[derive(Default)]
+pub struct BanOptions {
+    pub until: Option<DateTime<Utc>>,
+    pub historique: Option<u8>,
+}
 
 pub struct Moderation {
-    tasks: RwLock<Option<task::TaskManager<Sanction, RegistryFile, Context>>>,
+    tasks: RwLock<Option<task::TaskManager<Sanction, RegistryFile<Sanction>, Context>>>,
     logger: log_audit::Log,
     bot_id: Mutex<UserId>
 }
@@ -93,116 +98,139 @@ impl Moderation {
     }
     
     
-    #[command(name="ban",description="Banni un membre du serveur")]
+    #[command(name="ban", permissions="BAN_MEMBERS", dm=false, description="Banni un membre du serveur", description_en="Ban a member from the server")]
     async fn com_ban(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
-        #[argument(description="Membre à bannir", name="qui")]
+        #[argument(description="Membre à bannir", name="qui", description_en="Member to ban", name_en="who")]
         member: UserId,
-        #[argument(description="Raison du ban")]
+        #[argument(description="Raison du ban", description_en="Reason for the sanction", name_en="reason")]
         raison: String,
-        #[argument(description="Supprimer l'historique du membre (nombre de jours de 0 à 7)", name="historique")]
+        #[argument(description="Supprimer l'historique du membre (nombre de jours de 0 à 7)", name="historique", description_en="Delete the message history of the member (number of days from 0 to 7)", name_en="history", min=0, max=7)]
         del_msg: Option<u8>,
-        #[argument(description="Durée du ban")]
+        #[argument(description="Durée du ban", description_en="Duration of the ban", name_en="duration")]
         duree: Option<String>
     ) {
+        let lang = app_cmd.lang();
         self.command_send(ctx, &app_cmd, async {
             let Some(guild_id) = app_cmd.get_guild_id() else {
-                 return message::error("Cette commande doit être executé sur un serveur.".to_string());
+                 return message::error_lang(lang, lang.pick("Cette commande doit être executé sur un serveur.", "This command must be used in a server."));
             };
             let user_by = app_cmd.0.user.id;
             let Some(until) = Self::duration_to_datetime(ctx, &app_cmd,  duree).await else {
-                return message::error("Durée invalide");
+                return message::error_lang(lang, lang.pick("Durée invalide", "Invalid duration"));
             };
-            match self.ban(ctx, guild_id, member, Some(user_by), raison, until, del_msg).await {
-                Ok(v) => v,
+            match self.ban(ctx, guild_id, member, Some(user_by), raison, BanOptions { until, historique: del_msg }).await {
+                Ok(sanction) => sanction.to_server_message(ctx, lang).await,
                 Err(e) => {
                     log_error!("{}", e);
-                    message::error(e)
+                    message::error_lang(lang, e)
                 }
             }
         }).await;
     }
-    #[command(name="kick",description="Expulse un membre du serveur")]
+    #[command(name="kick", permissions="KICK_MEMBERS", dm=false, description="Expulse un membre du serveur", description_en="Kick a member from the server")]
     async fn com_kick(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
-        #[argument(description="Membre à expulser", name="qui")]
+        #[argument(description="Membre à expulser", name="qui", description_en="Member to kick", name_en="who")]
         member: UserId,
-        #[argument(description="Raison de l'expulsion")]
+        #[argument(description="Raison de l'expulsion", description_en="Reason for the kick", name_en="reason")]
         raison: String
     ) {
+        let lang = app_cmd.lang();
         self.command_send(ctx, &app_cmd, async {
             let Some(guild_id) = app_cmd.get_guild_id() else {
-                return message::error("Cette commande doit être executé sur un serveur.".to_string());
+                return message::error_lang(lang, lang.pick("Cette commande doit être executé sur un serveur.", "This command must be used in a server."));
             };
             let user_by = app_cmd.0.user.id;
             match self.kick(ctx, guild_id, member, Some(user_by), raison).await {
-                Ok(v) => v,
+                Ok(sanction) => sanction.to_server_message(ctx, lang).await,
                 Err(e) => {
                     log_error!("{}", e);
-                    message::error(e)
+                    message::error_lang(lang, e)
                 }
             }
         }).await;
     }
     
-    #[command(name="mute",description="Mute un membre du serveur")]
+    #[command(name="mute", permissions="MODERATE_MEMBERS", dm=false, description="Mute un membre du serveur", description_en="Mute a member of the server")]
     async fn com_mute(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
-        #[argument(description="Membre à mute", name="qui")]
+        #[argument(description="Membre à mute", name="qui", description_en="Member to mute", name_en="who")]
         member: UserId,
-        #[argument(description="Raison du ban")]
+        #[argument(description="Raison du ban", description_en="Reason for the sanction", name_en="reason")]
         raison: String,
-        #[argument(description="Durée du mute")]
+        #[argument(description="Durée du mute", description_en="Duration of the mute", name_en="duration")]
         duree: Option<String>
     ) {
+        let lang = app_cmd.lang();
         self.command_send(ctx, &app_cmd, async {
             let Some(guild_id) = app_cmd.get_guild_id() else {
-                return message::error("Cette commande doit être executé sur un serveur.".to_string());
+                return message::error_lang(lang, lang.pick("Cette commande doit être executé sur un serveur.", "This command must be used in a server."));
             };
             let user_by = app_cmd.0.user.id;
             let Some(until) = Self::duration_to_datetime(ctx, &app_cmd,  duree).await else {
-                return message::error("Durée invalide");
+                return message::error_lang(lang, lang.pick("Durée invalide", "Invalid duration"));
             };
             match self.mute(ctx, guild_id, member, Some(user_by), raison, until).await {
-                Ok(v) => v,
+                Ok(sanction) => sanction.to_server_message(ctx, lang).await,
                 Err(e) => {
                     log_error!("{}", e);
-                    message::error(e)
+                    message::error_lang(lang, e)
                 }
             }
         }).await;
     }
-    #[command(name="unban",description="Débanni un membre du serveur")]
+    #[command(name="unban", permissions="BAN_MEMBERS", dm=false, description="Débanni un membre du serveur", description_en="Unban a member from the server")]
     async fn com_unban(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
-        #[argument(description="Membre à débannir", name="qui")]
+        #[argument(description="Membre à débannir", name="qui", description_en="Member to unban", name_en="who")]
         member: UserId
     ) {
+        let lang = app_cmd.lang();
         self.command_send(ctx, &app_cmd, async {
             let Some(guild_id) = app_cmd.get_guild_id() else {
-                return message::error("Cette commande doit être executé sur un serveur.".to_string());
+                return message::error_lang(lang, lang.pick("Cette commande doit être executé sur un serveur.", "This command must be used in a server."));
             };
             let user_by = app_cmd.0.user.id;
             match self.unban(ctx, guild_id, member, Some(user_by)).await {
-                Ok(v) => v,
+                Ok(sanction) => sanction.to_server_message(ctx, lang).await,
                 Err(e) => {
                     log_error!("{}", e);
-                    message::error(e)
+                    message::error_lang(lang, e)
                 }
             }
         }).await;
     }
-    #[command(name="unmute",description="Démute un membre du serveur")]
+    #[command(name="unmute", permissions="MODERATE_MEMBERS", dm=false, description="Démute un membre du serveur", description_en="Unmute a member of the server")]
     async fn com_unmute(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
-        #[argument(description="Membre à démute", name="qui")]
+        #[argument(description="Membre à démute", name="qui", description_en="Member to unmute", name_en="who")]
         member: UserId
     ) {
+        let lang = app_cmd.lang();
         self.command_send(ctx, &app_cmd, async {
             let Some(guild_id) = app_cmd.get_guild_id() else {
-                return message::error("Cette commande doit être executé sur un serveur.".to_string());
+                return message::error_lang(lang, lang.pick("Cette commande doit être executé sur un serveur.", "This command must be used in a server."));
             };
             let user_by = app_cmd.0.user.id;
             match self.unmute(ctx, guild_id, member, Some(user_by)).await {
-                Ok(v) => v,
+                Ok(sanction) => sanction.to_server_message(ctx, lang).await,
+                Err(e) => {
+                    log_error!("{}", e);
+                    message::error_lang(lang, e)
+                }
+            }
+        }).await;
+    }
+    #[message_command(name="Purger l'auteur", name_en="Purge author", permissions="BAN_MEMBERS", dm=false)]
+    async fn menu_purge(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>, msg: &Message) {
+        let lang = app_cmd.lang();
+        self.command_send(ctx, &app_cmd, async {
+            let Some(guild_id) = app_cmd.get_guild_id() else {
+                return message::error_lang(lang, lang.pick("Cette commande doit être executé sur un serveur.", "This command must be used in a server."));
+            };
+            let user_by = app_cmd.0.user.id;
+            let raison = format!("Purge depuis le message {}", msg.link());
+            match self.ban(ctx, guild_id, msg.author.id, Some(user_by), raison, BanOptions { historique: Some(1), ..Default::default() }).await {
+                Ok(sanction) => sanction.to_server_message(ctx, lang).await,
                 Err(e) => {
                     log_error!("{}", e);
-                    message::error(e)
+                    message::error_lang(lang, e)
                 }
             }
         }).await;
@@ -228,21 +256,21 @@ impl Moderation {
         }
     }
     #[inline]
-    pub async fn ban(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, user_by: Option<UserId>, reason: String, until: Option<DateTime<Utc>>, historique: Option<u8>) -> Result<message::Message, String> {
+    pub async fn ban(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, user_by: Option<UserId>, reason: String, options: BanOptions) -> Result<Sanction, String> {
         let sanction = Sanction {
             user_id,
             guild_id,
             user_by: user_by.unwrap_or(ctx.cache.current_user_id()),
             data: SanctionType::Ban{
                 reason,
-                until,
-                historique: historique.map(|v| v.clamp(0, 7)).unwrap_or(0)
+                until: options.until,
+                historique: options.historique.unwrap_or(0)
             }
         };
         self.do_sanction(ctx, sanction).await
     }
     #[inline]
-    pub async fn kick(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, user_by: Option<UserId>, reason: String) -> Result<message::Message, String> {
+    pub async fn kick(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, user_by: Option<UserId>, reason: String) -> Result<Sanction, String> {
         let sanction = Sanction {
             user_id,
             guild_id,
@@ -254,7 +282,7 @@ impl Moderation {
         self.do_sanction(ctx, sanction).await
     }
     #[inline]
-    pub async fn mute(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, user_by: Option<UserId>, reason: String, until: Option<DateTime<Utc>>) -> Result<message::Message, String> {
+    pub async fn mute(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, user_by: Option<UserId>, reason: String, until: Option<DateTime<Utc>>) -> Result<Sanction, String> {
         let sanction = Sanction {
             user_id,
             guild_id,
@@ -267,7 +295,7 @@ impl Moderation {
         self.do_sanction(ctx, sanction).await
     }
     #[inline]
-    pub async fn unban(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, user_by: Option<UserId>) -> Result<message::Message, String> {
+    pub async fn unban(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, user_by: Option<UserId>) -> Result<Sanction, String> {
         let sanction = Sanction {
             user_id,
             guild_id,
@@ -277,7 +305,7 @@ impl Moderation {
         self.do_sanction(ctx, sanction).await
     }
     #[inline]
-    pub async fn unmute(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, user_by: Option<UserId>) -> Result<message::Message, String> {
+    pub async fn unmute(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, user_by: Option<UserId>) -> Result<Sanction, String> {
         let sanction = Sanction {
             user_id,
             guild_id,
@@ -322,7 +350,7 @@ impl Moderation {
         
         Ok(top_role_by > top_role_to)
     }
-    async fn do_sanction(&self, ctx: &Context, sanction: Sanction) -> Result<message::Message, String> {
+    async fn do_sanction(&self, ctx: &Context, sanction: Sanction) -> Result<Sanction, String> {
         let user_id = sanction.user_id();
         let guild_id = sanction.guild_id();
         let user_by = sanction.user_by;
@@ -357,15 +385,14 @@ impl Moderation {
         if let Err(e) = self.logger.push(&sanction).await {
             log_warn!("Impossible d'enregistrer la sanction dans les logs: {}", e);
         }
-        let msg = sanction.to_server_message(ctx).await;
         if let Sanction { data: SanctionType::Ban { until: Some(until), .. }, .. } = sanction {
             let mut tasks = self.tasks.write().await;
             let tasks = tasks.as_mut().unwrap();
-            if let Err(e) = tasks.add(sanction, until.timestamp()).await {
+            if let Err(e) = tasks.add(sanction.clone(), until.timestamp()).await {
                 return Err(format!("Impossible d'ajouter la sanction à la liste: {}", e))
             }
         }
-        Ok(msg)
+        Ok(sanction)
     }
     #[inline]
     async fn duration_to_datetime(ctx: &Context, app_cmd: &ApplicationCommandEmbed<'_>, duration_str: Option<String>) -> Option<Option<DateTime<Utc>>> {
@@ -376,13 +403,17 @@ impl Moderation {
         match res {
             Ok(v) => Some(v),
             Err(e) => {
-                Self::send_error(ctx, app_cmd, format!("Impossible de parser la durée: {}", e)).await;
+                let msg = match app_cmd.lang() {
+                    Lang::Fr => format!("Impossible de parser la durée: {}", e),
+                    Lang::En => format!("Unable to parse the duration: {}", e),
+                };
+                Self::send_error(ctx, app_cmd, msg).await;
                 None
             }
         }
     }
     async fn send_error<S: ToString>(ctx: &Context, app_cmd: &ApplicationCommandEmbed<'_>, msg: S) {
-        match app_cmd.direct_response(ctx, message::error(msg)).await {
+        match app_cmd.direct_response(ctx, message::error_lang(app_cmd.lang(), msg)).await {
             Ok(_) => (),
             Err(e) => log_error!("Impossible de renvoyer une réponse directe: {}", e)
         }
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use crate::{log_error, log_warn};
-use cddio_core::{message, ApplicationCommandEmbed};
+use cddio_core::{message, ApplicationCommandEmbed, locale::Lang};
 use serenity::{
     client::Context,
     model::id::*, 
@@ -58,13 +58,18 @@ impl Sanction {
             SanctionType::Unmute{..} => "Unmute",
         }
     }
-    pub const fn preterite(&self) -> &'static str {
-        match &self.data {
-            SanctionType::Ban{..} => "banni",
-            SanctionType::Mute{..} => "mute",
-            SanctionType::Kick{..} => "kick",
-            SanctionType::Unban{..} => "débanni",
-            SanctionType::Unmute{..} => "démute",
+    pub const fn preterite(&self, lang: Lang) -> &'static str {
+        match (&self.data, lang) {
+            (SanctionType::Ban{..}, Lang::Fr) => "banni",
+            (SanctionType::Mute{..}, Lang::Fr) => "mute",
+            (SanctionType::Kick{..}, Lang::Fr) => "kick",
+            (SanctionType::Unban, Lang::Fr) => "débanni",
+            (SanctionType::Unmute, Lang::Fr) => "démute",
+            (SanctionType::Ban{..}, Lang::En) => "banned",
+            (SanctionType::Mute{..}, Lang::En) => "muted",
+            (SanctionType::Kick{..}, Lang::En) => "kicked",
+            (SanctionType::Unban, Lang::En) => "unbanned",
+            (SanctionType::Unmute, Lang::En) => "unmuted",
         }
     }
     pub async fn apply(&self, ctx: &Context) -> serenity::Result<()> {
@@ -99,55 +104,63 @@ impl Sanction {
     pub fn data(&self) -> &SanctionType {
         &self.data
     }
+    /// Message envoyé au membre sanctionné, dans la langue principale du serveur.
     #[inline]
     pub async fn to_user_message(&self, ctx: &Context) -> message::Message {
         let guild_id = self.guild_id();
-        let guild_name = guild_id
+        let (guild_name, lang) = guild_id
             .to_guild_cached(ctx)
-            .map(|v| v.name)
-            .or_else(|| guild_id
-                .to_guild_cached(ctx)
-                .map(|v| v.name)
-            )
-            .unwrap_or_else(|| guild_id.to_string());
-        
-        self.to_message(message::COLOR_INFO, format!("Vous avez été {} du serveur {}", self.preterite(), guild_name))
+            .map(|v| (v.name, Lang::from_locale(&v.preferred_locale)))
+            .unwrap_or_else(|| (guild_id.to_string(), Lang::default()));
+        let description = match lang {
+            Lang::Fr => format!("Vous avez été {} du serveur {}", self.preterite(lang), guild_name),
+            Lang::En => format!("You have been {} from the server {}", self.preterite(lang), guild_name),
+        };
+        self.to_message(lang, message::COLOR_INFO, description)
     }
+    /// Message confirmant la sanction au modérateur.
     #[inline]
-    pub async fn to_server_message(&self, ctx: &Context) -> message::Message {
+    pub async fn to_server_message(&self, ctx: &Context, lang: Lang) -> message::Message {
         let user = self.user_id().to_user(ctx).await.unwrap();
-        self.to_message(message::COLOR_SUCCESS, format!("{} a été {}", user.name, self.preterite()))
+        let description = match lang {
+            Lang::Fr => format!("{} a été {}", user.name, self.preterite(lang)),
+            Lang::En => format!("{} has been {}", user.name, self.preterite(lang)),
+        };
+        self.to_message(lang, message::COLOR_SUCCESS, description)
     }
-    fn estimation_time(date: &DateTime<Utc>) -> String {
+    fn estimation_time(date: &DateTime<Utc>, lang: Lang) -> String {
         let now = Utc::now();
         let diff = date.signed_duration_since(now);
         if (diff.num_days()+15)/30 > 0 {
-            format!("{} mois", (diff.num_days()+15)/30)
+            format!("{} {}", (diff.num_days()+15)/30, lang.pick("mois", "months"))
         } else if diff.num_days() > 0 {
-            format!("{} jours", diff.num_days())
+            format!("{} {}", diff.num_days(), lang.pick("jours", "days"))
         } else if diff.num_hours() > 0 {
-            format!("{} heures", diff.num_hours())
+            format!("{} {}", diff.num_hours(), lang.pick("heures", "hours"))
         } else if diff.num_minutes() > 0 {
             format!("{} minutes", diff.num_minutes())
         } else {
-            format!("{} secondes", diff.num_seconds())
+            format!("{} {}", diff.num_seconds(), lang.pick("secondes", "seconds"))
         }
     }
-    fn format_date(date: &DateTime<Utc>) -> String {
-        format!("{} (environ {})", date.format("%d %B %Y à %H:%M:%S"), Sanction::estimation_time(date))
+    fn format_date(date: &DateTime<Utc>, lang: Lang) -> String {
+        match lang {
+            Lang::Fr => format!("{} (environ {})", date.format("%d %B %Y à %H:%M:%S"), Sanction::estimation_time(date, lang)),
+            Lang::En => format!("{} (about {})", date.format("%B %d, %Y at %H:%M:%S"), Sanction::estimation_time(date, lang)),
+        }
     }
     
-    fn to_message<S: ToString>(&self, color: serenity::utils::Colour, description: S) -> message::Message {
+    fn to_message<S: ToString>(&self, lang: Lang, color: serenity::utils::Colour, description: S) -> message::Message {
         let mut m = message::Message::new();
         m.add_embed(|e| {
             e.title(self.name())
                 .description(description)
                 .color(color);
             if let SanctionType::Ban{until: Some(until), ..} | SanctionType::Mute{until: Some(until), ..} = &self.data {
-                e.field("Temps", Self::format_date(until), true);
+                e.field(lang.pick("Temps", "Until"), Self::format_date(until, lang), true);
             }
             if let SanctionType::Ban{reason, ..} | SanctionType::Mute{reason, ..} | SanctionType::Kick{reason, ..} = &self.data {
-                e.field("Raison", reason, true);
+                e.field(lang.pick("Raison", "Reason"), reason, true);
             }
             e
         });
@@ -1,19 +1,25 @@
 //! Internal slash declaration handler
 
+mod sync;
+
 use std::collections::HashMap;
 
 use cddio_macros::component;
 use cddio_core::{self as core, message, ApplicationCommandEmbed, declarative::Node};
-use serenity::model::event::ReadyEvent;
+use crate::{log_error, log_info};
+use serenity::model::event::{ReadyEvent, GuildCreateEvent};
 use serenity::model::application::command::CommandPermissionType;
 use serenity::prelude::*;
 use serenity::model::id::{UserId, ApplicationId, CommandId, GuildId};
+use self::sync::Scope;
 
 pub struct SlashCommand {
     app_id: ApplicationId,
     container: core::container::RefContainer,
     owners: Vec<UserId>,
-    application_commands: RwLock<HashMap<GuildId,HashMap<String, CommandId>>>
+    application_commands: RwLock<HashMap<GuildId,HashMap<String, CommandId>>>,
+    /// Identifiants des commandes globales, par nom
+    global_commands: RwLock<HashMap<String, CommandId>>,
 }
 
 impl SlashCommand {
@@ -22,57 +28,64 @@ impl SlashCommand {
             app_id,
             container,
             owners,
-            application_commands: RwLock::new(HashMap::new())
+            application_commands: RwLock::new(HashMap::new()),
+            global_commands: RwLock::new(HashMap::new()),
         }
     }
 }
 
 #[component]
-#[group(name="slash", description="Gestion des commandes slash")]
+#[group(name="slash", description="Gestion des commandes slash", permissions="MANAGE_GUILD", dm=false)]
 #[group(name="permissions", description="Gérer les permissions des commandes", parent="slash")]
 impl SlashCommand {
     #[event(Ready)]
-    async fn on_ready(&self, ctx: &Context, ready: &ReadyEvent) {
-        let container = self.container.read().await;
-        let mut list_declarative = Vec::<&'static Node>::new();
-        let mut app_cmds = HashMap::new();
+    async fn on_ready(&self, ctx: &Context, _: &ReadyEvent) {
+        let list_declarative = self.list_declarative().await;
         #[cfg(feature = "show_markdown")]
         {
             let mut markdown = "# Commandes Slash\n\n".to_string();
-            for cont in container.as_ref() {
-                if let Some(node) = cont.declarative() {
-                    markdown.push_str(&format!("{}", node.to_markdown()));
-                }
+            for node in &list_declarative {
+                markdown.push_str(&format!("{}", node.to_markdown()));
             }
             println!("{}", markdown);
         }
-        for cont in container.as_ref() {
-            if let Some(node) = cont.declarative() {
-                list_declarative.push(node);
-                #[cfg(debug_assertions)]
-                node.iter_flat().for_each(|(fullname, item)| println!("|{}| {}", fullname, item));
+        #[cfg(debug_assertions)]
+        for node in &list_declarative {
+            node.iter_flat().for_each(|(fullname, item)| println!("|{}| {}", fullname, item));
+        }
+        match sync::sync(ctx, Scope::Global, &list_declarative).await {
+            Ok(report) => {
+                log_info!("Commandes globales synchronisées: {}", report);
+                *self.global_commands.write().await = report.commands;
+            },
+            Err(e) => log_error!("Erreur lors de la synchronisation des commandes globales: {}", e),
+        }
+    }
+    /// Synchronise les commandes d'un serveur.
+    /// 
+    /// L'événement est reçu pour chaque serveur au démarrage puis pour chaque nouveau serveur.
+    /// Un serveur n'est synchronisé qu'une fois par exécution.
+    #[event(GuildCreate)]
+    async fn on_guild_create(&self, ctx: &Context, event: &GuildCreateEvent) {
+        let guild_id = event.guild.id;
+        {
+            let mut app_cmds = self.application_commands.write().await;
+            if app_cmds.contains_key(&guild_id) {
+                return;
             }
+            app_cmds.insert(guild_id, HashMap::new());
         }
-        for guild in &ready.ready.guilds {
-            let status = guild.id.set_application_commands(ctx, |v| {
-                list_declarative.iter().for_each(|node| node.add_application_command(v));
-                v
-            }).await;
-            let guild_name= guild.id.name(ctx).or_else(|| Some(guild.id.0.to_string())).unwrap();
-            match status {
-                Ok(guild_app_cmds) => {
-                    let guild_app_cmds = guild_app_cmds.into_iter().map(|app_cmd| {
-                        (app_cmd.name.to_string(), app_cmd.id)
-                    }).collect();
-                    app_cmds.insert(guild.id, guild_app_cmds);
-                    println!("Application commands added to {}", guild_name)
-                },
-                Err(why) => {
-                    println!("Error while setting application commands to \"{}\": {:?}", guild_name, why);
-                }
+        let list_declarative = self.list_declarative().await;
+        match sync::sync(ctx, Scope::Guild(guild_id), &list_declarative).await {
+            Ok(report) => {
+                log_info!("Commandes du serveur {} synchronisées: {}", event.guild.name, report);
+                self.application_commands.write().await.insert(guild_id, report.commands);
+            },
+            Err(e) => {
+                log_error!("Erreur lors de la synchronisation des commandes du serveur {}: {}", event.guild.name, e);
+                self.application_commands.write().await.remove(&guild_id);
             }
         }
-        *self.application_commands.write().await = app_cmds;
     }
     #[command(name="list", description="Liste les permissions des commandes sur le serveur", group="permissions")]
     async fn permissions_list(
@@ -96,15 +109,16 @@ impl SlashCommand {
                 return;
             }
         };
-        let mut commands = match guild_id.get_application_commands(ctx).await {
-            Ok(v) => v,
-            Err(_) => Vec::new()
-        }.into_iter().filter(|c| c.application_id == self.app_id);
+        // Les permissions des commandes globales sur le serveur sont aussi listées
+        let mut commands = self.global_commands.read().await.clone();
+        if let Some(guild_commands) = self.application_commands.read().await.get(&guild_id) {
+            commands.extend(guild_commands.clone());
+        }
         let perms = match guild_id.get_application_commands_permissions(ctx).await {
             Ok(v) => v,
             Err(_) => Vec::new()
         }.into_iter().filter(|c| c.application_id == self.app_id);
-        let perms = perms.filter_map(|v| commands.find(|c| c.id == v.id).map(|command| (command.name, v.permissions)));
+        let perms = perms.filter_map(|v| commands.iter().find(|(_, id)| **id == v.id).map(|(name, _)| (name.clone(), v.permissions)));
 
         delayed.message = {
             let mut msg = message::success("");
@@ -138,6 +152,10 @@ impl SlashCommand {
 }
 
 impl SlashCommand {
+    async fn list_declarative(&self) -> Vec<&'static Node> {
+        let container = self.container.read().await;
+        container.as_ref().iter().filter_map(|cont| cont.declarative()).collect()
+    }
     async fn to_markdown(&self) -> String {
         let container = self.container.read().await;
         container.as_ref().iter().map(|cont| {
@@ -0,0 +1,205 @@
+//! Synchronisation des commandes d'application avec Discord.
+//!
+//! Les commandes déclarées par les composants sont comparées à celles enregistrées sur Discord :
+//! seules les commandes absentes, modifiées ou retirées donnent lieu à une requête.
+//! Une commande est considérée modifiée lorsque sa déclaration diffère de la commande
+//! enregistrée sur Discord, y compris si celle-ci a été modifiée en dehors du bot.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use cddio_core::declarative::Node;
+use serde_json::Value;
+use serenity::builder::CreateApplicationCommand;
+use serenity::client::Context;
+use serenity::model::application::command::{Command, CommandType};
+use serenity::model::id::{CommandId, GuildId};
+
+/// Champs d'une commande enregistrée qui ne font pas partie de sa déclaration
+const REGISTRATION_FIELDS: [&str; 7] = ["id", "application_id", "guild_id", "version", "default_permission", "name_localized", "description_localized"];
+
+/// Portée d'enregistrement des commandes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// Commandes disponibles sur tous les serveurs et en message privé
+    Global,
+    /// Commandes d'un serveur
+    Guild(GuildId),
+}
+
+impl Scope {
+    async fn get_commands(&self, ctx: &Context) -> serenity::Result<Vec<Command>> {
+        match self {
+            Scope::Global => Command::get_global_application_commands(ctx).await,
+            Scope::Guild(guild_id) => guild_id.get_application_commands(ctx).await,
+        }
+    }
+    async fn create(&self, ctx: &Context, command: CreateApplicationCommand) -> serenity::Result<Command> {
+        match self {
+            Scope::Global => Command::create_global_application_command(ctx, |cmd| {*cmd = command; cmd}).await,
+            Scope::Guild(guild_id) => guild_id.create_application_command(ctx, |cmd| {*cmd = command; cmd}).await,
+        }
+    }
+    async fn edit(&self, ctx: &Context, command_id: CommandId, command: CreateApplicationCommand) -> serenity::Result<Command> {
+        match self {
+            Scope::Global => Command::edit_global_application_command(ctx, command_id, |cmd| {*cmd = command; cmd}).await,
+            Scope::Guild(guild_id) => guild_id.edit_application_command(ctx, command_id, |cmd| {*cmd = command; cmd}).await,
+        }
+    }
+    async fn delete(&self, ctx: &Context, command_id: CommandId) -> serenity::Result<()> {
+        match self {
+            Scope::Global => Command::delete_global_application_command(ctx, command_id).await,
+            Scope::Guild(guild_id) => guild_id.delete_application_command(ctx, command_id).await,
+        }
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scope::Global => write!(f, "global"),
+            Scope::Guild(guild_id) => write!(f, "serveur {}", guild_id.0),
+        }
+    }
+}
+
+/// Commande déclarée par un composant
+struct Declared {
+    name: String,
+    kind: CommandType,
+    builder: CreateApplicationCommand,
+    /// Déclaration sous la forme comparable à une commande enregistrée
+    content: Value,
+}
+
+impl Declared {
+    fn new(mut builder: CreateApplicationCommand, scope: Scope) -> Self {
+        let kind = match builder.0.get("type").and_then(Value::as_u64) {
+            Some(2) => CommandType::User,
+            Some(3) => CommandType::Message,
+            _ => CommandType::ChatInput,
+        };
+        // Une modification ne réinitialise pas les champs absents : ils sont donc explicités.
+        if kind == CommandType::ChatInput {
+            builder.0.entry("options").or_insert_with(|| Value::Array(Vec::new()));
+        }
+        builder.0.entry("default_member_permissions").or_insert(Value::Null);
+        let name = builder.0.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+        let content = normalize(serde_json::to_value(&builder.0).unwrap_or_default(), scope);
+        Declared {
+            name,
+            kind,
+            builder,
+            content,
+        }
+    }
+    fn is(&self, command: &Command) -> bool {
+        self.name == command.name && self.kind == command.kind
+    }
+    /// La commande enregistrée correspond à la déclaration
+    fn matches(&self, command: &Command, scope: Scope) -> bool {
+        let registered = normalize(serde_json::to_value(command).unwrap_or_default(), scope);
+        same_value(&self.content, &registered)
+    }
+}
+
+/// Retire d'une commande les champs propres à son enregistrement.
+///
+/// `dm_permission` n'a de sens que pour les commandes globales, où son absence vaut `true`.
+fn normalize(mut command: Value, scope: Scope) -> Value {
+    if let Some(fields) = command.as_object_mut() {
+        for field in REGISTRATION_FIELDS {
+            fields.remove(field);
+        }
+        match scope {
+            Scope::Global if matches!(fields.get("dm_permission"), None | Some(Value::Null)) => {
+                fields.insert("dm_permission".to_string(), Value::Bool(true));
+            },
+            Scope::Global => (),
+            Scope::Guild(_) => {
+                fields.remove("dm_permission");
+            },
+        }
+    }
+    command
+}
+
+/// Compare deux valeurs JSON sans tenir compte des valeurs par défaut.
+///
+/// Un champ absent, nul, faux ou vide est équivalent à un autre, et un nombre est égal à
+/// la chaîne qui le représente (Discord renvoie les permissions sous forme de chaîne).
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => a.keys().chain(b.keys())
+            .all(|key| same_value(a.get(key).unwrap_or(&Value::Null), b.get(key).unwrap_or(&Value::Null))),
+        (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_value(a, b)),
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (Value::Number(n), Value::String(s)) | (Value::String(s), Value::Number(n)) => n.to_string() == *s,
+        (a, b) => a == b || (is_default(a) && is_default(b)),
+    }
+}
+
+fn is_default(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Bool(b) => !b,
+        Value::String(s) => s.is_empty(),
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+        Value::Number(_) => false,
+    }
+}
+
+/// Résultat d'une synchronisation
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub created: usize,
+    pub edited: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+    /// Identifiants des commandes de la portée, par nom
+    pub commands: HashMap<String, CommandId>,
+}
+
+impl Display for SyncReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} créée(s), {} modifiée(s), {} supprimée(s), {} inchangée(s)", self.created, self.edited, self.deleted, self.unchanged)
+    }
+}
+
+/// Synchronise les commandes de la portée avec celles déclarées dans `nodes`.
+///
+/// Les commandes enregistrées mais plus déclarées sont supprimées, les nouvelles sont créées
+/// et celles qui diffèrent de leur déclaration sont modifiées.
+pub async fn sync(ctx: &Context, scope: Scope, nodes: &[&'static Node]) -> serenity::Result<SyncReport> {
+    let declared = nodes.iter()
+        .flat_map(|node| node.application_commands(scope == Scope::Global))
+        .map(|builder| Declared::new(builder, scope))
+        .collect::<Vec<_>>();
+    let registered = scope.get_commands(ctx).await?;
+    let mut report = SyncReport::default();
+    for command in registered.iter().filter(|command| !declared.iter().any(|decl| decl.is(command))) {
+        scope.delete(ctx, command.id).await?;
+        report.deleted += 1;
+    }
+    for decl in declared {
+        let command_id = match registered.iter().find(|command| decl.is(command)) {
+            Some(command) if decl.matches(command, scope) => {
+                report.unchanged += 1;
+                command.id
+            },
+            Some(command) => {
+                scope.edit(ctx, command.id, decl.builder).await?;
+                report.edited += 1;
+                command.id
+            },
+            None => {
+                let command = scope.create(ctx, decl.builder).await?;
+                report.created += 1;
+                command.id
+            },
+        };
+        report.commands.insert(decl.name, command_id);
+    }
+    Ok(report)
+}
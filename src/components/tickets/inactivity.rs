@@ -0,0 +1,144 @@
+//! Fermeture automatique des tickets inactifs
+//! 
+//! Chaque ticket d'une catégorie avec un délai d'inactivité possède une seule tâche planifiée : 
+//! l'avertissement, puis la fermeture du ticket une fois l'avertissement envoyé. 
+//! 
+//! Les messages du ticket ne font qu'enregistrer la date de la dernière activité. 
+//! À son exécution, la tâche compare cette date à son délai et se replanifie 
+//! si le ticket a été actif entre temps.
+
+use std::sync::{Arc, Weak};
+
+use cddio_core::message;
+use serde::{Deserialize, Serialize};
+use serenity::{
+    async_trait,
+    client::Context,
+    model::{
+        application::component::ButtonStyle,
+        id::ChannelId
+    }
+};
+
+use crate::{db::controller as db_ctrl, log_error, log_info};
+use super::super::utils::{task, registry_file::RegistryFile, time_parser};
+
+pub type Manager = task::TaskManager<Inactivity, RegistryFile<Inactivity>, InactivityContext>;
+
+/// Données partagées par les tâches d'inactivité
+pub struct InactivityContext {
+    pub ctx: Context,
+    pub database: Arc<sea_orm::DatabaseConnection>,
+    /// Composant des tickets, pour fermer le ticket comme une fermeture manuelle
+    pub tickets: Weak<super::Tickets>,
+    /// Délai en secondes entre l'avertissement et la fermeture
+    pub grace_period: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InactivityStep {
+    /// Prévient les membres du ticket de sa fermeture prochaine
+    Warn,
+    /// Ferme le ticket
+    Close,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Inactivity {
+    pub channel_id: ChannelId,
+    /// Délai d'inactivité de la catégorie en secondes
+    pub timeout: i64,
+    pub step: InactivityStep,
+}
+
+impl Inactivity {
+    async fn warn(&self, persistent: &InactivityContext) -> Result<(), String> {
+        let ctx = &persistent.ctx;
+        if ctx.cache.guild_channel(self.channel_id).is_none() {
+            // Le salon a été supprimé entre temps, la tâche n'a plus lieu d'être
+            return Ok(());
+        }
+        let msg = message::warn(format!(
+            "Ce ticket est inactif depuis {}. Il sera fermé automatiquement dans {} si personne n'y écrit.", 
+            time_parser::format_duration(self.timeout as u64),
+            time_parser::format_duration(persistent.grace_period as u64)
+        ));
+        let res = self.channel_id.send_message(ctx, |create_msg| {
+            *create_msg = msg.into();
+            create_msg.components(|cmps| {
+                cmps.create_action_row(|action| {
+                    action.create_button(|button| {
+                        button
+                            .label("Garder ouvert")
+                            .style(ButtonStyle::Primary)
+                            .custom_id(super::Tickets::on_button_ticket_keep_open_custom_id())
+                    })
+                })
+            })
+        }).await;
+        res
+            .map(|_| ())
+            .map_err(|e| format!("Impossible d'envoyer l'avertissement d'inactivité dans {}: {}", self.channel_id, e))
+    }
+    async fn close(&self, persistent: &InactivityContext, tickets: Arc<super::Tickets>) -> Result<(), String> {
+        let ctx = &persistent.ctx;
+        if ctx.cache.guild_channel(self.channel_id).is_none() {
+            return Ok(());
+        }
+        log_info!("Fermeture du ticket inactif {}", self.channel_id);
+        let ctx = ctx.clone();
+        let channel_id = self.channel_id;
+        // La fermeture annule les tâches d'inactivité du ticket, dont celle-ci : elle est donc lancée à part
+        tokio::spawn(async move {
+            let reason = Some("Fermeture automatique pour inactivité".to_string());
+            if let Err(e) = tickets.ticket_close_channel(&ctx, channel_id, None, reason).await {
+                log_error!("Erreur lors de la fermeture du ticket inactif {}: {}", channel_id, e);
+            }
+        });
+        Ok(())
+    }
+    /// Planifie l'étape suivante de la fermeture automatique du ticket
+    async fn schedule(&self, tickets: &super::Tickets, step: InactivityStep, timestamp: i64) -> Result<(), String> {
+        let next = Inactivity { channel_id: self.channel_id, timeout: self.timeout, step };
+        tickets.schedule_inactivity_step(next, timestamp).await
+    }
+}
+
+#[async_trait]
+impl task::DataFunc for Inactivity {
+    type Persistent = InactivityContext;
+    async fn run(&self, persistent: &InactivityContext) -> Result<(), String> {
+        let is_ticket = db_ctrl::ticket::is_ticket_exists(&persistent.database, self.channel_id).await
+            .map_err(|e| format!("Erreur de la base de données: {}", e))?;
+        if !is_ticket {
+            return Ok(());
+        }
+        let last_activity = db_ctrl::ticket::last_activity(&persistent.database, self.channel_id).await
+            .map_err(|e| format!("Erreur de la base de données: {}", e))?;
+        let tickets = match persistent.tickets.upgrade() {
+            Some(tickets) => tickets,
+            None => return Err("Le composant des tickets n'est plus disponible".to_string()),
+        };
+        let now = chrono::Utc::now().timestamp();
+        // Délai sans activité au-delà duquel l'étape s'applique
+        let delay = match self.step {
+            InactivityStep::Warn => self.timeout,
+            InactivityStep::Close => self.timeout + persistent.grace_period,
+        };
+        match last_activity {
+            // Le ticket a été actif depuis la planification de la tâche
+            Some(last_activity) if now < last_activity + delay => {
+                return self.schedule(&tickets, InactivityStep::Warn, last_activity + self.timeout).await;
+            },
+            _ => (),
+        }
+        match self.step {
+            InactivityStep::Warn => {
+                let warned = self.warn(persistent).await;
+                self.schedule(&tickets, InactivityStep::Close, now + persistent.grace_period).await?;
+                warned
+            },
+            InactivityStep::Close => self.close(persistent, tickets).await,
+        }
+    }
+}
@@ -87,7 +87,10 @@ async fn from_attachment<C: ConnectionTrait>(db: &C, attachment: String, message
     model::discord::Attachment::insert(
         model::discord::attachment::ActiveModel {
             message_id: sea_orm::ActiveValue::Set(message_id),
+            filename: sea_orm::ActiveValue::Set(attachment.rsplit('/').next().unwrap_or_default().to_string()),
             url: sea_orm::ActiveValue::Set(attachment),
+            size: sea_orm::ActiveValue::Set(0),
+            saved_at: sea_orm::ActiveValue::Set(0),
             ..Default::default()
         }
     )
@@ -123,6 +126,10 @@ async fn from_message<C: ConnectionTrait>(db: &C, message: archive::ArchiveMessa
             content: ActiveValue::Set(message.content),
             in_reply_to: ActiveValue::Set(db_in_reply_to),
             last_modified: ActiveValue::Set(message.timestamp),
+            deleted_at: ActiveValue::Set(None),
+            kind: ActiveValue::Set(0),
+            embeds: ActiveValue::Set(None),
+            stickers: ActiveValue::Set(None),
         }
     )
         .exec(db).await
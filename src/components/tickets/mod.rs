@@ -2,30 +2,34 @@
 
 #[cfg(feature = "migration_json_db")]
 mod json_to_db;
+mod inactivity;
+mod retention;
 
-use std::sync::Arc;
+use std::{collections::{HashMap, HashSet}, sync::{Arc, OnceLock, Weak}};
 use crate::{
     log_error, log_warn, 
     db::{
-        model::ticket::category, 
+        model::ticket::{category, question, rating}, 
         controller as db_ctrl,
-        IDType
+        IDType, DbId
     },
-    config::Tickets as ConfigTicket
+    config::{Tickets as ConfigTicket, TicketsSettings}
 };
 use sea_orm::EntityTrait;
-use cddio_core::{message, ApplicationCommandEmbed};
-use cddio_macros::component;
+use cddio_core::{message, ApplicationCommandEmbed, AutocompleteEmbed, ModalSubmitEmbed};
+use cddio_macros::{component, CommandChoices};
 use serde::{Serialize, Deserialize};
 use serenity::{
     client::Context,
-    model::{id::*, event::ReadyEvent, prelude::Member},
-    model::application::interaction::message_component::MessageComponentInteraction, 
+    model::{id::*, event::{ReadyEvent, MessageCreateEvent, MessageUpdateEvent, MessageDeleteEvent, MessageDeleteBulkEvent, ReactionAddEvent, ReactionRemoveEvent, ReactionRemoveAllEvent}, prelude::Member},
+    model::application::interaction::message_component::MessageComponentInteraction,
     builder::CreateSelectMenuOption
 };
 use sea_orm::{entity::*, query::*};
 
-use super::utils::data2::Data;
+use tokio::sync::RwLock;
+use super::utils::{data2::Data, time_parser, task::Registry, registry_file::RegistryFile};
+use self::inactivity::{Inactivity, InactivityStep, InactivityContext};
 
 /// Le composant de gestion des tickets
 pub struct Tickets {
@@ -35,6 +39,16 @@ pub struct Tickets {
     config: Option<ConfigTicket>,
     /// Connexion a la base de données
     database: Arc<sea_orm::DatabaseConnection>,
+    /// Tâches de fermeture automatique des tickets inactifs
+    inactivity: RwLock<Option<inactivity::Manager>>,
+    /// Tâche de suppression des anciennes pièces jointes
+    retention: Arc<RwLock<Option<retention::Manager>>>,
+    /// Référence vers le composant, pour fermer les tickets inactifs comme une fermeture manuelle
+    this: OnceLock<Weak<Tickets>>,
+    /// Membres dont un ticket est en cours de création
+    /// 
+    /// Les limites ne sont vérifiées qu'une création à la fois par membre.
+    creating: tokio::sync::Mutex<HashSet<UserId>>,
 }
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone, Copy)]
@@ -43,14 +57,29 @@ struct MessageChoice {
     channel_id: u64
 }
 
+/// Format de la réponse à une question du formulaire d'ouverture
+#[derive(Debug, Clone, Copy, CommandChoices)]
+enum AnswerFormat {
+    #[choice(name="Courte, sur une ligne")]
+    Courte,
+    #[choice(name="Paragraphe, sur plusieurs lignes")]
+    Paragraphe,
+}
+
 /// Données persistantes du composant
 /// 
 /// A chaque écriture dans le fichier de données, le fichier est sauvegardé
 #[derive(Serialize, Deserialize, Default, Debug)]
 struct DataTickets {
-    /// Identifiants du channel et du message pour choisir le type de ticket
-    /// Ces identifiants est enregistré pour pouvoir le remplacer si nécessaire
+    /// Ancien emplacement du menu de choix du type de ticket, lorsqu'un seul serveur était géré.
+    /// 
+    /// Il est déplacé dans `message_choices` au démarrage du bot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     message_choice: Option<MessageChoice>,
+    /// Identifiants du channel et du message pour choisir le type de ticket, par serveur
+    /// Ces identifiants sont enregistrés pour pouvoir les remplacer si nécessaire
+    #[serde(default)]
+    message_choices: HashMap<u64, MessageChoice>,
 }
 
 impl From<category::Model> for CreateSelectMenuOption {
@@ -77,35 +106,198 @@ fn category_to_message(model: &category::Model, title: &str) -> message::Message
     embed.color(message::COLOR_INFO);
     embed.title(title);
     embed.field(&model.name, model.description.as_ref().map(|v| v.as_str()).unwrap_or("*Aucune description*"), false);
+    if let Some(hours) = model.inactivity_timeout {
+        embed.field("Fermeture automatique", format!("Après {} sans message", time_parser::format_duration(hours as u64 * 3600)), false);
+    }
+    msg.add_embed(|e| {*e=embed; e});
+    msg
+}
+fn questions_to_message(category: &category::Model, questions: &[question::Model], title: &str) -> message::Message {
+    let mut msg = message::Message::new();
+    let mut embed = message::Embed::default();
+    embed.color(message::COLOR_INFO);
+    embed.title(title);
+    let list = questions.iter()
+        .map(|q| format!(
+            "**{}.** {} ({}, {})", 
+            q.position + 1, 
+            q.label, 
+            if q.paragraph { "paragraphe" } else { "court" }, 
+            if q.required { "obligatoire" } else { "facultatif" }
+        ))
+        .collect::<Vec<_>>()
+        .join("\n");
+    embed.field(&category.name, if list.is_empty() { "*Aucune question*".to_string() } else { list }, false);
     msg.add_embed(|e| {*e=embed; e});
     msg
 }
+fn questions_to_modal(category: &category::Model, questions: &[question::Model]) -> message::Modal {
+    let mut modal = message::Modal::new(Tickets::on_modal_ticket_create_custom_id(&category.id), &category.name);
+    for q in questions {
+        modal.add_input(q.id, &q.label, |input| {
+            input
+                .style(q.input_style())
+                .required(q.required)
+        });
+    }
+    modal
+}
+
+/// Texte affiché pour un message système, selon son type Discord
+fn system_message_text(kind: i32) -> Option<&'static str> {
+    match kind {
+        6 => Some("*a épinglé un message*"),
+        7 => Some("*a rejoint le serveur*"),
+        8..=11 => Some("*a boosté le serveur*"),
+        18 => Some("*a créé un fil*"),
+        _ => None,
+    }
+}
+
+/// Résume les messages d'un ticket archivé en blocs de texte pouvant être envoyés dans des embeds
+fn replay_archived_messages(messages: &[db_ctrl::ticket::ArchivedMessage]) -> Vec<String> {
+    /// Nombre maximum de messages rejoués
+    const MAX_MESSAGES: usize = 50;
+    /// Taille maximale d'un message rejoué
+    const MAX_MESSAGE_LEN: usize = 300;
+    /// Taille maximale de la description d'un embed
+    const MAX_CHUNK_LEN: usize = 4000;
+    let authors = messages.iter()
+        .map(|archived| (archived.message.id, archived.author.as_deref().unwrap_or("Inconnu")))
+        .collect::<HashMap<_, _>>();
+    let skipped = messages.len().saturating_sub(MAX_MESSAGES);
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    if skipped > 0 {
+        chunk.push_str(&format!("*{} message(s) plus ancien(s) non affiché(s)*\n", skipped));
+    }
+    for archived in &messages[skipped..] {
+        let msg = &archived.message;
+        let mut content = match system_message_text(msg.kind) {
+            Some(text) => text.to_string(),
+            None => {
+                let mut content = msg.content.chars().take(MAX_MESSAGE_LEN).collect::<String>();
+                if content.len() < msg.content.len() {
+                    content.push('…');
+                }
+                content
+            }
+        };
+        if let Some(author) = msg.in_reply_to.and_then(|id| authors.get(&id)) {
+            content.insert_str(0, &format!("*(en réponse à {})* ", author));
+        }
+        let nb_embeds = msg.embeds.as_deref()
+            .and_then(|embeds| serde_json::from_str::<Vec<serde_json::Value>>(embeds).ok())
+            .map(|embeds| embeds.len())
+            .unwrap_or_default();
+        if nb_embeds > 0 {
+            content.push_str(&format!(" *[{} embed(s)]*", nb_embeds));
+        }
+        if msg.stickers.is_some() {
+            content.push_str(" *[sticker]*");
+        }
+        if archived.attachments > 0 {
+            content.push_str(&format!(" *[{} pièce(s) jointe(s)]*", archived.attachments));
+        }
+        if !archived.reactions.is_empty() {
+            let reactions = archived.reactions.iter()
+                .map(|(emoji, count)| format!("{} {}", emoji, count))
+                .collect::<Vec<_>>()
+                .join(" ");
+            content.push_str(&format!(" ({})", reactions));
+        }
+        if msg.deleted_at.is_some() {
+            content.push_str(" *(supprimé)*");
+        }
+        if content.is_empty() {
+            continue;
+        }
+        let line = format!("<t:{}:f> **{}** : {}\n", msg.last_modified, archived.author.as_deref().unwrap_or("Inconnu"), content);
+        if chunk.chars().count() + line.chars().count() > MAX_CHUNK_LEN {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        chunk.push_str(&line);
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// Modal demandant la raison de la fermeture d'un ticket
+fn close_modal() -> message::Modal {
+    use serenity::model::application::component::InputTextStyle;
+    let mut modal = message::Modal::new(Tickets::on_modal_ticket_close_custom_id(), "Fermer le ticket");
+    modal.add_input("reason", "Raison de la fermeture", |input| {
+        input
+            .style(InputTextStyle::Paragraph)
+            .max_length(1000)
+            .required(false)
+    });
+    modal
+}
 
 impl Tickets {
     /// Créer un nouveau composant de gestion des tickets
     pub fn new(config: Option<ConfigTicket>, database: Arc<sea_orm::DatabaseConnection>) -> Self {
         let data = Self::new_data();
+        db_ctrl::discord::set_max_attachment_size(config.as_ref().and_then(|c| c.max_attachment_size));
         Self {
             data,
             config,
             database,
+            inactivity: RwLock::new(None),
+            retention: Arc::new(RwLock::new(None)),
+            this: OnceLock::new(),
+            creating: tokio::sync::Mutex::new(HashSet::new()),
         }
     }
+    /// Donne au composant une référence vers lui-même une fois ajouté au conteneur
+    pub fn bind(self: &Arc<Self>) {
+        let _ = self.this.set(Arc::downgrade(self));
+    }
     fn new_data() -> Data<DataTickets> {
         Data::from_file_or_default("tickets").expect("Impossible d'importer le fichier de données")
     }
 }
 
 #[component]
-#[group(name="tickets", description="Gestion des tickets")]
+#[group(name="tickets", description="Gestion des tickets", permissions="MANAGE_CHANNELS", dm=false)]
 #[group(parent="tickets", name="categories", description="Gestion des catégories de tickets")]
-#[group(name="ticket", description="Commandes dans un ticket")]
+#[group(parent="tickets", name="questions", description="Gestion des formulaires d'ouverture de tickets")]
+#[group(name="ticket", description="Commandes dans un ticket", description_en="Commands inside a ticket", dm=false)]
 impl Tickets {
     #[event(Ready)]
-    async fn on_ready(&self, ctx: &Context, _:&ReadyEvent) {
+    async fn on_ready(&self, ctx: &Context, ready: &ReadyEvent) {
         #[cfg(feature = "migration_json_db")]
         self.do_migration_json_db(ctx).await;
 
+        let guilds = ready.ready.guilds.iter().map(|g| g.id).collect::<Vec<_>>();
+        self.migrate_to_guilds(ctx, &guilds).await;
+
+        {
+            let mut tasks = self.inactivity.write().await;
+            let persistent = InactivityContext {
+                ctx: ctx.clone(),
+                database: Arc::clone(&self.database),
+                tickets: self.this.get().cloned().unwrap_or_default(),
+                grace_period: self.inactivity_grace_period(),
+            };
+            match &mut *tasks {
+                Some(tasks) => tasks.reset_persistent(persistent),
+                None => match RegistryFile::from_file("./data/tickets_inactivity.json").await {
+                    Ok(registry) => {
+                        let mut new_tasks = inactivity::Manager::new(registry, persistent);
+                        new_tasks.init().await;
+                        *tasks = Some(new_tasks);
+                    },
+                    Err(e) => log_error!("Impossible de charger les tâches d'inactivité des tickets: {}", e),
+                }
+            }
+        }
+        self.schedule_open_tickets(&guilds).await;
+        self.start_attachment_retention().await;
+
         if let Err(e) = self.update_menu(ctx).await {
             log_error!("Erreur lors de la mise à jour du menu: {}", e);
         }
@@ -113,13 +305,20 @@ impl Tickets {
     #[cfg(feature = "migration_json_db")]
     async fn do_migration_json_db(&self, ctx: &Context) {
         log_info!("Migration des données de tickets...");
-        let res_migration = json_to_db::do_migration(&self.database, ctx.cache.current_user().id.0 as IDType).await;
+        let bot_id = match ctx.cache.current_user().id.db_id() {
+            Ok(bot_id) => bot_id,
+            Err(e) => {
+                log_error!("Migration des données de tickets impossible: {}", e);
+                return;
+            }
+        };
+        let res_migration = json_to_db::do_migration(&self.database, bot_id).await;
         std::fs::write("migration.log", format!("{:#?}", res_migration)).unwrap();
         log_info!("Migration des données de tickets terminée");
     }
     #[command(group="tickets", description="Assigne le salon de création de tickets")]
     async fn set_channel(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
-        #[argument(name="salon", description="Salon textuel")]
+        #[argument(name="salon", description="Salon textuel", channel_types=[text])]
         chan: Option<ChannelId>
     ) {
         let resp = match app_cmd.delayed_response(ctx, true).await {
@@ -129,8 +328,17 @@ impl Tickets {
                 return;
             }
         };
+        let guild_id = match app_cmd.0.guild_id {
+            Some(guild_id) => guild_id,
+            None => {
+                if let Err(err) = resp.send_message(message::error("Cette commande n'est pas disponible dans un DM")).await {
+                    log_error!("Erreur lors de l'envoi de la réponse: {:?}", err);
+                }
+                return;
+            }
+        };
         'msg: {
-            let message_choice = self.data.read().await.message_choice;
+            let message_choice = self.data.read().await.message_choices.get(&guild_id.0).copied();
             if let Some(MessageChoice { channel_id, message_id }) = message_choice {
                 let msg = match ChannelId(channel_id).message(ctx, message_id).await {
                     Ok(msg) => msg,
@@ -154,8 +362,8 @@ impl Tickets {
                 return;
             }
         };
-        self.data.write().await.message_choice = Some(MessageChoice{channel_id: channel.0, message_id: msg.id.0});
-        self.update_menu(ctx).await.unwrap_or_else(|e| {
+        self.reset_message_choose(guild_id, Some(MessageChoice{channel_id: channel.0, message_id: msg.id.0})).await;
+        self.update_guild_menu(ctx, guild_id).await.unwrap_or_else(|e| {
             log_error!("Erreur lors de la mise a jour du menu: {:?}", e);
         });
         
@@ -163,10 +371,18 @@ impl Tickets {
             log_error!("Erreur lors de l'envoi de la réponse: {:?}", err);
         }
     }
-    #[command(group="ticket", name="close", description="Ferme le ticket actuel")]
+    #[command(group="ticket", name="close", description="Ferme le ticket actuel", description_en="Close the current ticket")]
     async fn ticket_close(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>) {
-        if let Err(e) = self.ticket_close_channel(ctx, app_cmd.0.channel_id, app_cmd.0.member.as_ref()).await {
-            Self::send_error(ctx, app_cmd, e).await;
+        match self.is_a_ticket(ctx, app_cmd.0.channel_id).await {
+            Ok(true) => (),
+            Ok(false) => return Self::send_error(ctx, app_cmd, "Ce n'est pas un ticket").await,
+            Err(e) => return Self::send_error(ctx, app_cmd, e).await,
+        }
+        if let Err(e) = app_cmd.0.create_interaction_response(ctx, |resp| {
+            *resp = close_modal().into();
+            resp
+        }).await {
+            log_error!("Erreur lors de l'envoi du formulaire de fermeture: {}", e);
         }
     }
     #[allow(clippy::too_many_arguments)]
@@ -181,10 +397,21 @@ impl Tickets {
         #[argument(description="Cacher la catégorie du menu de ticket ?")]
         hidden: bool,
         #[argument(description="Description de la catégorie", name="description")]
-        desc: Option<String>
+        desc: Option<String>,
+        #[argument(description="Fermeture automatique après ce nombre d'heures sans message", name="inactivite")]
+        inactivity: Option<u16>
     ) {
         let res = 'error: {
+            let guild_id = match app_cmd.0.guild_id {
+                Some(guild_id) => guild_id,
+                None => break 'error Err("Cette commande n'est pas disponible dans un DM".to_string()),
+            };
+            let db_guild_id = match guild_id.db_id() {
+                Ok(id) => id,
+                Err(e) => break 'error Err(e.to_string()),
+            };
             let nb_categories = category::Entity::find()
+                .filter(category::Column::GuildId.eq(db_guild_id))
                 .filter(category::Column::Name.eq(&name))
                 .count(&*self.database).await;
             match nb_categories {
@@ -192,11 +419,20 @@ impl Tickets {
                 Err(err) => break 'error Err(format!("Erreur lors de la récupération du nombre de catégories: {}", err)),
                 _ => ()
             }
-            let category_id = match db_ctrl::ticket::add_category(&*self.database, name, prefix, category_id, desc, Some(hidden)).await {
+            let new_category = db_ctrl::ticket::NewCategory {
+                guild_id,
+                name,
+                prefix,
+                discord_category_id: category_id,
+                description: desc,
+                hidden,
+                inactivity_timeout: inactivity.filter(|h| *h > 0).map(|h| h as i32),
+            };
+            let category_id = match db_ctrl::ticket::add_category(&self.database, new_category).await {
                 Ok(id) => id,
                 Err(err) => break 'error Err(format!("Erreur lors de la création de la catégorie dans la base de données: {}", err))
             };
-            if let Err(e) = self.update_menu(ctx).await {
+            if let Err(e) = self.update_guild_menu(ctx, guild_id).await {
                 break 'error Err(format!("Erreur lors de la mise à jour du menu: {}", e));
             }
             let category_model = match category::Entity::find_by_id(category_id).one(&*self.database).await {
@@ -217,7 +453,7 @@ impl Tickets {
     #[allow(clippy::too_many_arguments)]
     #[command(group="categories", name="change", description="Change les données d'une categorie de ticket")]
     async fn change_categorie(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
-        #[argument(name="nom", description="Nom de la catégorie")]
+        #[argument(name="nom", description="Nom de la catégorie", autocomplete="complete_category")]
         name: String,
         #[argument(description="Catégorie Discord où les tickets seront créés", name="categorie_discord")]
         category_id: Option<ChannelId>,
@@ -226,7 +462,9 @@ impl Tickets {
         #[argument(description="Cacher la catégorie du menu de ticket ?")]
         hidden: Option<bool>,
         #[argument(description="Description de la catégorie", name="description")]
-        desc: Option<String>
+        desc: Option<String>,
+        #[argument(description="Fermeture automatique après ce nombre d'heures sans message (0 pour désactiver)", name="inactivite")]
+        inactivity: Option<u16>
     ) {
         let delay_resp = match app_cmd.delayed_response(ctx, false).await {
             Ok(resp) => resp,
@@ -236,20 +474,20 @@ impl Tickets {
             }
         };
         let res = 'error: {
-            let model = {
-                let cat = category::Entity::find()
-                    .filter(category::Column::Name.eq(name))
-                    .one(&*self.database).await;
-                let cat = match cat {
-                    Ok(Some(cat)) => cat,
-                    Ok(None) => break 'error Err("Cette catégorie n'existe pas".to_string()),
-                    Err(err) => break 'error Err(format!("Erreur lors de la récupération de la catégorie dans la base de données: {:#?}", err))
-                };
-                cat
+            let guild_id = match app_cmd.0.guild_id {
+                Some(guild_id) => guild_id,
+                None => break 'error Err("Cette commande n'est pas disponible dans un DM".to_string()),
+            };
+            let model = match self.category_by_name(guild_id, &name).await {
+                Ok(cat) => cat,
+                Err(e) => break 'error Err(e),
             };
             let mut active_model: category::ActiveModel = model.into();
             if let Some(category_id) = category_id {
-                active_model.discord_category_id = Set(category_id.0 as i64);
+                match category_id.db_id() {
+                    Ok(category_id) => active_model.discord_category_id = Set(category_id),
+                    Err(e) => break 'error Err(e.to_string()),
+                }
             }
             if let Some(prefix) = prefix {
                 active_model.prefix = Set(prefix);
@@ -260,11 +498,17 @@ impl Tickets {
             if let Some(desc) = desc {
                 active_model.description = Set(Some(desc));
             }
+            if let Some(inactivity) = inactivity {
+                active_model.inactivity_timeout = Set(Some(inactivity as i32).filter(|h| *h > 0));
+            }
             let new_model = match category::Entity::update(active_model).exec(&*self.database).await {
                 Ok(m) => m,
                 Err(e) => break 'error Err(format!("Erreur lors de la mise à jour de la catégorie dans la base de données: {:#?}", e)),
             };
-            if let Err(e) = self.update_menu(ctx).await {
+            if inactivity.is_some() {
+                self.reschedule_category_inactivity(&new_model).await;
+            }
+            if let Err(e) = self.update_guild_menu(ctx, guild_id).await {
                 break 'error Err(format!("Erreur lors de la mise à jour du menu: {}", e));
             }
             Ok(new_model)
@@ -279,11 +523,20 @@ impl Tickets {
     }
     #[command(group="categories", name="remove", description="Supprime une catégorie de ticket")]
     async fn remove_categorie(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
-        #[argument(name="nom", description="Nom de la catégorie")]
+        #[argument(name="nom", description="Nom de la catégorie", autocomplete="complete_category")]
         name: String
     ) {
         let res = 'error: {
+            let guild_id = match app_cmd.0.guild_id {
+                Some(guild_id) => guild_id,
+                None => break 'error Err("Cette commande n'est pas disponible dans un DM".to_string()),
+            };
+            let db_guild_id = match guild_id.db_id() {
+                Ok(id) => id,
+                Err(e) => break 'error Err(e.to_string()),
+            };
             let cat = category::Entity::find()
+                .filter(category::Column::GuildId.eq(db_guild_id))
                 .filter(category::Column::Name.eq(name))
                 .columns([category::Column::Id, category::Column::Name, category::Column::Description].into_iter())
                 .one(&*self.database).await;
@@ -295,7 +548,7 @@ impl Tickets {
             if let Err(e) = db_ctrl::ticket::remove_category(&*self.database, cat.id).await {
                 break 'error Err(format!("Erreur lors de la suppression de la catégorie dans la base de données: {:#?}", e));
             }
-            if let Err(e) = self.update_menu(ctx).await {
+            if let Err(e) = self.update_guild_menu(ctx, guild_id).await {
                 break 'error Err(format!("Erreur lors de la mise à jour du menu: {}", e));
             }
             Ok(cat)
@@ -313,7 +566,18 @@ impl Tickets {
     #[command(group="categories", name="list", description="Liste les catégories de ticket")]
     async fn list_categories(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>) {
         let res = 'error: {
-            let categories = match category::Entity::find().all(&*self.database).await {
+            let guild_id = match app_cmd.0.guild_id {
+                Some(guild_id) => guild_id,
+                None => break 'error Err("Cette commande n'est pas disponible dans un DM".to_string()),
+            };
+            let db_guild_id = match guild_id.db_id() {
+                Ok(id) => id,
+                Err(e) => break 'error Err(e.to_string()),
+            };
+            let categories = match category::Entity::find()
+                .filter(category::Column::GuildId.eq(db_guild_id))
+                .all(&*self.database).await 
+            {
                 Ok(categories) => categories,
                 Err(err) => break 'error Err(format!("Erreur lors de la récupération des catégories dans la base de données: {:#?}", err))
             };
@@ -334,9 +598,130 @@ impl Tickets {
             Self::send_error(ctx, app_cmd, e).await
         }
     }
-    #[command(group="ticket", description="Ajoute une personne au ticket")]
+    /// Propose les catégories du serveur dont le nom contient le texte saisi.
+    async fn complete_category(&self, _ctx: &Context, autocomplete: &AutocompleteEmbed<'_>) -> Vec<String> {
+        let guild_id = match autocomplete.get_guild_id() {
+            Some(guild_id) => guild_id,
+            None => return Vec::new(),
+        };
+        let guild_id = match guild_id.db_id() {
+            Ok(guild_id) => guild_id,
+            Err(_) => return Vec::new(),
+        };
+        let search = autocomplete.value().to_lowercase();
+        match category::Entity::find()
+            .filter(category::Column::GuildId.eq(guild_id))
+            .order_by_asc(category::Column::Name)
+            .all(&*self.database).await
+        {
+            Ok(categories) => categories.into_iter()
+                .map(|cat| cat.name)
+                .filter(|name| name.to_lowercase().contains(&search))
+                .collect(),
+            Err(e) => {
+                log_error!("Erreur lors de la récupération des catégories dans la base de données: {}", e);
+                Vec::new()
+            }
+        }
+    }
+    #[command(group="questions", name="add", description="Ajoute une question au formulaire d'ouverture d'une catégorie de ticket")]
+    async fn add_question(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
+        #[argument(name="categorie", description="Nom de la catégorie", autocomplete="complete_category")]
+        category_name: String,
+        #[argument(name="question", description="Intitulé de la question (45 caractères maximum)", max_length=45)]
+        label: String,
+        #[argument(description="Format de la réponse (courte par défaut)", enum=true)]
+        format: Option<AnswerFormat>,
+        #[argument(description="Réponse obligatoire ? (oui par défaut)")]
+        obligatoire: Option<bool>
+    ) {
+        let res = 'error: {
+            let guild_id = match app_cmd.0.guild_id {
+                Some(guild_id) => guild_id,
+                None => break 'error Err("Cette commande n'est pas disponible dans un DM".to_string()),
+            };
+            let cat = match self.category_by_name(guild_id, &category_name).await {
+                Ok(cat) => cat,
+                Err(e) => break 'error Err(e)
+            };
+            if let Err(e) = db_ctrl::ticket::add_question(&self.database, cat.id, label, matches!(format, Some(AnswerFormat::Paragraphe)), obligatoire.unwrap_or(true)).await {
+                break 'error Err(format!("Erreur lors de l'ajout de la question dans la base de données: {}", e));
+            }
+            match cat.questions().all(&*self.database).await {
+                Ok(questions) => Ok(questions_to_message(&cat, &questions, "Question ajoutée")),
+                Err(e) => Err(format!("Erreur lors de la récupération des questions dans la base de données: {}", e))
+            }
+        };
+        match res {
+            Ok(msg) => app_cmd.direct_response(ctx, msg).await.unwrap_or_else(|e| {
+                log_error!("Erreur lors de l'envoi du message: {}", e);
+            }),
+            Err(e) => Self::send_error(ctx, app_cmd, e).await
+        }
+    }
+    #[command(group="questions", name="remove", description="Retire une question du formulaire d'ouverture d'une catégorie de ticket")]
+    async fn remove_question(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
+        #[argument(name="categorie", description="Nom de la catégorie", autocomplete="complete_category")]
+        category_name: String,
+        #[argument(description="Numéro de la question dans le formulaire")]
+        numero: u8
+    ) {
+        let res = 'error: {
+            let guild_id = match app_cmd.0.guild_id {
+                Some(guild_id) => guild_id,
+                None => break 'error Err("Cette commande n'est pas disponible dans un DM".to_string()),
+            };
+            let cat = match self.category_by_name(guild_id, &category_name).await {
+                Ok(cat) => cat,
+                Err(e) => break 'error Err(e)
+            };
+            if numero == 0 {
+                break 'error Err("Cette question n'existe pas".to_string());
+            }
+            if let Err(e) = db_ctrl::ticket::remove_question(&self.database, cat.id, numero as i32 - 1).await {
+                break 'error Err(format!("Erreur lors de la suppression de la question dans la base de données: {}", e));
+            }
+            match cat.questions().all(&*self.database).await {
+                Ok(questions) => Ok(questions_to_message(&cat, &questions, "Question retirée")),
+                Err(e) => Err(format!("Erreur lors de la récupération des questions dans la base de données: {}", e))
+            }
+        };
+        match res {
+            Ok(msg) => app_cmd.direct_response(ctx, msg).await.unwrap_or_else(|e| {
+                log_error!("Erreur lors de l'envoi du message: {}", e);
+            }),
+            Err(e) => Self::send_error(ctx, app_cmd, e).await
+        }
+    }
+    #[command(group="questions", name="list", description="Liste les questions du formulaire d'ouverture d'une catégorie de ticket")]
+    async fn list_questions(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
+        #[argument(name="categorie", description="Nom de la catégorie", autocomplete="complete_category")]
+        category_name: String
+    ) {
+        let res = 'error: {
+            let guild_id = match app_cmd.0.guild_id {
+                Some(guild_id) => guild_id,
+                None => break 'error Err("Cette commande n'est pas disponible dans un DM".to_string()),
+            };
+            let cat = match self.category_by_name(guild_id, &category_name).await {
+                Ok(cat) => cat,
+                Err(e) => break 'error Err(e)
+            };
+            match cat.questions().all(&*self.database).await {
+                Ok(questions) => Ok(questions_to_message(&cat, &questions, "Formulaire de la catégorie")),
+                Err(e) => Err(format!("Erreur lors de la récupération des questions dans la base de données: {}", e))
+            }
+        };
+        match res {
+            Ok(msg) => app_cmd.direct_response(ctx, msg).await.unwrap_or_else(|e| {
+                log_error!("Erreur lors de l'envoi du message: {}", e);
+            }),
+            Err(e) => Self::send_error(ctx, app_cmd, e).await
+        }
+    }
+    #[command(group="ticket", description="Ajoute une personne au ticket", description_en="Add someone to the ticket")]
     async fn add_member(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
-        #[argument(name="qui", description="Personne à ajouter au ticket")]
+        #[argument(name="qui", description="Personne à ajouter au ticket", name_en="who", description_en="Person to add to the ticket")]
         personne: UserId
     ) {
         use serenity::model::{
@@ -352,28 +737,9 @@ impl Tickets {
             }
         };
         let msg = 'msg: {
-            let guild_id = match app_cmd.0.guild_id {
-                Some(guild_id) => guild_id,
-                None => break 'msg message::error("Cette commande n'est pas disponible dans un DM"),
-            };
-            
-            match self.is_a_ticket(ctx, channel_id).await  {
-                Ok(true) => (),
-                Ok(false) => break 'msg message::error("Ce salon n'est pas un ticket"),
-                Err(e) => break 'msg message::error(e),
-            }
-            let is_staff = match Self::is_staff(ctx, guild_id, app_cmd.0.user.id).await {
-                Ok(v) => v,
-                Err(e) => break 'msg message::error(e),
-            };
-            let is_owner = match Self::is_ticket_owner(ctx, channel_id, app_cmd.0.user.id).await {
-                Ok(v) => v,
-                Err(e) => break 'msg message::error(e),
-            };
-            if !is_staff && !is_owner {
-                break 'msg message::error("Vous n'avez pas la permission d'ajouter des membres au ticket.");
+            if let Err(e) = self.check_owner_or_staff(ctx, app_cmd.0.guild_id, channel_id, app_cmd.0.user.id, "d'ajouter des membres au ticket").await {
+                break 'msg message::error(e);
             }
-            
             let username = personne.to_user(ctx).await.map(|u| super::utils::user_fullname(&u)).unwrap_or_else(|_| personne.0.to_string());
             match channel_id.create_permission(ctx, &PermissionOverwrite {
                 allow: Permissions::VIEW_CHANNEL,
@@ -388,102 +754,732 @@ impl Tickets {
             log_error!("Erreur lors de l'envoi du message: {}", e);
         });
     }
-    #[message_component(custom_id="menu_ticket_create")]
-    async fn on_menu_ticket_create(&self, ctx: &Context, msg: &MessageComponentInteraction) {
-        use serenity::model::application::interaction::InteractionResponseType;
-        let ok = match msg.create_interaction_response(ctx, |resp| {
-            resp.kind(InteractionResponseType::DeferredChannelMessageWithSource)
-                .interaction_response_data(|data| {
-                    data.ephemeral(true)
-                })
-        }).await {
-            Ok(_) => true,
+    #[command(group="tickets", name="stats", description="Statistiques des tickets ouverts sur une période")]
+    async fn stats(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
+        #[argument(name="periode", description="Période à analyser (ex: 7j, 3mois). 30 jours par défaut")]
+        period: Option<String>
+    ) {
+        let delay_resp = match app_cmd.delayed_response(ctx, true).await {
+            Ok(resp) => resp,
             Err(e) => {
-                log_warn!("Erreur lors de la création de l'interaction: {}", e);
-                false
+                log_error!("Erreur lors de l'envoi du message: {}", e);
+                return;
             }
         };
-        if let Err(e) = self.update_menu(ctx).await {
-            log_error!("Erreur lors de la mise à jour du menu: {}", e);
-        }
-        
-        let guild_id = match msg.guild_id {
-            Some(guild_id) => guild_id,
-            None => {
-                log_error!("Le menu n'est pas dans un serveur");
+        let msg = 'msg: {
+            let guild_id = match app_cmd.0.guild_id {
+                Some(guild_id) => guild_id,
+                None => break 'msg message::error("Cette commande n'est pas disponible dans un DM"),
+            };
+            let period = match time_parser::parse(period.as_deref().unwrap_or("30j")) {
+                Ok(period) => period,
+                Err(e) => break 'msg message::error(e),
+            };
+            let since = chrono::Utc::now().timestamp() - period as i64;
+            let stats = match db_ctrl::ticket::ticket_stats(&self.database, guild_id, since).await {
+                Ok(stats) => stats,
+                Err(e) => break 'msg message::error(format!("Erreur lors du calcul des statistiques: {}", e)),
+            };
+            let or_none = |text: String| if text.is_empty() { "*Aucune donnée*".to_string() } else { text };
+            let duration = |d: Option<i64>| d.map(|d| time_parser::format_duration(d.max(0) as u64)).unwrap_or_else(|| "*Aucune donnée*".to_string());
+            let per_category = stats.per_category.iter()
+                .map(|(name, count)| format!("**{}** : {}", name, count))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let first_responses = stats.first_responses.iter()
+                .map(|(id, count)| format!("<@{}> : {}", id, count))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let closures = stats.closures.iter()
+                .map(|(id, count)| format!("<@{}> : {}", id, count))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let ratings_per_category = stats.ratings_per_category.iter()
+                .map(|(name, average, count)| format!("**{}** : {:.1}/{} ({} note(s))", name, average, rating::MAX_RATING, count))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let ratings_per_staff = stats.ratings_per_staff.iter()
+                .map(|(id, average, count)| format!("<@{}> : {:.1}/{} ({} note(s))", id, average, rating::MAX_RATING, count))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let mut msg = message::Message::new();
+            msg.add_embed(|embed| {
+                embed
+                    .color(message::COLOR_INFO)
+                    .title(format!("Statistiques des tickets sur {}", time_parser::format_duration(period)))
+                    .field("Tickets par catégorie", or_none(per_category), false)
+                    .field("Première réponse du staff (médiane)", duration(stats.median_first_response), true)
+                    .field("Résolution (médiane)", duration(stats.median_resolution), true)
+                    .field("Premières réponses par membre du staff", or_none(first_responses), false)
+                    .field("Tickets fermés par membre", or_none(closures), false)
+                    .field("Satisfaction par catégorie", or_none(ratings_per_category), false)
+                    .field("Satisfaction par membre du staff", or_none(ratings_per_staff), false)
+            });
+            msg
+        };
+        delay_resp.send_message(msg).await.unwrap_or_else(|e| {
+            log_error!("Erreur lors de l'envoi du message: {}", e);
+        });
+    }
+    #[command(group="tickets", name="rouvrir", description="Rouvre un ticket archivé dans un nouveau salon")]
+    async fn reopen_ticket(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
+        #[argument(name="membre", description="Membre dont le dernier ticket archivé sera rouvert")]
+        membre: UserId,
+        #[argument(name="ticket", description="Identifiant du salon du ticket archivé à rouvrir")]
+        ticket_id: Option<String>
+    ) {
+        let delay_resp = match app_cmd.delayed_response(ctx, true).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                log_error!("Erreur lors de l'envoi du message: {}", e);
                 return;
             }
         };
-        let user_id = msg.user.id;
-        let category = {
-            let category_name = match msg.data.values.iter().next() {
-                Some(value) => value.clone(),
-                None => {
-                    log_error!("Aucun item n'a été sélectionné");
-                    return;
-                }
+        let msg = 'msg: {
+            let guild_id = match app_cmd.0.guild_id {
+                Some(guild_id) => guild_id,
+                None => break 'msg message::error("Cette commande n'est pas disponible dans un DM"),
             };
-            match category::Entity::find().filter(category::Column::Name.eq(&category_name)).one(self.database.as_ref()).await {
-                Ok(Some(category)) => category,
-                Ok(None) => {
-                    log_error!("La catégorie {} n'existe pas", category_name);
-                    return;
-                }
-                Err(e) => {
-                    log_error!("Erreur lors de la récupération de la catégorie dans la base de données: {}", e);
-                    return;
+            match Self::is_staff(ctx, guild_id, app_cmd.0.user.id).await {
+                Ok(true) => (),
+                Ok(false) => break 'msg message::error("Seul le staff peut rouvrir un ticket."),
+                Err(e) => break 'msg message::error(e),
+            }
+            let archived = match ticket_id {
+                Some(ticket_id) => {
+                    let channel_id = match ticket_id.trim().parse::<u64>() {
+                        Ok(id) => ChannelId(id),
+                        Err(_) => break 'msg message::error("L'identifiant du ticket est invalide"),
+                    };
+                    let (db_channel_id, db_membre) = match (channel_id.db_id(), membre.db_id()) {
+                        (Ok(channel_id), Ok(membre)) => (channel_id, membre),
+                        (Err(e), _) | (_, Err(e)) => break 'msg message::error(e.to_string()),
+                    };
+                    let ticket = crate::db::model::ticket::Entity::find_by_id(db_channel_id).one(&*self.database).await;
+                    let archive = db_ctrl::ticket::archive_of(&self.database, channel_id).await;
+                    let archived = match (ticket, archive) {
+                        (Ok(Some(ticket)), Ok(Some(archive))) if ticket.opened_by == db_membre => (ticket, archive),
+                        (Ok(Some(_)), Ok(Some(_))) => break 'msg message::error("Ce ticket n'a pas été ouvert par ce membre"),
+                        (Ok(_), Ok(_)) => break 'msg message::error("Ce ticket n'existe pas ou n'est pas archivé"),
+                        (Err(e), _) => break 'msg message::error(format!("Erreur lors de la récupération du ticket: {}", e)),
+                        (_, Err(e)) => break 'msg message::error(format!("Erreur lors de la récupération du ticket: {}", e)),
+                    };
+                    let db_guild_id = match guild_id.db_id() {
+                        Ok(guild_id) => guild_id,
+                        Err(e) => break 'msg message::error(e.to_string()),
+                    };
+                    match category::Entity::find_by_id(archived.0.category_id).one(self.database.as_ref()).await {
+                        Ok(Some(category)) if category.guild_id == Some(db_guild_id) => archived,
+                        Ok(_) => break 'msg message::error("Ce ticket n'existe pas ou n'est pas archivé"),
+                        Err(e) => break 'msg message::error(format!("Erreur lors de la récupération du ticket: {}", e)),
+                    }
+                },
+                None => match db_ctrl::ticket::last_archived_ticket(&self.database, guild_id, membre).await {
+                    Ok(Some(archived)) => archived,
+                    Ok(None) => break 'msg message::error("Ce membre n'a aucun ticket archivé à rouvrir"),
+                    Err(e) => break 'msg message::error(format!("Erreur lors de la récupération du ticket: {}", e)),
                 }
+            };
+            if let Some(reopened_as) = archived.1.reopened_as {
+                break 'msg message::error(format!("Ce ticket a déjà été rouvert: <#{}>", reopened_as));
+            }
+            match self.ticket_reopen(ctx, guild_id, archived.0, archived.1).await {
+                Ok(channel_id) => message::success(format!("Ticket rouvert: <#{}>", channel_id)),
+                Err(e) => message::error(e),
             }
         };
-        let result = match self.ticket_create(ctx, guild_id, user_id, category).await {
-            Ok(result) => message::success(format!("Ticket créé: <#{}>", result)),
+        delay_resp.send_message(msg).await.unwrap_or_else(|e| {
+            log_error!("Erreur lors de l'envoi du message: {}", e);
+        });
+    }
+    #[command(group="ticket", name="retirer", description="Retire une personne du ticket", name_en="remove", description_en="Remove someone from the ticket")]
+    async fn remove_member(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
+        #[argument(name="qui", description="Personne à retirer du ticket", name_en="who", description_en="Person to remove from the ticket")]
+        personne: UserId
+    ) {
+        use serenity::model::channel::PermissionOverwriteType;
+        let channel_id = app_cmd.0.channel_id;
+        let delay_resp = match app_cmd.delayed_response(ctx, false).await {
+            Ok(resp) => resp,
             Err(e) => {
-                log_error!("Erreur lors de la création du ticket: {}", e);
-                message::error(e)
+                log_error!("Erreur lors de l'envoi du message: {}", e);
+                return;
             }
         };
-        if ok {
-            match msg.edit_original_interaction_response(ctx, |resp| {
-                *resp = result.into();
-                resp
-            }).await {
-                Ok(_) => (),
-                Err(e) => {
-                    log_error!("Erreur lors de la modification de l'interaction: {}", e);
-                }
+        let msg = 'msg: {
+            if let Err(e) = self.check_owner_or_staff(ctx, app_cmd.0.guild_id, channel_id, app_cmd.0.user.id, "de retirer des membres du ticket").await {
+                break 'msg message::error(e);
             }
-        }
-        
-    }
-    #[message_component(custom_id="button_ticket_close")]
-    async fn on_button_ticket_close(&self, ctx: &Context, msg: &MessageComponentInteraction) {
-        if let Err(e) = self.ticket_close_channel(ctx, msg.channel_id, msg.member.as_ref()).await {
-            log_error!("{}", e);
-            msg.create_interaction_response(ctx, |resp|{
-                resp.interaction_response_data(|inter| inter.content(e))
-            }).await.unwrap_or_else(|e| {
-                log_error!("Erreur lors de l'envoi d'une réponse d'interaction: {}", e);
-            });
-        }
+            match self.ticket_owner(ctx, channel_id).await {
+                Ok(Some(owner)) if owner == personne => break 'msg message::error("Impossible de retirer le propriétaire du ticket. Transférez d'abord le ticket à un autre membre."),
+                Err(e) => break 'msg message::error(e),
+                _ => (),
+            }
+            let username = personne.to_user(ctx).await.map(|u| super::utils::user_fullname(&u)).unwrap_or_else(|_| personne.0.to_string());
+            match channel_id.delete_permission(ctx, PermissionOverwriteType::Member(personne)).await {
+                Ok(_) => message::success(format!("{} a bien été retirée.", username)),
+                Err(e) => message::error(format!("Impossible de retirer {}: {}", personne, e))
+            }
+        };
+        delay_resp.send_message(msg).await.unwrap_or_else(|e| {
+            log_error!("Erreur lors de l'envoi du message: {}", e);
+        });
     }
-}
-
-impl Tickets {
+    #[command(group="ticket", name="transferer", description="Transfère la propriété du ticket à une autre personne", name_en="transfer", description_en="Transfer the ownership of the ticket to someone else")]
+    async fn transfer_ticket(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
+        #[argument(name="qui", description="Nouveau propriétaire du ticket", name_en="who", description_en="New owner of the ticket")]
+        personne: UserId
+    ) {
+        use serenity::model::{
+            channel::{PermissionOverwrite, PermissionOverwriteType},
+            permissions::Permissions,
+        };
+        let channel_id = app_cmd.0.channel_id;
+        let delay_resp = match app_cmd.delayed_response(ctx, false).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                log_error!("Erreur lors de l'envoi du message: {}", e);
+                return;
+            }
+        };
+        let msg = 'msg: {
+            if let Err(e) = self.check_owner_or_staff(ctx, app_cmd.0.guild_id, channel_id, app_cmd.0.user.id, "de transférer le ticket").await {
+                break 'msg message::error(e);
+            }
+            let registered = match db_ctrl::ticket::is_ticket_exists(&self.database, channel_id).await {
+                Ok(v) => v,
+                Err(e) => break 'msg message::error(format!("Erreur de la base de données: {}", e)),
+            };
+            let result = if registered {
+                db_ctrl::ticket::transfer_ticket(&self.database, ctx, channel_id, personne).await.map(|_| ())
+            } else {
+                let category = match self.category_from_ticket(ctx, channel_id).await {
+                    Ok(category) => category,
+                    Err(e) => break 'msg message::error(e),
+                };
+                db_ctrl::ticket::create_ticket(ctx, &self.database, category, channel_id, personne).await.map(|_| ())
+            };
+            if let Err(e) = result {
+                break 'msg message::error(format!("Erreur lors du transfert du ticket: {}", e));
+            }
+            if let Err(e) = channel_id.create_permission(ctx, &PermissionOverwrite {
+                allow: Permissions::VIEW_CHANNEL,
+                deny: Default::default(),
+                kind: PermissionOverwriteType::Member(personne),
+            }).await {
+                break 'msg message::error(format!("Le ticket a été transféré mais {} n'a pas pu y être ajouté: {}", personne, e));
+            }
+            message::success(format!("Le ticket appartient maintenant à <@{}>.", personne))
+        };
+        delay_resp.send_message(msg).await.unwrap_or_else(|e| {
+            log_error!("Erreur lors de l'envoi du message: {}", e);
+        });
+    }
+    #[command(group="ticket", name="membres", description="Liste les personnes ayant accès au ticket", name_en="members", description_en="List the people who can access the ticket")]
+    async fn list_members(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>) {
+        use serenity::model::{channel::{Channel, PermissionOverwriteType}, permissions::Permissions};
+        let channel_id = app_cmd.0.channel_id;
+        let delay_resp = match app_cmd.delayed_response(ctx, true).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                log_error!("Erreur lors de l'envoi du message: {}", e);
+                return;
+            }
+        };
+        let msg = 'msg: {
+            if let Err(e) = self.check_owner_or_staff(ctx, app_cmd.0.guild_id, channel_id, app_cmd.0.user.id, "de voir les membres du ticket").await {
+                break 'msg message::error(e);
+            }
+            let channel = match channel_id.to_channel(ctx).await {
+                Ok(Channel::Guild(channel)) => channel,
+                Ok(_) => break 'msg message::error("Ce salon n'est pas un ticket"),
+                Err(e) => break 'msg message::error(format!("Une erreur s'est produite lors de la récupération du channel: {}", e)),
+            };
+            let owner = match self.ticket_owner(ctx, channel_id).await {
+                Ok(owner) => owner,
+                Err(e) => break 'msg message::error(e),
+            };
+            let staff_role = channel.guild_id.roles(ctx).await.ok()
+                .and_then(|roles| roles.into_iter().find(|(_, role)| role.name == "staff").map(|(id, _)| id));
+            let mut lines = Vec::new();
+            if let Some(owner) = owner {
+                lines.push(format!("<@{}> : propriétaire", owner));
+            }
+            for overwrite in &channel.permission_overwrites {
+                if !overwrite.allow.contains(Permissions::VIEW_CHANNEL) {
+                    continue;
+                }
+                match overwrite.kind {
+                    PermissionOverwriteType::Member(user_id) if Some(user_id) == owner => (),
+                    PermissionOverwriteType::Member(user_id) => lines.push(format!("<@{}> : ajouté", user_id)),
+                    PermissionOverwriteType::Role(role_id) if Some(role_id) == staff_role => lines.push(format!("<@&{}> : staff", role_id)),
+                    PermissionOverwriteType::Role(role_id) => lines.push(format!("<@&{}> : rôle", role_id)),
+                    _ => (),
+                }
+            }
+            let mut msg = message::Message::new();
+            msg.add_embed(|embed| {
+                embed
+                    .color(message::COLOR_INFO)
+                    .title("Membres du ticket")
+                    .description(lines.join("\n"))
+            });
+            msg
+        };
+        delay_resp.send_message(msg).await.unwrap_or_else(|e| {
+            log_error!("Erreur lors de l'envoi du message: {}", e);
+        });
+    }
+    #[command(group="ticket", name="deplacer", description="Déplace le ticket dans une autre catégorie", name_en="move", description_en="Move the ticket to another category")]
+    async fn move_ticket(&self, ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>,
+        #[argument(name="categorie", description="Nom de la nouvelle catégorie", autocomplete="complete_category", name_en="category", description_en="Name of the new category")]
+        category_name: String
+    ) {
+        use serenity::model::channel::{Channel, PermissionOverwriteType};
+        let channel_id = app_cmd.0.channel_id;
+        let delay_resp = match app_cmd.delayed_response(ctx, false).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                log_error!("Erreur lors de l'envoi du message: {}", e);
+                return;
+            }
+        };
+        let msg = 'msg: {
+            let guild_id = match app_cmd.0.guild_id {
+                Some(guild_id) => guild_id,
+                None => break 'msg message::error("Cette commande n'est pas disponible dans un DM"),
+            };
+            match self.is_a_ticket(ctx, channel_id).await {
+                Ok(true) => (),
+                Ok(false) => break 'msg message::error("Ce salon n'est pas un ticket"),
+                Err(e) => break 'msg message::error(e),
+            }
+            match Self::is_staff(ctx, guild_id, app_cmd.0.user.id).await {
+                Ok(true) => (),
+                Ok(false) => break 'msg message::error("Vous n'avez pas la permission de déplacer le ticket."),
+                Err(e) => break 'msg message::error(e),
+            }
+            let category = match self.category_by_name(guild_id, &category_name).await {
+                Ok(category) => category,
+                Err(e) => break 'msg message::error(e),
+            };
+            let owner = match self.ticket_owner(ctx, channel_id).await {
+                Ok(Some(owner)) => owner,
+                Ok(None) => break 'msg message::error("Impossible de retrouver le propriétaire du ticket"),
+                Err(e) => break 'msg message::error(e),
+            };
+            let channel = match channel_id.to_channel(ctx).await {
+                Ok(Channel::Guild(channel)) => channel,
+                Ok(_) => break 'msg message::error("Ce salon n'est pas un ticket"),
+                Err(e) => break 'msg message::error(format!("Une erreur s'est produite lors de la récupération du channel: {}", e)),
+            };
+            let mut permissions = match Self::ticket_role_permissions(ctx, guild_id).await {
+                Ok(permissions) => permissions,
+                Err(e) => break 'msg message::error(e),
+            };
+            // Les membres ajoutés au ticket gardent leur accès
+            permissions.extend(channel.permission_overwrites.into_iter()
+                .filter(|overwrite| matches!(overwrite.kind, PermissionOverwriteType::Member(_))));
+            let name = Self::ticket_channel_name(ctx, owner, &category).await;
+            if let Err(e) = channel_id.edit(ctx, |chan| {
+                chan
+                    .name(name)
+                    .category(ChannelId(category.discord_category_id as u64))
+                    .permissions(permissions)
+            }).await {
+                break 'msg message::error(format!("Erreur lors du déplacement du salon: {}", e));
+            }
+            let registered = match db_ctrl::ticket::is_ticket_exists(&self.database, channel_id).await {
+                Ok(v) => v,
+                Err(e) => break 'msg message::error(format!("Erreur de la base de données: {}", e)),
+            };
+            let category_display = category.name.clone();
+            let inactivity_timeout = category.inactivity_timeout;
+            let result = if registered {
+                db_ctrl::ticket::change_ticket_category(&self.database, channel_id, category.id).await
+            } else {
+                db_ctrl::ticket::create_ticket(ctx, &self.database, category, channel_id, owner).await.map(|_| ())
+            };
+            if let Err(e) = result {
+                break 'msg message::error(format!("Erreur lors de l'enregistrement de la nouvelle catégorie: {}", e));
+            }
+            if let Err(e) = self.cancel_inactivity(channel_id).await {
+                log_warn!("Erreur lors de l'annulation de la fermeture automatique du ticket: {}", e);
+            }
+            self.start_inactivity(channel_id, inactivity_timeout).await;
+            message::success(format!("Le ticket a été déplacé dans la catégorie {}.", category_display))
+        };
+        delay_resp.send_message(msg).await.unwrap_or_else(|e| {
+            log_error!("Erreur lors de l'envoi du message: {}", e);
+        });
+    }
+    #[message_component(custom_id="menu_ticket_create")]
+    async fn on_menu_ticket_create(&self, ctx: &Context, msg: &MessageComponentInteraction) {
+        use serenity::model::application::interaction::InteractionResponseType;
+        let guild_id = match msg.guild_id {
+            Some(guild_id) => guild_id,
+            None => {
+                log_error!("Le menu n'est pas dans un serveur");
+                return;
+            }
+        };
+        let category = {
+            let category_name = match msg.data.values.iter().next() {
+                Some(value) => value.clone(),
+                None => {
+                    log_error!("Aucun item n'a été sélectionné");
+                    return;
+                }
+            };
+            match self.category_by_name(guild_id, &category_name).await {
+                Ok(category) if category.hidden => {
+                    log_error!("{}: la catégorie est cachée du menu", category_name);
+                    return;
+                },
+                Ok(category) => category,
+                Err(e) => {
+                    log_error!("{}: {}", category_name, e);
+                    return;
+                }
+            }
+        };
+        let questions = match category.questions().all(self.database.as_ref()).await {
+            Ok(questions) => questions,
+            Err(e) => {
+                log_error!("Erreur lors de la récupération du formulaire de la catégorie {}: {}", category.name, e);
+                Vec::new()
+            }
+        };
+        if !questions.is_empty() {
+            // Inutile de faire remplir le formulaire si le ticket sera refusé
+            if let Err(e) = self.check_ticket_limits(guild_id, msg.user.id, &category).await {
+                if let Err(e) = msg.create_interaction_response(ctx, |resp| {
+                    *resp = message::error(e).set_ephemeral(true).into();
+                    resp
+                }).await {
+                    log_error!("Erreur lors de l'envoi de la réponse: {}", e);
+                }
+                if let Err(e) = self.update_guild_menu(ctx, guild_id).await {
+                    log_error!("Erreur lors de la mise à jour du menu: {}", e);
+                }
+                return;
+            }
+            // Le ticket sera créé à la validation du formulaire
+            if let Err(e) = msg.create_interaction_response(ctx, |resp| {
+                *resp = questions_to_modal(&category, &questions).into();
+                resp
+            }).await {
+                log_error!("Erreur lors de l'envoi du formulaire: {}", e);
+            }
+            if let Err(e) = self.update_guild_menu(ctx, guild_id).await {
+                log_error!("Erreur lors de la mise à jour du menu: {}", e);
+            }
+            return;
+        }
+        let ok = match msg.create_interaction_response(ctx, |resp| {
+            resp.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|data| {
+                    data.ephemeral(true)
+                })
+        }).await {
+            Ok(_) => true,
+            Err(e) => {
+                log_warn!("Erreur lors de la création de l'interaction: {}", e);
+                false
+            }
+        };
+        if let Err(e) = self.update_guild_menu(ctx, guild_id).await {
+            log_error!("Erreur lors de la mise à jour du menu: {}", e);
+        }
+        
+        let result = self.ticket_create_message(ctx, guild_id, msg.user.id, category, Vec::new()).await;
+        if ok {
+            match msg.edit_original_interaction_response(ctx, |resp| {
+                *resp = result.into();
+                resp
+            }).await {
+                Ok(_) => (),
+                Err(e) => {
+                    log_error!("Erreur lors de la modification de l'interaction: {}", e);
+                }
+            }
+        }
+        
+    }
+    #[modal(custom_id="modal_ticket_close")]
+    async fn on_modal_ticket_close(&self, ctx: &Context, modal: ModalSubmitEmbed<'_>, reason: Option<String>) {
+        let resp = match modal.delayed_response(ctx, true).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                log_warn!("Erreur lors de la création de l'interaction: {}", e);
+                return;
+            }
+        };
+        let reason = reason.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        if let Err(e) = self.ticket_close_channel(ctx, modal.0.channel_id, modal.0.member.as_ref(), reason).await {
+            log_error!("{}", e);
+            if let Err(e) = resp.send_message(message::error(e)).await {
+                log_error!("Erreur lors de la modification de l'interaction: {}", e);
+            }
+        }
+    }
+    #[modal(custom_id="modal_ticket_create:{category_id}")]
+    async fn on_modal_ticket_create(&self, ctx: &Context, modal: ModalSubmitEmbed<'_>, category_id: IDType) {
+        use serenity::model::application::interaction::InteractionResponseType;
+        if let Err(e) = modal.0.create_interaction_response(ctx, |resp| {
+            resp.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|data| {
+                    data.ephemeral(true)
+                })
+        }).await {
+            log_warn!("Erreur lors de la création de l'interaction: {}", e);
+            return;
+        }
+        let result = 'msg: {
+            let guild_id = match modal.0.guild_id {
+                Some(guild_id) => guild_id,
+                None => break 'msg message::error("Le formulaire n'est pas dans un serveur"),
+            };
+            let category = match self.selectable_category(guild_id, category_id).await {
+                Ok(category) => category,
+                Err(e) => break 'msg message::error(e),
+            };
+            let questions = match category.questions().all(self.database.as_ref()).await {
+                Ok(questions) => questions,
+                Err(e) => break 'msg message::error(format!("Erreur lors de la récupération du formulaire: {}", e)),
+            };
+            let answers = questions.into_iter()
+                .map(|q| {
+                    let value = modal.get_input(&q.id.to_string()).unwrap_or_default().to_string();
+                    (q.label, value)
+                })
+                .collect::<Vec<_>>();
+            self.ticket_create_message(ctx, guild_id, modal.0.user.id, category, answers).await
+        };
+        if let Err(e) = modal.0.edit_original_interaction_response(ctx, |resp| {
+            *resp = result.into();
+            resp
+        }).await {
+            log_error!("Erreur lors de la modification de l'interaction: {}", e);
+        }
+    }
+    #[modal(custom_id="modal_ticket_rating:{channel_id}")]
+    async fn on_modal_ticket_rating(&self, ctx: &Context, modal: ModalSubmitEmbed<'_>, channel_id: u64, comment: Option<String>) {
+        let channel_id = ChannelId(channel_id);
+        let comment = comment.as_deref().map(str::trim).unwrap_or_default();
+        let msg = 'msg: {
+            match self.ticket_owner(ctx, channel_id).await {
+                Ok(Some(owner)) if owner == modal.0.user.id => (),
+                Ok(_) => break 'msg message::error("Seul le propriétaire du ticket peut le noter."),
+                Err(e) => break 'msg message::error(e),
+            }
+            if comment.is_empty() {
+                break 'msg message::success("Merci pour ton retour !");
+            }
+            match db_ctrl::ticket::save_rating_comment(&self.database, channel_id, comment.to_string()).await {
+                Ok(_) => message::success("Merci pour ton retour !"),
+                Err(e) => {
+                    log_error!("Erreur lors de l'enregistrement du commentaire: {}", e);
+                    message::error("Impossible d'enregistrer ton commentaire.")
+                }
+            }
+        };
+        modal.0.create_interaction_response(ctx, |resp| {
+            *resp = msg.into();
+            resp
+        }).await.unwrap_or_else(|e| {
+            log_error!("Erreur lors de l'envoi d'une réponse d'interaction: {}", e);
+        });
+    }
+    #[event(MessageCreate)]
+    async fn on_message_create(&self, ctx: &Context, msg_create: &MessageCreateEvent) {
+        if !self.is_ticket_channel(msg_create.message.channel_id).await {
+            return;
+        }
+        if let Err(e) = db_ctrl::discord::save_message(&self.database, &msg_create.message).await {
+            log_error!("Erreur lors de l'enregistrement d'un message du ticket: {}", e);
+        }
+        if msg_create.message.author.id == ctx.cache.current_user_id() {
+            return;
+        }
+        if let Err(e) = self.record_activity(msg_create.message.channel_id).await {
+            log_error!("Erreur lors de la mise à jour de l'inactivité du ticket: {}", e);
+        }
+        let is_staff = match (msg_create.message.guild_id, &msg_create.message.member) {
+            (Some(guild_id), Some(member)) => Self::staff_role_cached(ctx, guild_id)
+                .map(|role| member.roles.contains(&role))
+                .unwrap_or(false),
+            _ => false,
+        };
+        if is_staff {
+            let timestamp = msg_create.message.timestamp.unix_timestamp();
+            if let Err(e) = db_ctrl::ticket::record_staff_response(&self.database, msg_create.message.channel_id, msg_create.message.author.id, timestamp).await {
+                log_error!("Erreur lors de l'enregistrement de la réponse du staff: {}", e);
+            }
+        }
+    }
+    #[event(MessageUpdate)]
+    async fn on_message_update(&self, _ctx: &Context, msg_update: &MessageUpdateEvent) {
+        let content = match &msg_update.content {
+            Some(content) => content.clone(),
+            None => return,
+        };
+        let edited_at = msg_update.edited_timestamp
+            .map(|t| t.unix_timestamp())
+            .unwrap_or_else(|| chrono::Utc::now().timestamp());
+        if let Err(e) = db_ctrl::discord::update_message(&self.database, msg_update.id, content, edited_at).await {
+            log_error!("Erreur lors de l'enregistrement de la modification d'un message: {}", e);
+        }
+    }
+    #[event(MessageDelete)]
+    async fn on_message_delete(&self, _ctx: &Context, msg_delete: &MessageDeleteEvent) {
+        let deleted_at = chrono::Utc::now().timestamp();
+        if let Err(e) = db_ctrl::discord::mark_messages_deleted(&self.database, &[msg_delete.message_id], deleted_at).await {
+            log_error!("Erreur lors de l'enregistrement de la suppression d'un message: {}", e);
+        }
+    }
+    #[event(MessageDeleteBulk)]
+    async fn on_message_delete_bulk(&self, _ctx: &Context, msg_delete: &MessageDeleteBulkEvent) {
+        let deleted_at = chrono::Utc::now().timestamp();
+        if let Err(e) = db_ctrl::discord::mark_messages_deleted(&self.database, &msg_delete.ids, deleted_at).await {
+            log_error!("Erreur lors de l'enregistrement de la suppression de messages: {}", e);
+        }
+    }
+    #[event(ReactionAdd)]
+    async fn on_reaction_add(&self, _ctx: &Context, reaction_add: &ReactionAddEvent) {
+        let reaction = &reaction_add.reaction;
+        if !self.is_ticket_channel(reaction.channel_id).await {
+            return;
+        }
+        if let Err(e) = db_ctrl::discord::add_reaction(&self.database, reaction.message_id, reaction.emoji.to_string()).await {
+            log_error!("Erreur lors de l'enregistrement d'une réaction: {}", e);
+        }
+    }
+    #[event(ReactionRemove)]
+    async fn on_reaction_remove(&self, _ctx: &Context, reaction_remove: &ReactionRemoveEvent) {
+        let reaction = &reaction_remove.reaction;
+        if !self.is_ticket_channel(reaction.channel_id).await {
+            return;
+        }
+        if let Err(e) = db_ctrl::discord::remove_reaction(&self.database, reaction.message_id, reaction.emoji.to_string()).await {
+            log_error!("Erreur lors de l'enregistrement du retrait d'une réaction: {}", e);
+        }
+    }
+    #[event(ReactionRemoveAll)]
+    async fn on_reaction_remove_all(&self, _ctx: &Context, reaction_remove: &ReactionRemoveAllEvent) {
+        if !self.is_ticket_channel(reaction_remove.channel_id).await {
+            return;
+        }
+        if let Err(e) = db_ctrl::discord::clear_reactions(&self.database, reaction_remove.message_id).await {
+            log_error!("Erreur lors de l'enregistrement du retrait des réactions: {}", e);
+        }
+    }
+    #[message_component(custom_id="button_ticket_keep_open")]
+    async fn on_button_ticket_keep_open(&self, ctx: &Context, msg: &MessageComponentInteraction) {
+        let result = match self.record_activity(msg.channel_id).await {
+            Ok(_) => message::success("Le ticket reste ouvert."),
+            Err(e) => {
+                log_error!("Erreur lors de la mise à jour de l'inactivité du ticket: {}", e);
+                message::error(e)
+            }
+        };
+        msg.create_interaction_response(ctx, |resp| {
+            *resp = result.into();
+            resp
+        }).await.unwrap_or_else(|e| {
+            log_error!("Erreur lors de l'envoi d'une réponse d'interaction: {}", e);
+        });
+        msg.message.delete(ctx).await.unwrap_or_else(|e| {
+            log_warn!("Erreur lors de la suppression de l'avertissement d'inactivité: {}", e);
+        });
+    }
+    #[message_component(custom_id="button_ticket_close")]
+    async fn on_button_ticket_close(&self, ctx: &Context, msg: &MessageComponentInteraction) {
+        if let Err(e) = msg.create_interaction_response(ctx, |resp| {
+            *resp = close_modal().into();
+            resp
+        }).await {
+            log_error!("Erreur lors de l'envoi du formulaire de fermeture: {}", e);
+        }
+    }
+    #[message_component(custom_id="button_ticket_rating:{channel_id}:{rating}")]
+    async fn on_button_ticket_rating(&self, ctx: &Context, component: &MessageComponentInteraction, channel_id: u64, rating: i32) {
+        let channel_id = ChannelId(channel_id);
+        let result = match self.ticket_owner(ctx, channel_id).await {
+            Ok(Some(owner)) if owner == component.user.id => db_ctrl::ticket::save_rating(&self.database, channel_id, rating).await
+                .map_err(|e| format!("Erreur lors de l'enregistrement de la note: {}", e)),
+            Ok(_) => Err("Seul le propriétaire du ticket peut le noter.".to_string()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            log_error!("{}", e);
+            component.create_interaction_response(ctx, |resp| {
+                *resp = message::error(e).into();
+                resp
+            }).await.unwrap_or_else(|e| {
+                log_error!("Erreur lors de l'envoi d'une réponse d'interaction: {}", e);
+            });
+            return;
+        }
+        let mut modal = message::Modal::new(Self::on_modal_ticket_rating_custom_id(&channel_id.0), "Merci pour ta note !");
+        modal.add_input("comment", "Un commentaire ? (facultatif)", |input| {
+            input
+                .style(serenity::model::application::component::InputTextStyle::Paragraph)
+                .max_length(1000)
+                .required(false)
+        });
+        if let Err(e) = component.create_interaction_response(ctx, |resp| {
+            *resp = modal.into();
+            resp
+        }).await {
+            log_error!("Erreur lors de l'envoi du formulaire de commentaire: {}", e);
+        }
+        let mut rating_msg = component.message.clone();
+        let stars = "★".repeat(rating as usize) + &"☆".repeat((rating::MAX_RATING - rating).max(0) as usize);
+        rating_msg.edit(ctx, |msg| {
+            msg.content(format!("Merci pour ta note : {}", stars))
+                .components(|comps| comps)
+        }).await.unwrap_or_else(|e| {
+            log_warn!("Erreur lors de la mise à jour du message de notation: {}", e);
+        });
+    }
+}
+
+impl Tickets {
+    /// Met à jour le menu de création de tickets de chaque serveur
     async fn update_menu(&self, ctx: &Context) -> serenity::Result<()> {
-        use std::ops::Deref;
-        let message_choice = self.data.read().await.deref().message_choice;
+        let guilds = self.data.read().await.message_choices.keys().copied().collect::<Vec<_>>();
+        for guild_id in guilds {
+            if let Err(e) = self.update_guild_menu(ctx, GuildId(guild_id)).await {
+                log_error!("Erreur lors de la mise à jour du menu du serveur {}: {}", guild_id, e);
+            }
+        }
+        Ok(())
+    }
+    /// Met à jour le menu de création de tickets d'un serveur
+    async fn update_guild_menu(&self, ctx: &Context, guild_id: GuildId) -> serenity::Result<()> {
+        let message_choice = self.data.read().await.message_choices.get(&guild_id.0).copied();
         let mut msg = match message_choice {
             Some(MessageChoice { channel_id, message_id }) => ChannelId(channel_id).message(ctx, message_id).await?,
             _ => return Ok(()),
         };
 
+        let db_guild_id = match guild_id.db_id() {
+            Ok(id) => id,
+            Err(e) => {
+                log_error!("Impossible de mettre à jour le menu du serveur {}: {}", guild_id, e);
+                return Ok(());
+            }
+        };
         let categories = match category::Entity::find()
+            .filter(category::Column::GuildId.eq(db_guild_id))
             .filter(category::Column::Hidden.eq(false))
             .all(&*self.database).await 
         {
             Ok(categories) => categories,
-            Err(_e) => {
-                todo!()
+            Err(e) => {
+                log_error!("Erreur lors de la récupération des catégories du serveur {}: {}", guild_id, e);
+                return Ok(());
             }
         };
         let options = categories.into_iter().map(|cat| cat.into()).collect::<Vec<_>>();
@@ -493,12 +1489,76 @@ impl Tickets {
                     action.create_select_menu(|menu| {
                         menu.options(|opts|{
                             opts.set_options(options)
-                        }).custom_id("menu_ticket_create")
+                        }).custom_id(Self::on_menu_ticket_create_custom_id())
                     })
                 })
             })
         }).await
     }
+    /// Assigne à leur serveur les données enregistrées avant la gestion de plusieurs serveurs
+    async fn migrate_to_guilds(&self, ctx: &Context, guilds: &[GuildId]) {
+        use serenity::model::channel::Channel;
+        let guild_of = |channel_id: u64| async move {
+            match ChannelId(channel_id).to_channel(ctx).await {
+                Ok(Channel::Guild(channel)) => Some(channel.guild_id),
+                Ok(Channel::Category(channel)) => Some(channel.guild_id),
+                _ if guilds.len() == 1 => Some(guilds[0]),
+                _ => None,
+            }
+        };
+        let legacy_choice = self.data.read().await.message_choice;
+        if let Some(choice) = legacy_choice {
+            match guild_of(choice.channel_id).await {
+                Some(guild_id) => {
+                    let mut data = self.data.write().await;
+                    data.message_choices.insert(guild_id.0, choice);
+                    data.message_choice = None;
+                },
+                None => log_warn!("Impossible de retrouver le serveur du menu de création de tickets"),
+            }
+        }
+        let categories = match db_ctrl::ticket::categories_without_guild(&self.database).await {
+            Ok(categories) => categories,
+            Err(e) => {
+                log_error!("Erreur lors de la récupération des catégories sans serveur: {}", e);
+                return;
+            }
+        };
+        for category in categories {
+            let name = category.name.clone();
+            match guild_of(category.discord_category_id as u64).await {
+                Some(guild_id) => if let Err(e) = db_ctrl::ticket::set_category_guild(&self.database, category, guild_id).await {
+                    log_error!("Erreur lors de l'assignation de la catégorie {} à son serveur: {}", name, e);
+                },
+                None => log_warn!("Impossible de retrouver le serveur de la catégorie {}", name),
+            }
+        }
+    }
+    async fn category_by_name(&self, guild_id: GuildId, name: &str) -> Result<category::Model, String> {
+        match category::Entity::find()
+            .filter(category::Column::GuildId.eq(guild_id.db_id().map_err(|e| e.to_string())?))
+            .filter(category::Column::Name.eq(name))
+            .one(&*self.database).await 
+        {
+            Ok(Some(cat)) => Ok(cat),
+            Ok(None) => Err("Cette catégorie n'existe pas".to_string()),
+            Err(err) => Err(format!("Erreur lors de la récupération de la catégorie dans la base de données: {}", err))
+        }
+    }
+    /// Catégorie pouvant être choisie dans le menu de création de tickets du serveur.
+    /// 
+    /// Les catégories cachées ou d'un autre serveur sont refusées, même si l'interaction a été forgée.
+    async fn selectable_category(&self, guild_id: GuildId, category_id: IDType) -> Result<category::Model, String> {
+        let category = match category::Entity::find_by_id(category_id).one(self.database.as_ref()).await {
+            Ok(Some(category)) => category,
+            Ok(None) => return Err("Cette catégorie n'existe plus".to_string()),
+            Err(e) => return Err(format!("Erreur lors de la récupération de la catégorie dans la base de données: {}", e)),
+        };
+        if category.guild_id != Some(guild_id.db_id().map_err(|e| e.to_string())?) || category.hidden {
+            return Err("Cette catégorie n'est pas disponible".to_string());
+        }
+        Ok(category)
+    }
     async fn send_error<D: std::fmt::Display>(ctx: &Context, app_cmd: ApplicationCommandEmbed<'_>, error: D) {
         log_error!("{}", error);
         let mut msg = message::Message::new();
@@ -525,6 +1585,15 @@ impl Tickets {
                 Err(e) => return Err(format!("Erreur lors de la récupération d'une catégorie: {}", e)),
                 _ => (),
             }
+            let guild_id = match ctx.cache.guild_channel(channel_id) {
+                Some(channel) => channel.guild_id,
+                None => match channel_id.to_channel(ctx).await.map(|c| c.guild()) {
+                    Ok(Some(channel)) => channel.guild_id,
+                    Ok(None) => return Err("Ce salon n'est pas dans un serveur".to_string()),
+                    Err(e) => return Err(format!("Une erreur s'est produite lors de la récupération du channel: {}", e)),
+                }
+            };
+            let in_guild = category::Column::GuildId.eq(guild_id.db_id().map_err(|e| e.to_string())?);
             // #2: Deduce the category from the channel prefix
             'skip_prefix: {
                 let channel_name = match channel_id.name(ctx).await {
@@ -533,7 +1602,7 @@ impl Tickets {
                 };
                 if let Some(pos_underscore) = channel_name.find(&['_', '-']) {
                     let prefix = &channel_name[..pos_underscore];
-                    match category::Entity::find().filter(category::Column::Prefix.eq(prefix)).one(&*self.database).await {
+                    match category::Entity::find().filter(in_guild.clone()).filter(category::Column::Prefix.eq(prefix)).one(&*self.database).await {
                         Ok(Some(cat)) => break 'result cat.id,
                         Err(e) => return Err(format!("Erreur lors de la récupération d'une catégorie: {}", e)),
                         _ => (),
@@ -541,17 +1610,17 @@ impl Tickets {
                 }
             }
             // #3: Get the the default category from the configuration
-            if let Some(ConfigTicket { default_category: Some(category_name) }) = &self.config {
-                match category::Entity::find().filter(category::Column::Name.eq(category_name)).column(category::Column::Id).one(&*self.database).await {
+            if let Some(TicketsSettings { default_category: Some(category_name), .. }) = self.config.as_ref().map(|c| c.guild(guild_id.0)) {
+                match category::Entity::find().filter(in_guild.clone()).filter(category::Column::Name.eq(category_name)).column(category::Column::Id).one(&*self.database).await {
                     Ok(Some(cat)) => break 'result cat.id,
                     Err(e) => return Err(format!("Erreur lors de la récupération d'une catégorie: {}", e)),
                     _ => (),
                 }
             }
             // #4: Finally, if none found, get the first category from the database
-            match category::Entity::find().column(category::Column::Id).one(&*self.database).await {
+            match category::Entity::find().filter(in_guild).column(category::Column::Id).one(&*self.database).await {
                 Ok(Some(cat)) => break 'result cat.id,
-                Ok(None) => return Err("Aucune catégorie dans la base de données".to_string()),
+                Ok(None) => return Err("Aucune catégorie pour ce serveur dans la base de données".to_string()),
                 Err(e) => return Err(format!("Erreur lors de la récupération d'une catégorie: {}", e)),
             }
         };
@@ -561,7 +1630,7 @@ impl Tickets {
             Err(e) => Err(format!("Erreur lors de la récupération d'une catégorie: {}", e))
         }
     }
-    async fn ticket_close_channel(&self, ctx: &Context, channel_id: ChannelId, member: Option<&Member>) -> Result<(), String> {
+    async fn ticket_close_channel(&self, ctx: &Context, channel_id: ChannelId, member: Option<&Member>, reason: Option<String>) -> Result<(), String> {
         match self.is_a_ticket(ctx, channel_id).await {
             Ok(true) => (),
             Ok(false) => return Err("Ce n'est pas un ticket".to_string()),
@@ -576,12 +1645,226 @@ impl Tickets {
             db_ctrl::ticket::create_ticket(ctx, &*self.database, category, channel_id, ctx.cache.current_user_id()).await
                 .map_err(|e| format!("Erreur lors de la création du ticket: {}", e))?;
         }
-        db_ctrl::ticket::archive_ticket(&*self.database, ctx, channel_id, closed_by).await
+        Self::archive_channel(ctx, &self.database, channel_id, closed_by, reason).await?;
+        if let Err(e) = self.cancel_inactivity(channel_id).await {
+            log_warn!("Erreur lors de l'annulation de la fermeture automatique du ticket: {}", e);
+        }
+        Ok(())
+    }
+    /// Archive un ticket enregistré dans la base de données, supprime son salon 
+    /// puis demande à son propriétaire de noter le ticket.
+    async fn archive_channel(ctx: &Context, database: &sea_orm::DatabaseConnection, channel_id: ChannelId, closed_by: UserId, reason: Option<String>) -> Result<(), String> {
+        let channel_name = channel_id.name(ctx).await.unwrap_or_else(|| channel_id.to_string());
+        db_ctrl::ticket::archive_ticket(database, ctx, channel_id, closed_by, reason.clone()).await
             .map_err(|e| format!("Erreur lors de l'archivage du ticket: {}", e))?;
         channel_id.delete(ctx).await
             .map_err(|e| format!("Erreur lors de la suppression du salon discord lié au ticket: {}", e))?;
+        if let Err(e) = Self::send_rating_request(ctx, database, channel_id, &channel_name, reason.as_deref()).await {
+            log_warn!("Erreur lors de l'envoi de la demande de notation du ticket: {}", e);
+        }
         Ok(())
     }
+    /// Envoie en message privé au propriétaire d'un ticket fermé une demande de notation de 1 à 5 étoiles
+    async fn send_rating_request(ctx: &Context, database: &sea_orm::DatabaseConnection, channel_id: ChannelId, channel_name: &str, reason: Option<&str>) -> Result<(), String> {
+        use serenity::model::application::component::ButtonStyle;
+        let ticket = match crate::db::model::ticket::Entity::find_by_id(channel_id.db_id().map_err(|e| e.to_string())?).one(database).await {
+            Ok(Some(ticket)) => ticket,
+            Ok(None) => return Ok(()),
+            Err(e) => return Err(format!("Erreur lors de la récupération du ticket: {}", e)),
+        };
+        let owner = UserId(ticket.opened_by as u64);
+        if owner == ctx.cache.current_user_id() {
+            return Ok(());
+        }
+        let dm = owner.create_dm_channel(ctx).await
+            .map_err(|e| format!("Impossible d'ouvrir une conversation privée: {}", e))?;
+        dm.send_message(ctx, |msg| {
+            msg.embed(|embed| {
+                embed
+                    .title(format!("Ticket {} fermé", channel_name))
+                    .description("Comment évaluerais-tu l'aide reçue ?")
+                    .color(message::COLOR_INFO);
+                if let Some(reason) = reason {
+                    embed.field("Raison de la fermeture", reason, false);
+                }
+                embed
+            });
+            msg.components(|comps| {
+                comps.create_action_row(|row| {
+                    for rating in rating::MIN_RATING..=rating::MAX_RATING {
+                        row.create_button(|button| {
+                            button
+                                .label(format!("{} ★", rating))
+                                .style(ButtonStyle::Secondary)
+                                .custom_id(Self::on_button_ticket_rating_custom_id(&channel_id.0, &rating))
+                        });
+                    }
+                    row
+                })
+            })
+        }).await.map_err(|e| format!("Erreur lors de l'envoi de la demande de notation: {}", e))?;
+        Ok(())
+    }
+    /// Démarre la suppression quotidienne des anciennes pièces jointes si une durée de rétention est configurée
+    async fn start_attachment_retention(&self) {
+        let days = match self.config.as_ref().and_then(|c| c.attachment_retention) {
+            Some(days) => days,
+            None => return,
+        };
+        let mut tasks = self.retention.write().await;
+        if tasks.is_some() {
+            return;
+        }
+        let registry = match RegistryFile::from_file("./data/tickets_retention.json").await {
+            Ok(registry) => registry,
+            Err(e) => {
+                log_error!("Impossible de charger la tâche de suppression des pièces jointes: {}", e);
+                return;
+            }
+        };
+        let is_scheduled = !registry.get_all().await.is_empty();
+        let mut new_tasks = retention::Manager::new(registry, retention::RetentionContext {
+            database: Arc::clone(&self.database),
+            retention: days as i64 * 24 * 3600,
+            manager: Arc::downgrade(&self.retention),
+        });
+        new_tasks.init().await;
+        if !is_scheduled {
+            if let Err(e) = new_tasks.add(retention::Prune, chrono::Utc::now().timestamp()).await {
+                log_error!("Impossible de planifier la suppression des pièces jointes: {}", e);
+            }
+        }
+        *tasks = Some(new_tasks);
+    }
+    fn inactivity_grace_period(&self) -> i64 {
+        let hours = self.config.as_ref().and_then(|c| c.inactivity_grace_period).unwrap_or(24);
+        hours as i64 * 3600
+    }
+    /// Planifie l'avertissement d'un ticket inactif, qui planifiera ensuite sa fermeture
+    async fn schedule_inactivity(&self, tasks: &mut inactivity::Manager, channel_id: ChannelId, timeout: i64) -> Result<(), String> {
+        let warn_at = chrono::Utc::now().timestamp() + timeout;
+        tasks.add(Inactivity { channel_id, timeout, step: InactivityStep::Warn }, warn_at).await?;
+        Ok(())
+    }
+    /// Planifie une étape de la fermeture automatique d'un ticket, depuis la tâche d'inactivité précédente
+    async fn schedule_inactivity_step(&self, inactivity: Inactivity, timestamp: i64) -> Result<(), String> {
+        let mut tasks = self.inactivity.write().await;
+        match tasks.as_mut() {
+            Some(tasks) => tasks.add(inactivity, timestamp).await.map(|_| ()),
+            None => Ok(()),
+        }
+    }
+    /// Planifie la fermeture automatique d'un ticket selon le délai d'inactivité de sa catégorie, en heures
+    async fn start_inactivity(&self, channel_id: ChannelId, inactivity_timeout: Option<i32>) {
+        let hours = match inactivity_timeout {
+            Some(hours) => hours,
+            None => return,
+        };
+        let mut tasks = self.inactivity.write().await;
+        if let Some(tasks) = tasks.as_mut() {
+            self.schedule_inactivity(tasks, channel_id, hours as i64 * 3600).await.unwrap_or_else(|e| {
+                log_warn!("Erreur lors de la planification de la fermeture automatique du ticket: {}", e);
+            });
+        }
+    }
+    /// Annule les tâches d'inactivité d'un ticket et retourne le délai d'inactivité de sa catégorie s'il y en avait
+    async fn cancel_inactivity(&self, channel_id: ChannelId) -> Result<Option<i64>, String> {
+        let found = {
+            let tasks = self.inactivity.read().await;
+            let registry = match tasks.as_ref() {
+                Some(tasks) => tasks.registry(),
+                None => return Ok(None),
+            };
+            let registry = registry.lock().await;
+            registry.find_all(|t| t.data.channel_id == channel_id).await
+        };
+        if found.is_empty() {
+            return Ok(None);
+        }
+        let mut tasks = self.inactivity.write().await;
+        let tasks = match tasks.as_mut() {
+            Some(tasks) => tasks,
+            None => return Ok(None),
+        };
+        for (id, _) in &found {
+            tasks.remove(*id).await?;
+        }
+        Ok(found.first().map(|(_, t)| t.data.timeout))
+    }
+    /// Repousse la fermeture automatique d'un ticket suite à une activité. 
+    /// 
+    /// Seule la date de l'activité est enregistrée : la tâche d'inactivité se replanifie à son exécution.
+    async fn record_activity(&self, channel_id: ChannelId) -> Result<(), String> {
+        db_ctrl::ticket::record_activity(&self.database, channel_id, chrono::Utc::now().timestamp()).await
+            .map_err(|e| format!("Erreur de la base de données: {}", e))
+    }
+    /// Planifie la fermeture automatique des tickets ouverts des serveurs qui n'en ont pas encore, 
+    /// par exemple ceux ouverts avant l'ajout d'un délai d'inactivité à leur catégorie
+    async fn schedule_open_tickets(&self, guilds: &[GuildId]) {
+        let guild_ids = guilds.iter().filter_map(|guild_id| guild_id.db_id().ok()).collect::<Vec<_>>();
+        let categories = match category::Entity::find()
+            .filter(category::Column::GuildId.is_in(guild_ids))
+            .filter(category::Column::InactivityTimeout.is_not_null())
+            .all(&*self.database).await 
+        {
+            Ok(categories) => categories,
+            Err(e) => {
+                log_error!("Erreur lors de la récupération des catégories: {}", e);
+                return;
+            }
+        };
+        for category in categories {
+            let tickets = match db_ctrl::ticket::opened_tickets_in_category(&self.database, category.id).await {
+                Ok(tickets) => tickets,
+                Err(e) => {
+                    log_error!("Erreur lors de la récupération des tickets de la catégorie {}: {}", category.name, e);
+                    continue;
+                }
+            };
+            for ticket in tickets {
+                let channel_id = ChannelId(ticket.channel_id as u64);
+                if !self.is_inactivity_scheduled(channel_id).await {
+                    self.start_inactivity(channel_id, category.inactivity_timeout).await;
+                }
+            }
+        }
+    }
+    /// Replanifie la fermeture automatique des tickets ouverts d'une catégorie après la modification de son délai d'inactivité
+    async fn reschedule_category_inactivity(&self, category: &category::Model) {
+        let tickets = match db_ctrl::ticket::opened_tickets_in_category(&self.database, category.id).await {
+            Ok(tickets) => tickets,
+            Err(e) => {
+                log_error!("Erreur lors de la récupération des tickets de la catégorie {}: {}", category.name, e);
+                return;
+            }
+        };
+        for ticket in tickets {
+            let channel_id = ChannelId(ticket.channel_id as u64);
+            if let Err(e) = self.cancel_inactivity(channel_id).await {
+                log_warn!("Erreur lors de l'annulation de la fermeture automatique du ticket: {}", e);
+            }
+            self.start_inactivity(channel_id, category.inactivity_timeout).await;
+        }
+    }
+    /// Vérifie si la fermeture automatique d'un ticket est planifiée
+    async fn is_inactivity_scheduled(&self, channel_id: ChannelId) -> bool {
+        let tasks = self.inactivity.read().await;
+        let registry = match tasks.as_ref() {
+            Some(tasks) => tasks.registry(),
+            None => return false,
+        };
+        let registry = registry.lock().await;
+        !registry.find_all(|t| t.data.channel_id == channel_id).await.is_empty()
+    }
+    /// Indique si le salon est un ticket enregistré, sans requête à Discord.
+    /// 
+    /// Une erreur de la base de données est journalisée et le salon n'est alors pas considéré comme un ticket.
+    async fn is_ticket_channel(&self, channel_id: ChannelId) -> bool {
+        db_ctrl::ticket::is_ticket_exists(&self.database, channel_id).await.unwrap_or_else(|e| {
+            log_error!("Erreur de la base de données: {}", e);
+            false
+        })
+    }
     async fn is_a_ticket(&self, ctx: &Context, channel_id: ChannelId) -> Result<bool, String> {
         use serenity::model::channel::Channel;
         let current_channel = match channel_id.to_channel(ctx).await {
@@ -601,16 +1884,48 @@ impl Tickets {
             Err(e) => Err(format!("{}", e))
         }
     }
-    async fn is_ticket_owner(ctx: &Context, channel: ChannelId, user_by: UserId) -> Result<bool, String> {
+    /// Vérifie que la commande est lancée dans un ticket par son propriétaire ou un membre du staff
+    async fn check_owner_or_staff(&self, ctx: &Context, guild_id: Option<GuildId>, channel_id: ChannelId, user_id: UserId, action: &str) -> Result<GuildId, String> {
+        let guild_id = match guild_id {
+            Some(guild_id) => guild_id,
+            None => return Err("Cette commande n'est pas disponible dans un DM".to_string()),
+        };
+        if !self.is_a_ticket(ctx, channel_id).await? {
+            return Err("Ce salon n'est pas un ticket".to_string());
+        }
+        if Self::is_staff(ctx, guild_id, user_id).await? {
+            return Ok(guild_id);
+        }
+        match self.ticket_owner(ctx, channel_id).await? {
+            Some(owner) if owner == user_id => Ok(guild_id),
+            _ => Err(format!("Vous n'avez pas la permission {}.", action)),
+        }
+    }
+    /// Propriétaire du ticket.
+    /// 
+    /// Si le ticket n'est pas enregistré dans la base de données, 
+    /// le propriétaire est la personne mentionnée dans le message épinglé de présentation.
+    async fn ticket_owner(&self, ctx: &Context, channel: ChannelId) -> Result<Option<UserId>, String> {
+        match crate::db::model::ticket::Entity::find_by_id(channel.db_id().map_err(|e| e.to_string())?).one(&*self.database).await {
+            Ok(Some(ticket)) => return Ok(Some(UserId(ticket.opened_by as u64))),
+            Ok(None) => (),
+            Err(e) => return Err(format!("Erreur lors de la récupération du ticket: {}", e)),
+        }
         let pins = match channel.pins(ctx).await {
             Ok(pins) => pins,
             Err(e) => return Err(format!("{}", e))
         };
         let first_message = match pins.last() {
             Some(pin) => pin,
-            None => return Ok(false)
+            None => return Ok(None)
         };
-        Ok(first_message.mentions.iter().find(|m| m.id == user_by).is_some())
+        Ok(first_message.mentions.iter().find(|m| !m.bot).map(|m| m.id))
+    }
+    /// Rôle staff du serveur, depuis le cache
+    fn staff_role_cached(ctx: &Context, guild_id: GuildId) -> Option<RoleId> {
+        ctx.cache.guild_field(guild_id, |guild| {
+            guild.roles.iter().find(|(_, role)| role.name == "staff").map(|(id, _)| *id)
+        }).flatten()
     }
     async fn is_staff(ctx: &Context, guild_id: GuildId, user_by: UserId) -> Result<bool, String> {
         let roles = match guild_id.roles(ctx).await {
@@ -627,13 +1942,156 @@ impl Tickets {
         };
         Ok(member.roles.into_iter().find(|role| role == &staff_role.0).is_some())
     }
-    async fn reset_message_choose(&self, new_ids: Option<MessageChoice>) {
-        self.data.write().await.message_choice = new_ids;
+    async fn reset_message_choose(&self, guild_id: GuildId, new_ids: Option<MessageChoice>) {
+        let mut data = self.data.write().await;
+        match new_ids {
+            Some(ids) => data.message_choices.insert(guild_id.0, ids),
+            None => data.message_choices.remove(&guild_id.0),
+        };
     }
-    async fn ticket_create(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, category: category::Model) -> Result<ChannelId, String> {
+    async fn ticket_create_message(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, category: category::Model, answers: Vec<(String, String)>) -> message::Message {
+        match self.ticket_create(ctx, guild_id, user_id, category, answers).await {
+            Ok(result) => message::success(format!("Ticket créé: <#{}>", result)),
+            Err(e) => {
+                log_error!("Erreur lors de la création du ticket: {}", e);
+                message::error(e)
+            }
+        }
+    }
+    /// Vérifie que le membre peut ouvrir un nouveau ticket dans la catégorie.
+    /// 
+    /// Les limites sont définies dans la configuration des tickets.
+    async fn check_ticket_limits(&self, guild_id: GuildId, user_id: UserId, category: &category::Model) -> Result<(), String> {
+        let config = match &self.config {
+            Some(config) => config.guild(guild_id.0),
+            None => return Ok(()),
+        };
+        let links = |tickets: &[&crate::db::model::ticket::Model]| tickets.iter()
+            .map(|t| format!("<#{}>", t.channel_id))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let opened = db_ctrl::ticket::opened_tickets(&self.database, guild_id, user_id).await
+            .map_err(|e| format!("Erreur lors de la récupération de vos tickets: {}", e))?;
+        let opened = opened.iter().collect::<Vec<_>>();
+        if let Some(max) = config.max_open_tickets {
+            if opened.len() >= max as usize {
+                return Err(format!("Vous avez déjà {} ticket(s) ouvert(s) : {}\nFermez-en un avant d'en créer un nouveau.", opened.len(), links(&opened)));
+            }
+        }
+        if let Some(max) = config.max_open_tickets_per_category {
+            let opened_category = opened.iter()
+                .filter(|t| t.category_id == category.id)
+                .copied()
+                .collect::<Vec<_>>();
+            if opened_category.len() >= max as usize {
+                return Err(format!("Vous avez déjà {} ticket(s) ouvert(s) dans la catégorie {} : {}", opened_category.len(), category.name, links(&opened_category)));
+            }
+        }
+        if let Some(cooldown) = config.creation_cooldown {
+            let last = db_ctrl::ticket::last_ticket(&self.database, guild_id, user_id).await
+                .map_err(|e| format!("Erreur lors de la récupération de vos tickets: {}", e))?;
+            if let Some(last) = last {
+                let created_at = ChannelId(last.channel_id as u64).created_at().unix_timestamp();
+                // Une horloge locale en retard sur Discord donnerait une durée négative
+                let elapsed = (chrono::Utc::now().timestamp() - created_at).max(0) as u64;
+                if elapsed < cooldown {
+                    let remaining = time_parser::format_duration(cooldown.saturating_sub(elapsed));
+                    return Err(match opened.iter().find(|t| t.channel_id == last.channel_id) {
+                        Some(t) => format!("Vous avez créé un ticket récemment : <#{}>\nVous pourrez en créer un nouveau dans {}.", t.channel_id, remaining),
+                        None => format!("Vous avez créé un ticket récemment. Vous pourrez en créer un nouveau dans {}.", remaining),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Crée un ticket si les limites du membre le permettent.
+    /// 
+    /// Les créations d'un même membre ne se chevauchent pas : sans cela, plusieurs clics rapides
+    /// passeraient tous la vérification des limites avant l'enregistrement du premier ticket.
+    async fn ticket_create(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, category: category::Model, answers: Vec<(String, String)>) -> Result<ChannelId, String> {
+        if !self.creating.lock().await.insert(user_id) {
+            return Err("Un ticket est déjà en cours de création, patientez quelques secondes.".to_string());
+        }
+        let result = self.ticket_create_checked(ctx, guild_id, user_id, category, answers).await;
+        self.creating.lock().await.remove(&user_id);
+        result
+    }
+    async fn ticket_create_checked(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, category: category::Model, answers: Vec<(String, String)>) -> Result<ChannelId, String> {
+        self.check_ticket_limits(guild_id, user_id, &category).await?;
+        let new_channel = self.ticket_channel_create(ctx, guild_id, user_id, &category).await?;
+        let content = format!("Hey <@{}>, par ici !\nDès que tu as fini avec le ticket, appuie sur le bouton \"Fermer le ticket\".", user_id.0);
+        let presentation = self.ticket_presentation(ctx, new_channel, content, &answers).await?;
+        self.ticket_register(ctx, category, new_channel, user_id, &presentation).await?;
+        db_ctrl::ticket::save_answers(&self.database, new_channel, answers).await
+            .map_err(|e| format!("Erreur lors de l'enregistrement du formulaire: {}", e))?;
+        Ok(new_channel)
+    }
+    /// Rouvre un ticket archivé dans un nouveau salon et y rejoue la conversation archivée
+    async fn ticket_reopen(&self, ctx: &Context, guild_id: GuildId, ticket: crate::db::model::ticket::Model, archive: crate::db::model::archive::Model) -> Result<ChannelId, String> {
+        let category = match category::Entity::find_by_id(ticket.category_id).one(&*self.database).await {
+            Ok(Some(cat)) => cat,
+            Ok(None) => return Err("La catégorie de ce ticket n'existe plus".to_string()),
+            Err(e) => return Err(format!("Erreur lors de la récupération d'une catégorie: {}", e)),
+        };
+        let old_channel = ChannelId(ticket.channel_id as u64);
+        let user_id = UserId(ticket.opened_by as u64);
+        let messages = db_ctrl::ticket::archived_messages(&self.database, old_channel).await
+            .map_err(|e| format!("Erreur lors de la récupération des messages archivés: {}", e))?;
+        let answers = db_ctrl::ticket::ticket_answers(&self.database, &ticket).await
+            .map_err(|e| format!("Erreur lors de la récupération du formulaire: {}", e))?;
+        let new_channel = self.ticket_channel_create(ctx, guild_id, user_id, &category).await?;
+        let content = format!("Hey <@{}>, ton ticket a été rouvert !\nL'ancien ticket ({}) est résumé ci-dessous.", user_id.0, old_channel.0);
+        let presentation = self.ticket_presentation(ctx, new_channel, content, &answers).await?;
+        self.ticket_register(ctx, category, new_channel, user_id, &presentation).await?;
+        for chunk in replay_archived_messages(&messages) {
+            new_channel.send_message(ctx, |msg| msg.embed(|embed| {
+                embed
+                    .title("Historique du ticket")
+                    .description(chunk)
+                    .color(message::COLOR_INFO)
+            })).await.map_err(|e| format!("Erreur lors de l'envoi de l'historique du ticket: {}", e))?;
+        }
+        db_ctrl::ticket::save_answers(&self.database, new_channel, answers).await
+            .map_err(|e| format!("Erreur lors de l'enregistrement du formulaire: {}", e))?;
+        db_ctrl::ticket::mark_reopened(&self.database, archive, new_channel).await
+            .map_err(|e| format!("Erreur lors de l'enregistrement de la réouverture: {}", e))?;
+        Ok(new_channel)
+    }
+    /// Crée le salon d'un ticket, visible uniquement par le membre et le staff
+    async fn ticket_channel_create(&self, ctx: &Context, guild_id: GuildId, user_id: UserId, category: &category::Model) -> Result<ChannelId, String> {
         use serenity::model::channel::{PermissionOverwrite, PermissionOverwriteType, ChannelType};
         use serenity::model::permissions::Permissions;
-        use serenity::model::application::component::ButtonStyle;
+        let mut permissions = Self::ticket_role_permissions(ctx, guild_id).await?;
+        permissions.insert(0, PermissionOverwrite {
+            allow: Permissions::VIEW_CHANNEL,
+            deny: Permissions::default(),
+            kind: PermissionOverwriteType::Member(user_id),
+        });
+        let name = Self::ticket_channel_name(ctx, user_id, category).await;
+        match guild_id.create_channel(ctx, |chan| {
+            chan
+                .name(name)
+                .kind(ChannelType::Text)
+                .category(category.discord_category_id as u64)
+                .permissions(permissions)
+        }).await {
+            Ok(chan) => Ok(chan.id),
+            Err(e) => Err(format!("Erreur lors de la création du ticket: {}", e))
+        }
+    }
+    /// Nom du salon d'un ticket: `<prefix>-<username>`
+    async fn ticket_channel_name(ctx: &Context, user_id: UserId, category: &category::Model) -> String {
+        let username = match user_id.to_user(ctx).await {
+            Ok(user) => user.name,
+            Err(_) => user_id.to_string()
+        };
+        format!("{}-{}", category.prefix, username)
+    }
+    /// Permissions des rôles sur un ticket: visible par le staff et caché pour les autres
+    async fn ticket_role_permissions(ctx: &Context, guild_id: GuildId) -> Result<Vec<serenity::model::channel::PermissionOverwrite>, String> {
+        use serenity::model::channel::{PermissionOverwrite, PermissionOverwriteType};
+        use serenity::model::permissions::Permissions;
         let role_staff = match guild_id.roles(ctx).await {
             Ok(roles) => {
                 let role = roles.iter().find(|(_, role)| role.name == "staff");
@@ -649,12 +2107,7 @@ impl Tickets {
         };
         let everyone = RoleId(guild_id.0);
         
-        let permissions = vec![
-            PermissionOverwrite {
-                allow: Permissions::VIEW_CHANNEL,
-                deny: Permissions::default(),
-                kind: PermissionOverwriteType::Member(user_id),
-            },
+        Ok(vec![
             PermissionOverwrite {
                 allow: Permissions::VIEW_CHANNEL,
                 deny: Permissions::default(),
@@ -665,24 +2118,29 @@ impl Tickets {
                 deny: Permissions::VIEW_CHANNEL,
                 kind: PermissionOverwriteType::Role(everyone),
             },
-        ];
-        let username = match user_id.to_user(ctx).await {
-            Ok(user) => user.name,
-            Err(_) => user_id.to_string()
-        };
-        let new_channel = match guild_id.create_channel(ctx, |chan| {
-            chan
-                .name(format!("{}-{}", category.prefix, username))
-                .kind(ChannelType::Text)
-                .category(category.discord_category_id as u64)
-                .permissions(permissions)
+        ])
+    }
+    /// Envoie et épingle le message de présentation du ticket avec le bouton de fermeture
+    async fn ticket_presentation(&self, ctx: &Context, channel_id: ChannelId, content: String, answers: &[(String, String)]) -> Result<serenity::model::channel::Message, String> {
+        use serenity::model::application::component::ButtonStyle;
+        let mut msg_prez = match channel_id.send_message(ctx, |msg| {
+            msg.content(content);
+            if !answers.is_empty() {
+                msg.embed(|embed| {
+                    embed
+                        .title("Formulaire")
+                        .color(message::COLOR_INFO);
+                    for (label, value) in answers {
+                        let value = if value.is_empty() { "*Sans réponse*".to_string() } else { value.chars().take(1024).collect() };
+                        embed.field(label, value, false);
+                    }
+                    embed
+                });
+            }
+            msg
         }).await {
-            Ok(chan) => chan,
-            Err(e) => return Err(format!("Erreur lors de la création du ticket: {}", e))
-        };
-        let mut msg_prez = match new_channel.say(ctx, format!("Hey <@{}>, par ici !\nDès que tu as fini avec le ticket, appuie sur le bouton \"Fermer le ticket\".", user_id.0)).await {
             Ok(msg) => msg,
-            Err(e) => return Err(format!("Erreur pendent l'envoi du message de presentation: {}\nLe salon a tout de même été créé: <#{}>", e, new_channel.id.0))
+            Err(e) => return Err(format!("Erreur pendent l'envoi du message de presentation: {}\nLe salon a tout de même été créé: <#{}>", e, channel_id.0))
         };
         msg_prez.edit(ctx, |msg| {
             msg.components(|cmps| {
@@ -691,7 +2149,7 @@ impl Tickets {
                         button
                             .label("Fermer le ticket")
                             .style(ButtonStyle::Danger)
-                            .custom_id("button_ticket_close")
+                            .custom_id(Self::on_button_ticket_close_custom_id())
                     })
                 })
             })
@@ -702,9 +2160,18 @@ impl Tickets {
         msg_prez.pin(ctx).await.unwrap_or_else(|e| {
             log_warn!("Erreur lors du pin du message de présentation: {}", e);
         });
-        db_ctrl::ticket::create_ticket(ctx, &self.database, category, new_channel.id, user_id).await
+        Ok(msg_prez)
+    }
+    /// Enregistre le ticket et son message de présentation dans la base de données et planifie sa fermeture automatique.
+    /// 
+    /// Les messages suivants sont enregistrés à leur réception.
+    async fn ticket_register(&self, ctx: &Context, category: category::Model, channel_id: ChannelId, user_id: UserId, presentation: &serenity::model::channel::Message) -> Result<(), String> {
+        let inactivity_timeout = category.inactivity_timeout;
+        db_ctrl::ticket::create_ticket(ctx, &self.database, category, channel_id, user_id).await
             .map_err(|e| format!("Erreur lors de la création du ticket: {}", e))?;
-
-        Ok(new_channel.id)
+        db_ctrl::discord::save_message(&self.database, presentation).await
+            .map_err(|e| format!("Erreur lors de l'enregistrement du message de présentation: {}", e))?;
+        self.start_inactivity(channel_id, inactivity_timeout).await;
+        Ok(())
     }
 }
\ No newline at end of file
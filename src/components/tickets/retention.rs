@@ -0,0 +1,61 @@
+//! Suppression des anciennes pièces jointes des tickets
+//! 
+//! Une tâche quotidienne supprime les fichiers des pièces jointes enregistrées depuis plus longtemps 
+//! que la durée de rétention, puis planifie la suivante. 
+//! Les informations des pièces jointes restent dans la base de données.
+
+use std::sync::{Arc, Weak};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serenity::async_trait;
+use tokio::sync::RwLock;
+
+use crate::{db::controller as db_ctrl, log_error, log_info};
+use super::super::utils::{task, registry_file::RegistryFile};
+
+/// Délai en secondes entre deux nettoyages
+pub const PRUNE_INTERVAL: i64 = 24 * 3600;
+
+pub type Manager = task::TaskManager<Prune, RegistryFile<Prune>, RetentionContext>;
+
+/// Données partagées par les tâches de nettoyage
+pub struct RetentionContext {
+    pub database: Arc<sea_orm::DatabaseConnection>,
+    /// Durée de rétention en secondes
+    pub retention: i64,
+    /// Gestionnaire des tâches, pour planifier le nettoyage suivant
+    pub manager: Weak<RwLock<Option<Manager>>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Prune;
+
+impl Prune {
+    /// Planifie le prochain nettoyage
+    async fn schedule_next(persistent: &RetentionContext) -> Result<(), String> {
+        let manager = match persistent.manager.upgrade() {
+            Some(manager) => manager,
+            None => return Ok(()),
+        };
+        let mut manager = manager.write().await;
+        match manager.as_mut() {
+            Some(manager) => manager.add(Prune, Utc::now().timestamp() + PRUNE_INTERVAL).await.map(|_| ()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl task::DataFunc for Prune {
+    type Persistent = RetentionContext;
+    async fn run(&self, persistent: &RetentionContext) -> Result<(), String> {
+        let saved_before = Utc::now().timestamp() - persistent.retention;
+        // Un échec n'empêche pas la tâche d'être retirée : le nettoyage suivant est déjà planifié
+        match db_ctrl::discord::prune_attachment_files(&persistent.database, saved_before).await {
+            Ok(count) => log_info!("{} fichier(s) de pièces jointes supprimé(s)", count),
+            Err(e) => log_error!("Erreur lors de la suppression des anciennes pièces jointes: {}", e),
+        }
+        Self::schedule_next(persistent).await
+    }
+}
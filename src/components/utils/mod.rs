@@ -1,6 +1,7 @@
 //! Utilities for the components.
 
 pub mod task;
+pub mod registry_file;
 pub mod time_parser;
 pub mod data;
 pub mod data2;
@@ -1,23 +1,29 @@
+//! Task registry saved in a JSON file.
+//! 
+//! Every change in the registry is written to the file, so tasks survive restarts.
+
 use std::collections::HashMap;
 use crate::log_error;
 
 use async_std::io::WriteExt;
 use tokio::sync::RwLock;
 
+use serde::{Serialize, de::DeserializeOwned};
 use serenity::{
     async_trait
 };
 
-use super::sanction::Sanction;
 use super::task;
 
-pub struct RegistryFile {
+pub struct RegistryFile<D: task::DataFunc + Clone> {
     path_file: std::path::PathBuf,
-    tasks: RwLock<HashMap<task::TaskID, task::Task<Sanction>>>,
+    tasks: RwLock<HashMap<task::TaskID, task::Task<D>>>,
     task_counter: RwLock<task::TaskID>
 }
 
-impl RegistryFile {
+impl<D> RegistryFile<D> where
+    D: task::DataFunc + Clone + Serialize + DeserializeOwned
+{
     pub async fn from_file(path_file: impl AsRef<std::path::Path>) -> Result<Self, String> {
         let res = Self {
             path_file: path_file.as_ref().to_path_buf(),
@@ -29,7 +35,7 @@ impl RegistryFile {
     }
     async fn save(&self) -> Result<(), String> {
         let log_error = |msg, e| {
-            let e = format!("modo::RegistryFile::save: {}: {}", msg, e);
+            let e = format!("RegistryFile::save: {}: {}", msg, e);
             log_error!("{}", e);
             e
         };
@@ -45,7 +51,7 @@ impl RegistryFile {
     }
     async fn load(&self) -> Result<(), String> {
         let log_error = |msg, e| {
-            let e = format!("modo::RegistryFile::load: {}: {}", msg, e);
+            let e = format!("RegistryFile::load: {}: {}", msg, e);
             log_error!("{}", e);
             e
         };
@@ -62,8 +68,10 @@ impl RegistryFile {
     }
 }
 #[async_trait]
-impl task::Registry for RegistryFile {
-    type Data = Sanction;
+impl<D> task::Registry for RegistryFile<D> where
+    D: task::DataFunc + Clone + Serialize + DeserializeOwned
+{
+    type Data = D;
     async fn register(&mut self, task: task::Task<Self::Data>) -> Result<task::TaskID, String> {
         let id = self.task_counter.read().await.clone();
         self.tasks.write().await.insert(id, task);
@@ -3,7 +3,7 @@
 //! Un fichier config.json est lu et utilisé pour configurer l'application.
 //! Celui ci contient des informations pour le client.
 
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 use serde::Deserialize;
 
 /// Configuration de l'application
@@ -18,12 +18,51 @@ pub struct Config {
     pub bot: Bot,
     pub tickets: Option<Tickets>,
     pub autobahn: Option<Autobahn>,
+    #[serde(default)]
+    pub backup: Backup,
     #[serde(skip)]
     filepath: PathBuf,
 }
 #[derive(Deserialize)]
 pub struct Tickets {
-    pub default_category: Option<String>
+    /// Réglages utilisés sur tous les serveurs
+    #[serde(flatten)]
+    pub settings: TicketsSettings,
+    /// Délai en heures entre l'avertissement d'inactivité et la fermeture automatique d'un ticket
+    pub inactivity_grace_period: Option<u64>,
+    /// Taille maximale en octets des pièces jointes enregistrées. Les plus grosses ne gardent que leurs informations.
+    pub max_attachment_size: Option<u64>,
+    /// Durée en jours de conservation des fichiers des pièces jointes
+    pub attachment_retention: Option<u64>,
+    /// Réglages propres à un serveur, prioritaires sur les réglages généraux
+    #[serde(default)]
+    pub guilds: HashMap<u64, TicketsSettings>,
+}
+#[derive(Deserialize, Default, Clone)]
+pub struct TicketsSettings {
+    pub default_category: Option<String>,
+    /// Nombre maximum de tickets ouverts par membre, toutes catégories confondues
+    pub max_open_tickets: Option<u32>,
+    /// Nombre maximum de tickets ouverts par membre dans une même catégorie
+    pub max_open_tickets_per_category: Option<u32>,
+    /// Délai minimum en secondes entre deux créations de ticket d'un même membre
+    pub creation_cooldown: Option<u64>,
+}
+
+impl Tickets {
+    /// Réglages d'un serveur, complétés par les réglages généraux
+    pub fn guild(&self, guild_id: u64) -> TicketsSettings {
+        let general = &self.settings;
+        match self.guilds.get(&guild_id) {
+            Some(guild) => TicketsSettings {
+                default_category: guild.default_category.clone().or_else(|| general.default_category.clone()),
+                max_open_tickets: guild.max_open_tickets.or(general.max_open_tickets),
+                max_open_tickets_per_category: guild.max_open_tickets_per_category.or(general.max_open_tickets_per_category),
+                creation_cooldown: guild.creation_cooldown.or(general.creation_cooldown),
+            },
+            None => general.clone(),
+        }
+    }
 }
 
 #[derive(Deserialize, PartialEq, Eq)]
@@ -44,6 +83,27 @@ impl Autobahn {
     }
 }
 
+/// Sauvegardes de la base de données
+#[derive(Deserialize, Default, Clone)]
+pub struct Backup {
+    /// Dossier des sauvegardes, `data/backups` par défaut
+    pub directory: Option<String>,
+    /// Nombre de sauvegardes conservées, 7 par défaut
+    pub keep: Option<usize>,
+    /// Sauvegarde automatiquement la base de données chaque jour
+    #[serde(default)]
+    pub daily: bool,
+}
+
+impl Backup {
+    pub fn directory(&self) -> &str {
+        self.directory.as_deref().unwrap_or("data/backups")
+    }
+    pub fn keep(&self) -> usize {
+        self.keep.unwrap_or(7)
+    }
+}
+
 #[derive(Deserialize)]
 pub struct Bot {
     pub token: String,
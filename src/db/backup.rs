@@ -0,0 +1,124 @@
+//! Sauvegarde et restauration de la base de données
+//! 
+//! Les sauvegardes sont des copies cohérentes de la base SQLite réalisées à chaud avec `VACUUM INTO`. 
+//! Elles sont nommées `data-<date>.db` pour être triées de la plus ancienne à la plus récente.
+
+use std::path::{Path, PathBuf};
+
+use sea_orm::{ConnectionTrait, Database, DbBackend, DbConn, Statement};
+use crate::log_info;
+use super::{controller::{Error, FileError}, migration};
+
+const BACKUP_PREFIX: &str = "data-";
+const BACKUP_EXTENSION: &str = "db";
+
+/// Sauvegarde la base de données dans `directory` puis ne conserve que les `keep` sauvegardes les plus récentes.
+/// 
+/// Retourne le chemin de la sauvegarde.
+pub async fn backup(db: &DbConn, directory: &Path, keep: usize) -> Result<PathBuf, Error> {
+    if db.get_database_backend() != DbBackend::Sqlite {
+        return Err(Error::Custom("Les sauvegardes ne sont disponibles qu'avec une base de données SQLite".to_string()));
+    }
+    async_std::fs::create_dir_all(directory).await.map_err(|e| Error::File(FileError::Io(e)))?;
+    let filename = format!("{}{}.{}", BACKUP_PREFIX, chrono::Utc::now().format("%Y%m%d-%H%M%S"), BACKUP_EXTENSION);
+    let path = directory.join(filename);
+    let quoted_path = path.to_string_lossy().replace('\'', "''");
+    db.execute(Statement::from_string(DbBackend::Sqlite, format!("VACUUM INTO '{}'", quoted_path))).await.map_err(Error::SeaORM)?;
+    log_info!("Base de données sauvegardée dans {}", path.display());
+    let pruned = prune_backups(directory, keep).await.map_err(|e| Error::File(FileError::Io(e)))?;
+    if pruned > 0 {
+        log_info!("{} ancienne(s) sauvegarde(s) supprimée(s)", pruned);
+    }
+    Ok(path)
+}
+
+/// Sauvegardes présentes dans `directory`, de la plus ancienne à la plus récente
+pub fn list_backups(directory: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        let is_backup = path.file_name().map(|name| name.to_string_lossy().starts_with(BACKUP_PREFIX)).unwrap_or(false)
+            && path.extension().map(|ext| ext == BACKUP_EXTENSION).unwrap_or(false);
+        if is_backup {
+            backups.push(path);
+        }
+    }
+    backups.sort();
+    Ok(backups)
+}
+
+/// Supprime les sauvegardes les plus anciennes pour n'en garder que `keep`
+async fn prune_backups(directory: &Path, keep: usize) -> std::io::Result<usize> {
+    let backups = list_backups(directory)?;
+    let to_remove = backups.len().saturating_sub(keep);
+    for path in &backups[..to_remove] {
+        async_std::fs::remove_file(path).await?;
+    }
+    Ok(to_remove)
+}
+
+/// Chemin du fichier d'une base de données SQLite à partir de son URL
+fn sqlite_path(url: &str) -> Option<PathBuf> {
+    let path = url.strip_prefix("sqlite://").or_else(|| url.strip_prefix("sqlite:"))?;
+    let path = path.split('?').next().unwrap_or_default();
+    if path.is_empty() || path == ":memory:" {
+        return None;
+    }
+    Some(PathBuf::from(path))
+}
+
+/// Vérifie qu'un fichier est une sauvegarde intègre de la base de données du bot
+async fn validate_backup(file: &Path) -> Result<(), Error> {
+    if !file.is_file() {
+        return Err(Error::Custom(format!("Le fichier {} n'existe pas", file.display())));
+    }
+    let db = Database::connect(format!("sqlite:{}?mode=ro", file.display())).await.map_err(Error::SeaORM)?;
+    let integrity = db.query_one(Statement::from_string(DbBackend::Sqlite, "PRAGMA integrity_check".to_string())).await
+        .map_err(Error::SeaORM)?
+        .map(|row| row.try_get::<String>("", "integrity_check"))
+        .transpose()
+        .map_err(Error::SeaORM)?;
+    if integrity.as_deref() != Some("ok") {
+        return Err(Error::Custom(format!("La sauvegarde est corrompue: {}", integrity.unwrap_or_default())));
+    }
+    let version = migration::current_version(&db).await
+        .map_err(|_| Error::Custom("Le fichier n'est pas une base de données du bot".to_string()))?;
+    if version > migration::latest_version() {
+        return Err(Error::Custom(format!(
+            "La sauvegarde provient d'une version plus récente du bot (schéma {}, version connue {})", 
+            version, migration::latest_version()
+        )));
+    }
+    Ok(())
+}
+
+/// Remplace la base de données SQLite de `url` par la sauvegarde `file`.
+/// 
+/// La sauvegarde est vérifiée avant d'être copiée. L'ancienne base de données est conservée 
+/// à côté de la nouvelle avec l'extension `.<date>.bak`, dont le chemin est retourné.
+pub async fn restore(url: &str, file: &Path) -> Result<Option<PathBuf>, Error> {
+    let target = sqlite_path(url)
+        .ok_or_else(|| Error::Custom("La restauration n'est disponible qu'avec une base de données SQLite".to_string()))?;
+    validate_backup(file).await?;
+    let io_err = |e| Error::File(FileError::Io(e));
+    let previous = if target.exists() {
+        let mut previous = target.clone().into_os_string();
+        previous.push(format!(".{}.bak", chrono::Utc::now().format("%Y%m%d-%H%M%S")));
+        let previous = PathBuf::from(previous);
+        async_std::fs::rename(&target, &previous).await.map_err(io_err)?;
+        Some(previous)
+    } else {
+        None
+    };
+    for suffix in ["-wal", "-shm"] {
+        let mut journal = target.clone().into_os_string();
+        journal.push(suffix);
+        let journal = PathBuf::from(journal);
+        if journal.exists() {
+            async_std::fs::remove_file(&journal).await.map_err(io_err)?;
+        }
+    }
+    async_std::fs::copy(file, &target).await.map_err(io_err)?;
+    log_info!("Base de données restaurée depuis {}", file.display());
+    Ok(previous)
+}
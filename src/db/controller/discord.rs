@@ -1,17 +1,18 @@
 use crate::db::{
     model, 
     controller::{Error, FileError}, 
-    IDType
+    IDType, DbId
 };
 use crate::{log_info, log_error};
 use sea_orm::{entity::*, TransactionTrait, ConnectionTrait};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 const ATTACHMENTS_PATH: &str = "data/attachments";
 
 pub async fn create_channel_if_not_exists(db: &sea_orm::DbConn, ctx: &serenity::client::Context, channel_id: serenity::model::id::ChannelId) -> Result<IDType, Error> {
     log_info!("Creating channel");
-    let db_channel_id = channel_id.0.try_into().map_err(|_| Error::BadConversionId(channel_id.0))?;
+    let db_channel_id = channel_id.db_id()?;
     if let None = model::discord::Channel::find_by_id(db_channel_id).one(db).await.map_err(Error::SeaORM)? {
         let channel_name = channel_id.name(ctx).await.unwrap_or_default();
         model::discord::Channel::insert(model::discord::channel::ActiveModel{
@@ -22,37 +23,128 @@ pub async fn create_channel_if_not_exists(db: &sea_orm::DbConn, ctx: &serenity::
     Ok(db_channel_id)
 }
 
-pub async fn save_attachment_file(attachment: serenity::model::channel::Attachment) -> Result<(), FileError> {
+/// Taille maximale en octets des pièces jointes enregistrées sur le disque. 0 pour aucune limite.
+static MAX_ATTACHMENT_SIZE: AtomicU64 = AtomicU64::new(0);
+
+/// Définit la taille maximale en octets des pièces jointes enregistrées sur le disque
+pub fn set_max_attachment_size(max_size: Option<u64>) {
+    MAX_ATTACHMENT_SIZE.store(max_size.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Fichier d'une pièce jointe enregistré sur le disque
+pub struct AttachmentFile {
+    /// Empreinte SHA-256 du contenu
+    pub hash: String,
+    pub path: String,
+}
+
+/// Télécharge une pièce jointe et l'enregistre sous le nom de l'empreinte de son contenu.
+/// 
+/// Un fichier identique déjà enregistré n'est pas réécrit. 
+/// Retourne `None` si la pièce jointe dépasse la taille maximale.
+pub async fn save_attachment_file(attachment: &serenity::model::channel::Attachment) -> Result<Option<AttachmentFile>, FileError> {
     use std::path::Path;
-    let bytes = attachment.download().await.map_err(FileError::Serenity)?;
+    use sha2::Digest;
+    use cddio_core::attachment::{self, DownloadError};
+    let max_size = match MAX_ATTACHMENT_SIZE.load(Ordering::Relaxed) {
+        0 => u64::MAX,
+        max_size => max_size,
+    };
+    let bytes = match attachment::download(attachment, max_size).await {
+        Ok(bytes) => bytes,
+        Err(DownloadError::TooLarge { .. }) => return Ok(None),
+        Err(e) => return Err(FileError::Download(e)),
+    };
     let attachment_dir = Path::new(ATTACHMENTS_PATH);
     if !attachment_dir.exists() {
         async_std::fs::create_dir_all(attachment_dir).await.map_err(FileError::Io)?;
     }
+    let hash = format!("{:x}", sha2::Sha256::digest(&bytes));
     let file_extension = Path::new(&attachment.filename).extension().unwrap_or_default().to_string_lossy().to_owned();
-    let filename = format!("{}.{}", attachment.id.0, file_extension);
+    let filename = format!("{}.{}", hash, file_extension);
     let attachment_file = attachment_dir.join(filename);
-    async_std::fs::write(&attachment_file, bytes).await.map_err(FileError::Io)?;
-    Ok(())
+    if !attachment_file.exists() {
+        async_std::fs::write(&attachment_file, bytes).await.map_err(FileError::Io)?;
+    }
+    Ok(Some(AttachmentFile {
+        hash,
+        path: attachment_file.to_string_lossy().to_string(),
+    }))
+}
+
+/// Télécharge une pièce jointe, une erreur est journalisée et la pièce jointe est enregistrée sans fichier.
+async fn download_attachment(attachment: &serenity::model::channel::Attachment) -> Option<AttachmentFile> {
+    save_attachment_file(attachment).await.unwrap_or_else(|e| {
+        log_error!("Impossible d'enregistrer la pièce jointe {}: {}", attachment.id, e);
+        None
+    })
 }
 
-pub async fn save_attachment<C: ConnectionTrait>(connector: &C, ctx: &serenity::client::Context, message_id: serenity::model::id::MessageId, attachment: serenity::model::channel::Attachment) -> Result<(Option<tokio::task::JoinHandle<Result<(), FileError>>>, IDType), Error> {
-    let db_msg_id: IDType = message_id.0.try_into().map_err(|_| Error::BadConversionId(message_id.0))?;
-    let db_attachment_id: IDType = attachment.id.0.try_into().map_err(|_| Error::BadConversionId(attachment.id.0))?;
+/// Enregistre une pièce jointe dont le fichier a déjà été téléchargé.
+/// 
+/// Le téléchargement se fait en amont pour ne pas garder de transaction ouverte pendant celui-ci.
+pub async fn save_attachment<C: ConnectionTrait>(connector: &C, message_id: serenity::model::id::MessageId, attachment: serenity::model::channel::Attachment, file: Option<AttachmentFile>) -> Result<IDType, Error> {
+    let db_msg_id: IDType = message_id.db_id()?;
+    let db_attachment_id: IDType = attachment.id.db_id()?;
     if let None = model::discord::Attachment::find_by_id(db_attachment_id).one(connector).await.map_err(Error::SeaORM)? {
-        let attachment_url = attachment.url.clone();
-        let join_handle = tokio::spawn(async move {save_attachment_file(attachment).await});
+        let (hash, path) = match file {
+            Some(AttachmentFile { hash, path }) => (Some(hash), Some(path)),
+            None => (None, None),
+        };
         let active_model = model::discord::attachment::ActiveModel {
             id: sea_orm::ActiveValue::Set(db_attachment_id),
             message_id: sea_orm::ActiveValue::Set(db_msg_id),
-            url: sea_orm::ActiveValue::Set(attachment_url),
-            ..Default::default()
+            url: sea_orm::ActiveValue::Set(attachment.url),
+            filename: sea_orm::ActiveValue::Set(attachment.filename),
+            size: sea_orm::ActiveValue::Set(attachment.size as i64),
+            content_type: sea_orm::ActiveValue::Set(attachment.content_type),
+            hash: sea_orm::ActiveValue::Set(hash),
+            path: sea_orm::ActiveValue::Set(path),
+            saved_at: sea_orm::ActiveValue::Set(chrono::Utc::now().timestamp()),
         };
         model::discord::Attachment::insert(active_model).exec(connector).await.map_err(Error::SeaORM)?;
-        Ok((Some(join_handle), db_attachment_id))
-    } else {
-        Ok((None, db_attachment_id))
     }
+    Ok(db_attachment_id)
+}
+
+/// Supprime les fichiers des pièces jointes enregistrées avant `saved_before`.
+/// 
+/// Les informations des pièces jointes sont conservées, seul leur chemin est effacé. 
+/// Un fichier partagé avec une pièce jointe plus récente est conservé.
+/// Retourne le nombre de fichiers supprimés.
+pub async fn prune_attachment_files(db: &sea_orm::DbConn, saved_before: i64) -> Result<usize, Error> {
+    use sea_orm::{QueryFilter, QuerySelect, ColumnTrait, sea_query::Expr};
+    use model::discord::attachment::Column;
+    let paths_of = |old: bool| {
+        let saved_at = if old { Column::SavedAt.lt(saved_before) } else { Column::SavedAt.gte(saved_before) };
+        model::discord::Attachment::find()
+            .select_only()
+            .column(Column::Path)
+            .distinct()
+            .filter(Column::Path.is_not_null())
+            .filter(saved_at)
+            .into_tuple::<Option<String>>()
+    };
+    let old_paths = paths_of(true).all(db).await.map_err(Error::SeaORM)?;
+    let recent_paths = paths_of(false).all(db).await.map_err(Error::SeaORM)?
+        .into_iter()
+        .flatten()
+        .collect::<HashSet<_>>();
+    let mut pruned = Vec::new();
+    for path in old_paths.into_iter().flatten().filter(|path| !recent_paths.contains(path)) {
+        match async_std::fs::remove_file(&path).await {
+            Ok(()) => pruned.push(path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => pruned.push(path),
+            Err(e) => log_error!("Impossible de supprimer la pièce jointe {}: {}", path, e),
+        }
+    }
+    if !pruned.is_empty() {
+        model::discord::Attachment::update_many()
+            .col_expr(Column::Path, Expr::value(Option::<String>::None))
+            .filter(Column::Path.is_in(pruned.iter().cloned()))
+            .exec(db).await.map_err(Error::SeaORM)?;
+    }
+    Ok(pruned.len())
 }
 
 #[inline]
@@ -61,7 +153,7 @@ pub async fn save_user_from_id<C: ConnectionTrait>(connector: &C, ctx: &serenity
     save_user(connector, user).await
 }
 pub async fn save_user<C: ConnectionTrait>(connector: &C, user: serenity::model::user::User) -> Result<IDType, Error> {
-    let db_user_id: IDType = user.id.0.try_into().map_err(|_| Error::BadConversionId(user.id.0))?;
+    let db_user_id: IDType = user.id.db_id()?;
     if let None = model::discord::User::find_by_id(db_user_id).one(connector).await.map_err(Error::SeaORM)? {
         let active_model = model::discord::user::ActiveModel {
             id: sea_orm::ActiveValue::Set(db_user_id),
@@ -74,55 +166,257 @@ pub async fn save_user<C: ConnectionTrait>(connector: &C, user: serenity::model:
     Ok(db_user_id)
 }
 
+/// Modèle d'un message Discord à insérer dans la base de données
+fn message_active_model(msg: &serenity::model::channel::Message, message_id: IDType, channel_id: IDType, user_id: IDType, in_reply_to: Option<IDType>) -> model::discord::message::ActiveModel {
+    let to_json = |value: Result<String, serde_json::Error>| value.map_err(|e| {
+        log_error!("Impossible de convertir le contenu du message {} en JSON: {}", msg.id, e);
+    }).ok();
+    let embeds = if msg.embeds.is_empty() { None } else { to_json(serde_json::to_string(&msg.embeds)) };
+    let stickers = if msg.sticker_items.is_empty() { None } else { to_json(serde_json::to_string(&msg.sticker_items)) };
+    model::discord::message::ActiveModel {
+        id: sea_orm::ActiveValue::Set(message_id),
+        channel_id: sea_orm::ActiveValue::Set(channel_id),
+        user_id: sea_orm::ActiveValue::Set(user_id),
+        content: sea_orm::ActiveValue::Set(msg.content.clone()),
+        last_modified: sea_orm::ActiveValue::Set(msg.timestamp.unix_timestamp()),
+        in_reply_to: sea_orm::ActiveValue::Set(in_reply_to),
+        deleted_at: sea_orm::ActiveValue::Set(None),
+        kind: sea_orm::ActiveValue::Set(msg.kind.num() as i32),
+        embeds: sea_orm::ActiveValue::Set(embeds),
+        stickers: sea_orm::ActiveValue::Set(stickers),
+    }
+}
+
+/// Message auquel répond un message, s'il est enregistré
+async fn reply_target<C: ConnectionTrait>(connector: &C, msg: &serenity::model::channel::Message) -> Result<Option<IDType>, Error> {
+    let reply_id = match msg.message_reference.as_ref().and_then(|r| r.message_id) {
+        Some(reply_id) => reply_id.db_id()?,
+        None => return Ok(None),
+    };
+    let exists = model::discord::Message::find_by_id(reply_id).one(connector).await.map_err(Error::SeaORM)?.is_some();
+    Ok(if exists { Some(reply_id) } else { None })
+}
+
+/// Enregistre les réactions d'un message
+async fn save_reactions<C: ConnectionTrait>(connector: &C, message_id: IDType, reactions: &[serenity::model::channel::MessageReaction]) -> Result<(), Error> {
+    if reactions.is_empty() {
+        return Ok(());
+    }
+    model::discord::MessageReaction::insert_many(reactions.iter().map(|reaction| model::discord::message_reaction::ActiveModel {
+        message_id: sea_orm::ActiveValue::Set(message_id),
+        emoji: sea_orm::ActiveValue::Set(reaction.reaction_type.to_string()),
+        count: sea_orm::ActiveValue::Set(reaction.count as i32),
+        ..Default::default()
+    })).exec(connector).await.map_err(Error::SeaORM)?;
+    Ok(())
+}
+
+/// Enregistre les messages d'un salon qui ne sont pas encore dans la base de données.
+/// 
+/// Les messages étant enregistrés au fil de l'eau, seuls ceux postérieurs au dernier message enregistré 
+/// sont récupérés, par pages depuis ce message : ceux envoyés pendant une interruption du bot sont ainsi rattrapés.
 pub async fn save_channel(db: &sea_orm::DbConn, ctx: &serenity::client::Context, channel_id: serenity::model::id::ChannelId) -> Result<IDType, Error> {
-    use serenity::futures::StreamExt;
+    use serenity::model::id::MessageId;
+    /// Nombre maximal de messages par requête à Discord
+    const PAGE_SIZE: u64 = 100;
     
     let db_chan = create_channel_if_not_exists(db, ctx, channel_id).await?;
-    let txn = db.begin().await.map_err(Error::SeaORM)?;
-    let mut registered_users = HashSet::new();
-    let mut messages = channel_id.messages_iter(ctx).boxed();
-    while let Some(message_result) = messages.next().await {
-        let msg = match message_result {
-            Ok(m) => m,
+    // Un message est toujours postérieur à la création de son salon
+    let mut after = match last_stored_message_id(db, channel_id).await? {
+        Some(id) => MessageId(id as u64),
+        None => MessageId(channel_id.0),
+    };
+    let mut messages = Vec::new();
+    loop {
+        let mut page = match channel_id.messages(ctx, |m| m.after(after).limit(PAGE_SIZE)).await {
+            Ok(page) => page,
             Err(e) => {
                 log_error!("Error while saving ticket: {}", e);
-                continue;
-            } 
+                break;
+            }
         };
-        let user_id = msg.author.id.0;
+        page.sort_by_key(|m| m.id);
+        let is_last_page = (page.len() as u64) < PAGE_SIZE;
+        match page.last() {
+            Some(m) => after = m.id,
+            None => break,
+        }
+        messages.append(&mut page);
+        if is_last_page {
+            break;
+        }
+    }
+    let mut files = HashMap::new();
+    for attachment in messages.iter().flat_map(|msg| msg.attachments.iter()) {
+        files.insert(attachment.id, download_attachment(attachment).await);
+    }
+    let txn = db.begin().await.map_err(Error::SeaORM)?;
+    let mut registered_users = HashSet::new();
+    // Les messages sont enregistrés du plus ancien au plus récent pour que les réponses trouvent leur message d'origine
+    for msg in messages {
+        let user_id = msg.author.id.db_id()?;
         if !registered_users.contains(&user_id) {
-            if let None = model::discord::User::find_by_id(user_id as IDType).one(&txn).await.map_err(Error::SeaORM)? {
-                let user = msg.author;
-                let active_model = model::discord::user::ActiveModel {
-                    id: sea_orm::ActiveValue::Set(user.id.0 as IDType),
-                    name: sea_orm::ActiveValue::Set(format!("{}#{}", user.name, user.discriminator)),
-                    avatar: sea_orm::ActiveValue::Set(user.avatar_url().unwrap_or_default()),
-                };
-                let res = model::discord::User::insert(active_model).exec(&txn).await.map_err(Error::SeaORM)?;
-                log_info!("User {} added", res.last_insert_id);
-            }
+            save_user(&txn, msg.author.clone()).await?;
             registered_users.insert(user_id);
         }
-        let db_msg = {
-            let active_model = model::discord::message::ActiveModel {
-                id: sea_orm::ActiveValue::Set(msg.id.0 as IDType),
-                channel_id: sea_orm::ActiveValue::Set(db_chan),
-                user_id: sea_orm::ActiveValue::Set(user_id as IDType),
-                content: sea_orm::ActiveValue::Set(msg.content),
-                last_modified: sea_orm::ActiveValue::Set(msg.timestamp.unix_timestamp()),
-                in_reply_to: sea_orm::ActiveValue::NotSet,
-            };
-            let res = model::discord::Message::insert(active_model).exec(&txn).await.map_err(Error::SeaORM)?;
-            // log_info!("Message {} saved", res.last_insert_id);
-            res.last_insert_id
-        };
-        if !msg.attachments.is_empty() {
-            for attachment in msg.attachments {
-                save_attachment(&txn, ctx, msg.id, attachment).await?;
-            }
+        let in_reply_to = reply_target(&txn, &msg).await?;
+        let db_msg = msg.id.db_id()?;
+        let active_model = message_active_model(&msg, db_msg, db_chan, user_id, in_reply_to);
+        model::discord::Message::insert(active_model).exec(&txn).await.map_err(Error::SeaORM)?;
+        save_reactions(&txn, db_msg, &msg.reactions).await?;
+        for attachment in msg.attachments {
+            let file = files.remove(&attachment.id).flatten();
+            save_attachment(&txn, msg.id, attachment, file).await?;
         }
     }
     txn.commit().await.map_err(Error::SeaORM)?;
     log_info!("Channel {} saved", db_chan);
     Ok(db_chan)
+}
+
+/// Identifiant du dernier message d'un salon déjà enregistré
+async fn last_stored_message_id(db: &sea_orm::DbConn, channel_id: serenity::model::id::ChannelId) -> Result<Option<IDType>, Error> {
+    use sea_orm::{QueryFilter, QuerySelect, ColumnTrait};
+    use model::discord::message::Column;
+    model::discord::Message::find()
+        .select_only()
+        .column_as(Column::Id.max(), "id")
+        .filter(Column::ChannelId.eq(channel_id.db_id()?))
+        .into_tuple::<Option<IDType>>()
+        .one(db).await
+        .map(Option::flatten)
+        .map_err(Error::SeaORM)
+}
+
+/// Enregistre un nouveau message et ses pièces jointes.
+/// 
+/// Le salon du message doit déjà être enregistré. 
+/// Les pièces jointes sont téléchargées puis enregistrées dans une tâche séparée.
+pub async fn save_message(db: &sea_orm::DbConn, msg: &serenity::model::channel::Message) -> Result<IDType, Error> {
+    let db_msg_id: IDType = msg.id.db_id()?;
+    if model::discord::Message::find_by_id(db_msg_id).one(db).await.map_err(Error::SeaORM)?.is_some() {
+        return Ok(db_msg_id);
+    }
+    let user_id = save_user(db, msg.author.clone()).await?;
+    let in_reply_to = reply_target(db, msg).await?;
+    let active_model = message_active_model(msg, db_msg_id, msg.channel_id.db_id()?, user_id, in_reply_to);
+    model::discord::Message::insert(active_model).exec(db).await.map_err(Error::SeaORM)?;
+    save_reactions(db, db_msg_id, &msg.reactions).await?;
+    if !msg.attachments.is_empty() {
+        let db = db.clone();
+        let message_id = msg.id;
+        let attachments = msg.attachments.clone();
+        tokio::spawn(async move {
+            for attachment in attachments {
+                let file = download_attachment(&attachment).await;
+                if let Err(e) = save_attachment(&db, message_id, attachment, file).await {
+                    log_error!("Impossible d'enregistrer les pièces jointes du message {}: {}", message_id, e);
+                }
+            }
+        });
+    }
+    Ok(db_msg_id)
+}
+
+/// Met à jour le contenu d'un message enregistré en conservant l'ancienne version
+pub async fn update_message(
+    db: &sea_orm::DbConn, 
+    message_id: serenity::model::id::MessageId, 
+    content: String, 
+    edited_at: i64
+) -> Result<(), Error> {
+    let message = match model::discord::Message::find_by_id(message_id.db_id()?).one(db).await.map_err(Error::SeaORM)? {
+        Some(message) => message,
+        None => return Ok(()),
+    };
+    if message.content == content {
+        return Ok(());
+    }
+    let txn = db.begin().await.map_err(Error::SeaORM)?;
+    model::discord::MessageEdit::insert(model::discord::message_edit::ActiveModel {
+        message_id: sea_orm::ActiveValue::Set(message.id),
+        content: sea_orm::ActiveValue::Set(message.content.clone()),
+        edited_at: sea_orm::ActiveValue::Set(edited_at),
+        ..Default::default()
+    }).exec(&txn).await.map_err(Error::SeaORM)?;
+    let mut active_model: model::discord::message::ActiveModel = message.into();
+    active_model.content = sea_orm::ActiveValue::Set(content);
+    active_model.last_modified = sea_orm::ActiveValue::Set(edited_at);
+    model::discord::Message::update(active_model).exec(&txn).await.map_err(Error::SeaORM)?;
+    txn.commit().await.map_err(Error::SeaORM)?;
+    Ok(())
+}
+
+/// Marque des messages enregistrés comme supprimés
+pub async fn mark_messages_deleted(db: &sea_orm::DbConn, message_ids: &[serenity::model::id::MessageId], deleted_at: i64) -> Result<(), Error> {
+    use sea_orm::{QueryFilter, ColumnTrait, sea_query::Expr};
+    model::discord::Message::update_many()
+        .col_expr(model::discord::message::Column::DeletedAt, Expr::value(deleted_at))
+        .filter(model::discord::message::Column::Id.is_in(message_ids.iter().map(DbId::db_id).collect::<Result<Vec<_>, _>>()?))
+        .filter(model::discord::message::Column::DeletedAt.is_null())
+        .exec(db).await.map_err(Error::SeaORM)?;
+    Ok(())
+}
+
+/// Ajoute une réaction à un message enregistré
+pub async fn add_reaction(db: &sea_orm::DbConn, message_id: serenity::model::id::MessageId, emoji: String) -> Result<(), Error> {
+    use sea_orm::{QueryFilter, ColumnTrait};
+    use model::discord::message_reaction::{Column, ActiveModel};
+    let db_msg_id = message_id.db_id()?;
+    if model::discord::Message::find_by_id(db_msg_id).one(db).await.map_err(Error::SeaORM)?.is_none() {
+        return Ok(());
+    }
+    let reaction = model::discord::MessageReaction::find()
+        .filter(Column::MessageId.eq(db_msg_id))
+        .filter(Column::Emoji.eq(emoji.as_str()))
+        .one(db).await.map_err(Error::SeaORM)?;
+    match reaction {
+        Some(reaction) => {
+            let count = reaction.count + 1;
+            let mut active_model: ActiveModel = reaction.into();
+            active_model.count = sea_orm::ActiveValue::Set(count);
+            model::discord::MessageReaction::update(active_model).exec(db).await.map_err(Error::SeaORM)?;
+        },
+        None => {
+            model::discord::MessageReaction::insert(ActiveModel {
+                message_id: sea_orm::ActiveValue::Set(db_msg_id),
+                emoji: sea_orm::ActiveValue::Set(emoji),
+                count: sea_orm::ActiveValue::Set(1),
+                ..Default::default()
+            }).exec(db).await.map_err(Error::SeaORM)?;
+        }
+    }
+    Ok(())
+}
+
+/// Retire une réaction d'un message enregistré
+pub async fn remove_reaction(db: &sea_orm::DbConn, message_id: serenity::model::id::MessageId, emoji: String) -> Result<(), Error> {
+    use sea_orm::{QueryFilter, ColumnTrait, ModelTrait};
+    use model::discord::message_reaction::{Column, ActiveModel};
+    let reaction = model::discord::MessageReaction::find()
+        .filter(Column::MessageId.eq(message_id.db_id()?))
+        .filter(Column::Emoji.eq(emoji))
+        .one(db).await.map_err(Error::SeaORM)?;
+    match reaction {
+        Some(reaction) if reaction.count <= 1 => {
+            reaction.delete(db).await.map_err(Error::SeaORM)?;
+        },
+        Some(reaction) => {
+            let count = reaction.count - 1;
+            let mut active_model: ActiveModel = reaction.into();
+            active_model.count = sea_orm::ActiveValue::Set(count);
+            model::discord::MessageReaction::update(active_model).exec(db).await.map_err(Error::SeaORM)?;
+        },
+        None => (),
+    }
+    Ok(())
+}
+
+/// Retire toutes les réactions d'un message enregistré
+pub async fn clear_reactions(db: &sea_orm::DbConn, message_id: serenity::model::id::MessageId) -> Result<(), Error> {
+    use sea_orm::{QueryFilter, ColumnTrait};
+    model::discord::MessageReaction::delete_many()
+        .filter(model::discord::message_reaction::Column::MessageId.eq(message_id.db_id()?))
+        .exec(db).await.map_err(Error::SeaORM)?;
+    Ok(())
 }
\ No newline at end of file
@@ -43,15 +43,15 @@ impl Display for Error {
 
 #[derive(Debug)]
 pub enum FileError {
-    Serenity(serenity::Error),
+    Download(cddio_core::attachment::DownloadError),
     Io(std::io::Error),
 }
 
 impl Display for FileError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            FileError::Serenity(e) => {
-                f.write_str("Serenity error: ")?;
+            FileError::Download(e) => {
+                f.write_str("Download error: ")?;
                 e.fmt(f)
             },
             FileError::Io(e) => {
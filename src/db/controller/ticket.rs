@@ -2,10 +2,10 @@ use crate::db::controller::discord;
 use crate::db::{
     model, 
     controller::Error, 
-    IDType
+    IDType, DbId
 };
 use crate::log_info;
-use sea_orm::{entity::*, prelude::*};
+use sea_orm::{entity::*, prelude::*, TransactionTrait};
 
 
 pub async fn create_ticket(
@@ -21,8 +21,10 @@ pub async fn create_ticket(
     
     let active_model = model::ticket::ActiveModel {
         category_id: sea_orm::ActiveValue::Set(category.id),
-        channel_id: sea_orm::ActiveValue::Set(channel_id.0 as IDType),
-        opened_by: sea_orm::ActiveValue::Set(opened_by.0 as IDType),
+        channel_id: sea_orm::ActiveValue::Set(channel_id.db_id()?),
+        opened_by: sea_orm::ActiveValue::Set(opened_by.db_id()?),
+        opened_at: sea_orm::ActiveValue::Set(Some(chrono::Utc::now().timestamp())),
+        ..Default::default()
     };
     let res = model::ticket::Ticket::insert(active_model).exec(db).await.map_err(Error::SeaORM)?;
     log_info!("Channel {} saved", res.last_insert_id);
@@ -30,7 +32,7 @@ pub async fn create_ticket(
     Ok(res.last_insert_id)
 }
 pub async fn is_ticket_exists(db: &sea_orm::DbConn, channel_id: serenity::model::id::ChannelId) -> Result<bool, Error> {
-    Ok(model::ticket::Ticket::find_by_id(channel_id.0 as IDType).count(db).await.map_err(Error::SeaORM)? > 0)
+    Ok(model::ticket::Ticket::find_by_id(channel_id.db_id()?).count(db).await.map_err(Error::SeaORM)? > 0)
 }
 
 pub async fn create_ticket_if_not_exist(
@@ -40,29 +42,153 @@ pub async fn create_ticket_if_not_exist(
     channel_id: serenity::model::id::ChannelId, 
     opened_by: serenity::model::id::UserId
 ) -> Result<IDType, Error> {
-    if let None = model::ticket::Ticket::find_by_id(channel_id.0 as IDType).one(db).await.map_err(Error::SeaORM)? {
+    if model::ticket::Ticket::find_by_id(channel_id.db_id()?).one(db).await.map_err(Error::SeaORM)?.is_none() {
         create_ticket(ctx, db, category, channel_id, opened_by).await?;
     }
-    Ok(channel_id.0 as IDType)
+    channel_id.db_id()
+}
+
+/// Change le propriétaire d'un ticket
+pub async fn transfer_ticket(
+    db: &sea_orm::DbConn, 
+    ctx: &serenity::client::Context, 
+    channel_id: serenity::model::id::ChannelId, 
+    new_owner: serenity::model::id::UserId
+) -> Result<(), Error> {
+    log_info!("Transferring ticket {} to {}", channel_id, new_owner);
+    let ticket = match model::ticket::Ticket::find_by_id(channel_id.db_id()?).one(db).await.map_err(Error::SeaORM)? {
+        Some(ticket) => ticket,
+        None => return Err(Error::Custom("Ticket not found".to_string())),
+    };
+    discord::save_user_from_id(db, ctx, new_owner).await?;
+    let mut active_model: model::ticket::ActiveModel = ticket.into();
+    active_model.opened_by = sea_orm::ActiveValue::Set(new_owner.db_id()?);
+    model::ticket::Ticket::update(active_model).exec(db).await.map_err(Error::SeaORM)?;
+    Ok(())
+}
+
+/// Change la catégorie d'un ticket
+pub async fn change_ticket_category(db: &sea_orm::DbConn, channel_id: serenity::model::id::ChannelId, category_id: IDType) -> Result<(), Error> {
+    log_info!("Moving ticket {} to category {}", channel_id, category_id);
+    let ticket = match model::ticket::Ticket::find_by_id(channel_id.db_id()?).one(db).await.map_err(Error::SeaORM)? {
+        Some(ticket) => ticket,
+        None => return Err(Error::Custom("Ticket not found".to_string())),
+    };
+    let mut active_model: model::ticket::ActiveModel = ticket.into();
+    active_model.category_id = sea_orm::ActiveValue::Set(category_id);
+    model::ticket::Ticket::update(active_model).exec(db).await.map_err(Error::SeaORM)?;
+    Ok(())
+}
+
+/// Enregistre la première réponse du staff dans un ticket, si elle n'a pas déjà été enregistrée.
+/// 
+/// Les messages du propriétaire du ticket ne sont pas pris en compte.
+pub async fn record_staff_response(
+    db: &sea_orm::DbConn, 
+    channel_id: serenity::model::id::ChannelId, 
+    staff_id: serenity::model::id::UserId, 
+    timestamp: i64
+) -> Result<(), Error> {
+    use sea_orm::sea_query::Expr;
+    model::ticket::Ticket::update_many()
+        .col_expr(model::ticket::Column::FirstStaffResponseAt, Expr::value(timestamp))
+        .col_expr(model::ticket::Column::FirstStaffResponseBy, Expr::value(staff_id.db_id()?))
+        .filter(model::ticket::Column::ChannelId.eq(channel_id.db_id()?))
+        .filter(model::ticket::Column::FirstStaffResponseAt.is_null())
+        .filter(model::ticket::Column::OpenedBy.ne(staff_id.db_id()?))
+        .exec(db).await.map_err(Error::SeaORM)?;
+    Ok(())
+}
+
+/// Enregistre une activité dans un ticket, qui repousse sa fermeture automatique
+pub async fn record_activity(db: &sea_orm::DbConn, channel_id: serenity::model::id::ChannelId, timestamp: i64) -> Result<(), Error> {
+    use sea_orm::sea_query::Expr;
+    model::ticket::Ticket::update_many()
+        .col_expr(model::ticket::Column::LastActivityAt, Expr::value(timestamp))
+        .filter(model::ticket::Column::ChannelId.eq(channel_id.db_id()?))
+        .exec(db).await.map_err(Error::SeaORM)?;
+    Ok(())
+}
+
+/// Date de la dernière activité d'un ticket, ou de son ouverture s'il n'y en a pas eu
+pub async fn last_activity(db: &sea_orm::DbConn, channel_id: serenity::model::id::ChannelId) -> Result<Option<i64>, Error> {
+    let ticket = model::ticket::Ticket::find_by_id(channel_id.db_id()?).one(db).await.map_err(Error::SeaORM)?;
+    Ok(ticket.and_then(|ticket| ticket.last_activity_at.or(ticket.opened_at)))
+}
+
+/// Tickets d'un membre sur un serveur qui n'ont pas encore été archivés, du plus récent au plus ancien
+pub async fn opened_tickets(db: &sea_orm::DbConn, guild_id: serenity::model::id::GuildId, opened_by: serenity::model::id::UserId) -> Result<Vec<model::ticket::Model>, Error> {
+    use sea_orm::{QueryOrder, sea_query::Query};
+    model::ticket::Ticket::find()
+        .filter(model::ticket::Column::OpenedBy.eq(opened_by.db_id()?))
+        .filter(model::ticket::Column::CategoryId.in_subquery(
+            Query::select()
+                .column(model::ticket::category::Column::Id)
+                .from(model::ticket::Category)
+                .and_where(model::ticket::category::Column::GuildId.eq(guild_id.db_id()?))
+                .to_owned()
+        ))
+        .filter(model::ticket::Column::ChannelId.not_in_subquery(
+            Query::select()
+                .column(model::archive::Column::TicketId)
+                .from(model::archive::Archive)
+                .to_owned()
+        ))
+        .order_by_desc(model::ticket::Column::ChannelId)
+        .all(db).await.map_err(Error::SeaORM)
+}
+
+/// Tickets d'une catégorie qui n'ont pas encore été archivés
+pub async fn opened_tickets_in_category(db: &sea_orm::DbConn, category_id: IDType) -> Result<Vec<model::ticket::Model>, Error> {
+    use sea_orm::sea_query::Query;
+    model::ticket::Ticket::find()
+        .filter(model::ticket::Column::CategoryId.eq(category_id))
+        .filter(model::ticket::Column::ChannelId.not_in_subquery(
+            Query::select()
+                .column(model::archive::Column::TicketId)
+                .from(model::archive::Archive)
+                .to_owned()
+        ))
+        .all(db).await.map_err(Error::SeaORM)
+}
+
+/// Dernier ticket créé par un membre sur un serveur, archivé ou non
+pub async fn last_ticket(db: &sea_orm::DbConn, guild_id: serenity::model::id::GuildId, opened_by: serenity::model::id::UserId) -> Result<Option<model::ticket::Model>, Error> {
+    use sea_orm::{QueryOrder, sea_query::Query};
+    model::ticket::Ticket::find()
+        .filter(model::ticket::Column::OpenedBy.eq(opened_by.db_id()?))
+        .filter(model::ticket::Column::CategoryId.in_subquery(
+            Query::select()
+                .column(model::ticket::category::Column::Id)
+                .from(model::ticket::Category)
+                .and_where(model::ticket::category::Column::GuildId.eq(guild_id.db_id()?))
+                .to_owned()
+        ))
+        .order_by_desc(model::ticket::Column::ChannelId)
+        .one(db).await.map_err(Error::SeaORM)
 }
 
 pub async fn archive_ticket(
     db: &sea_orm::DbConn, 
     ctx: &serenity::client::Context, 
     channel_id: serenity::model::id::ChannelId, 
-    closed_by_by: serenity::model::id::UserId
+    closed_by_by: serenity::model::id::UserId,
+    reason: Option<String>
 ) -> Result<IDType, Error> {
     log_info!("Archiving ticket");
-    match model::ticket::Ticket::find_by_id(channel_id.0 as IDType).one(db).await {
+    match model::ticket::Ticket::find_by_id(channel_id.db_id()?).one(db).await {
         Ok(None) => Err(Error::Custom("Ticket not found".to_string()))?,
         Err(e) => Err(Error::SeaORM(e))?,
         _ => ()
     }
     discord::save_user_from_id(db, ctx, closed_by_by).await?;
+    // Les messages sont enregistrés au fil de l'eau, seuls ceux manqués sont rattrapés
     super::discord::save_channel(db, ctx, channel_id).await?;
     let active_model = model::archive::ActiveModel {
-        ticket_id: sea_orm::ActiveValue::Set(channel_id.0 as IDType),
-        closed_by: sea_orm::ActiveValue::Set(closed_by_by.0 as IDType),
+        ticket_id: sea_orm::ActiveValue::Set(channel_id.db_id()?),
+        closed_by: sea_orm::ActiveValue::Set(closed_by_by.db_id()?),
+        closed_at: sea_orm::ActiveValue::Set(Some(chrono::Utc::now().timestamp())),
+        reason: sea_orm::ActiveValue::Set(reason),
         ..Default::default()
     };
     let res = model::archive::Archive::insert(active_model).exec(db).await.map_err(Error::SeaORM)?;
@@ -70,24 +196,161 @@ pub async fn archive_ticket(
     Ok(res.last_insert_id)
 }
 
-pub async fn add_category(
-    db: &sea_orm::DbConn, 
-    name: String, 
-    prefix: String, 
-    discord_category_id: serenity::model::id::ChannelId,
-    description: Option<String>, 
-    hidden: Option<bool>
-) -> Result<IDType, Error> {
+/// Archive d'un ticket
+pub async fn archive_of(db: &sea_orm::DbConn, channel_id: serenity::model::id::ChannelId) -> Result<Option<model::archive::Model>, Error> {
+    model::archive::Archive::find()
+        .filter(model::archive::Column::TicketId.eq(channel_id.db_id()?))
+        .one(db).await.map_err(Error::SeaORM)
+}
+
+/// Dernier ticket archivé d'un membre sur un serveur qui n'a pas encore été rouvert, avec son archive
+pub async fn last_archived_ticket(db: &sea_orm::DbConn, guild_id: serenity::model::id::GuildId, opened_by: serenity::model::id::UserId) -> Result<Option<(model::ticket::Model, model::archive::Model)>, Error> {
+    use sea_orm::{QueryOrder, sea_query::Query};
+    let res = model::ticket::Ticket::find()
+        .filter(model::ticket::Column::OpenedBy.eq(opened_by.db_id()?))
+        .filter(model::ticket::Column::CategoryId.in_subquery(
+            Query::select()
+                .column(model::ticket::category::Column::Id)
+                .from(model::ticket::Category)
+                .and_where(model::ticket::category::Column::GuildId.eq(guild_id.db_id()?))
+                .to_owned()
+        ))
+        .order_by_desc(model::ticket::Column::ChannelId)
+        .find_also_related(model::archive::Archive)
+        .filter(model::archive::Column::Id.is_not_null())
+        .filter(model::archive::Column::ReopenedAs.is_null())
+        .one(db).await.map_err(Error::SeaORM)?;
+    Ok(res.and_then(|(ticket, archive)| archive.map(|archive| (ticket, archive))))
+}
+
+/// Message d'un ticket archivé
+pub struct ArchivedMessage {
+    pub message: model::discord::message::Model,
+    /// Nom de l'auteur du message
+    pub author: Option<String>,
+    /// Nombre de pièces jointes
+    pub attachments: usize,
+    /// Réactions au message (emoji et nombre)
+    pub reactions: Vec<(String, i32)>,
+}
+
+/// Messages enregistrés d'un salon, du plus ancien au plus récent
+pub async fn archived_messages(db: &sea_orm::DbConn, channel_id: serenity::model::id::ChannelId) -> Result<Vec<ArchivedMessage>, Error> {
+    use sea_orm::QueryOrder;
+    use std::collections::HashMap;
+    let messages = model::discord::Message::find()
+        .filter(model::discord::message::Column::ChannelId.eq(channel_id.db_id()?))
+        .order_by_asc(model::discord::message::Column::Id)
+        .all(db).await.map_err(Error::SeaORM)?;
+    let users = model::discord::User::find()
+        .filter(model::discord::user::Column::Id.is_in(messages.iter().map(|m| m.user_id)))
+        .all(db).await.map_err(Error::SeaORM)?
+        .into_iter()
+        .map(|u| (u.id, u.name))
+        .collect::<HashMap<_, _>>();
+    let mut attachments = HashMap::<IDType, usize>::new();
+    for attachment in model::discord::Attachment::find()
+        .filter(model::discord::attachment::Column::MessageId.is_in(messages.iter().map(|m| m.id)))
+        .all(db).await.map_err(Error::SeaORM)? 
+    {
+        *attachments.entry(attachment.message_id).or_default() += 1;
+    }
+    let mut reactions = HashMap::<IDType, Vec<(String, i32)>>::new();
+    for reaction in model::discord::MessageReaction::find()
+        .filter(model::discord::message_reaction::Column::MessageId.is_in(messages.iter().map(|m| m.id)))
+        .order_by_asc(model::discord::message_reaction::Column::Id)
+        .all(db).await.map_err(Error::SeaORM)? 
+    {
+        reactions.entry(reaction.message_id).or_default().push((reaction.emoji, reaction.count));
+    }
+    Ok(messages.into_iter().map(|m| ArchivedMessage {
+        author: users.get(&m.user_id).cloned(),
+        attachments: attachments.get(&m.id).copied().unwrap_or_default(),
+        reactions: reactions.remove(&m.id).unwrap_or_default(),
+        message: m,
+    }).collect())
+}
+
+/// Réponses au formulaire d'ouverture d'un ticket
+pub async fn ticket_answers(db: &sea_orm::DbConn, ticket: &model::ticket::Model) -> Result<Vec<(String, String)>, Error> {
+    Ok(ticket.answers().all(db).await.map_err(Error::SeaORM)?
+        .into_iter()
+        .map(|a| (a.label, a.value))
+        .collect())
+}
+
+/// Marque un ticket archivé comme rouvert dans un nouveau salon
+pub async fn mark_reopened(db: &sea_orm::DbConn, archive: model::archive::Model, new_channel_id: serenity::model::id::ChannelId) -> Result<(), Error> {
+    log_info!("Ticket {} reopened as {}", archive.ticket_id, new_channel_id);
+    let mut active_model: model::archive::ActiveModel = archive.into();
+    active_model.reopened_as = sea_orm::ActiveValue::Set(Some(new_channel_id.db_id()?));
+    model::archive::Archive::update(active_model).exec(db).await.map_err(Error::SeaORM)?;
+    Ok(())
+}
+
+/// Enregistre la note donnée par le propriétaire d'un ticket, en remplaçant la précédente
+pub async fn save_rating(db: &sea_orm::DbConn, channel_id: serenity::model::id::ChannelId, rating: i32) -> Result<(), Error> {
+    use model::ticket::rating;
+    if !(rating::MIN_RATING..=rating::MAX_RATING).contains(&rating) {
+        return Err(Error::Custom(format!("Rating must be between {} and {}", rating::MIN_RATING, rating::MAX_RATING)));
+    }
+    log_info!("Saving rating {} for ticket {}", rating, channel_id);
+    let now = chrono::Utc::now().timestamp();
+    match rating::Entity::find_by_id(channel_id.db_id()?).one(db).await.map_err(Error::SeaORM)? {
+        Some(model) => {
+            let mut active_model: rating::ActiveModel = model.into();
+            active_model.rating = sea_orm::ActiveValue::Set(rating);
+            active_model.rated_at = sea_orm::ActiveValue::Set(now);
+            rating::Entity::update(active_model).exec(db).await.map_err(Error::SeaORM)?;
+        },
+        None => {
+            rating::Entity::insert(rating::ActiveModel {
+                ticket_id: sea_orm::ActiveValue::Set(channel_id.db_id()?),
+                rating: sea_orm::ActiveValue::Set(rating),
+                comment: sea_orm::ActiveValue::Set(None),
+                rated_at: sea_orm::ActiveValue::Set(now),
+            }).exec(db).await.map_err(Error::SeaORM)?;
+        }
+    }
+    Ok(())
+}
+
+/// Ajoute un commentaire à la note d'un ticket
+pub async fn save_rating_comment(db: &sea_orm::DbConn, channel_id: serenity::model::id::ChannelId, comment: String) -> Result<(), Error> {
+    use model::ticket::rating;
+    let model = match rating::Entity::find_by_id(channel_id.db_id()?).one(db).await.map_err(Error::SeaORM)? {
+        Some(model) => model,
+        None => return Err(Error::Custom("Rating not found".to_string())),
+    };
+    let mut active_model: rating::ActiveModel = model.into();
+    active_model.comment = sea_orm::ActiveValue::Set(Some(comment));
+    rating::Entity::update(active_model).exec(db).await.map_err(Error::SeaORM)?;
+    Ok(())
+}
+
+/// Catégorie de ticket à créer
+pub struct NewCategory {
+    pub guild_id: serenity::model::id::GuildId,
+    pub name: String,
+    pub prefix: String,
+    /// Catégorie Discord où les tickets sont créés
+    pub discord_category_id: serenity::model::id::ChannelId,
+    pub description: Option<String>,
+    pub hidden: bool,
+    /// Délai d'inactivité avant la fermeture automatique, en heures
+    pub inactivity_timeout: Option<i32>,
+}
+
+pub async fn add_category(db: &sea_orm::DbConn, category: NewCategory) -> Result<IDType, Error> {
     log_info!("Adding category");
-    let discord_category_id = discord_category_id.0
-        .try_into()
-        .map_err(|e| Error::Custom(format!("Unable to convert ID from u64 to i64: {:?}", e)))?;
     let active_model = model::ticket::category::ActiveModel {
-        name: sea_orm::ActiveValue::Set(name),
-        prefix: sea_orm::ActiveValue::Set(prefix),
-        discord_category_id: sea_orm::ActiveValue::Set(discord_category_id),
-        description: sea_orm::ActiveValue::Set(description),
-        hidden: sea_orm::ActiveValue::Set(hidden.unwrap_or(false)),
+        guild_id: sea_orm::ActiveValue::Set(Some(category.guild_id.db_id()?)),
+        name: sea_orm::ActiveValue::Set(category.name),
+        prefix: sea_orm::ActiveValue::Set(category.prefix),
+        discord_category_id: sea_orm::ActiveValue::Set(category.discord_category_id.db_id()?),
+        description: sea_orm::ActiveValue::Set(category.description),
+        hidden: sea_orm::ActiveValue::Set(category.hidden),
+        inactivity_timeout: sea_orm::ActiveValue::Set(category.inactivity_timeout),
         .. Default::default()
     };
     let res = model::ticket::Category::insert(active_model).exec(db).await.map_err(Error::SeaORM)?;
@@ -95,9 +358,292 @@ pub async fn add_category(
     Ok(res.last_insert_id)
 }
 
+/// Catégories qui n'ont pas encore été assignées à un serveur
+pub async fn categories_without_guild(db: &sea_orm::DbConn) -> Result<Vec<model::ticket::category::Model>, Error> {
+    model::ticket::Category::find()
+        .filter(model::ticket::category::Column::GuildId.is_null())
+        .all(db).await.map_err(Error::SeaORM)
+}
+
+/// Assigne une catégorie à un serveur
+pub async fn set_category_guild(db: &sea_orm::DbConn, category: model::ticket::category::Model, guild_id: serenity::model::id::GuildId) -> Result<(), Error> {
+    log_info!("Assigning category {} to guild {}", category.id, guild_id);
+    let mut active_model: model::ticket::category::ActiveModel = category.into();
+    active_model.guild_id = sea_orm::ActiveValue::Set(Some(guild_id.db_id()?));
+    model::ticket::Category::update(active_model).exec(db).await.map_err(Error::SeaORM)?;
+    Ok(())
+}
+
 pub async fn remove_category(db: &sea_orm::DbConn, category_id: IDType) -> Result<(), Error> {
     log_info!("Removing category {}", category_id);
+    model::ticket::Question::delete_many()
+        .filter(model::ticket::question::Column::CategoryId.eq(category_id))
+        .exec(db).await.map_err(Error::SeaORM)?;
     model::ticket::Category::delete_by_id(category_id).exec(db).await.map_err(Error::SeaORM)?;
     log_info!("Category {} removed", category_id);
     Ok(())
-}
\ No newline at end of file
+}
+pub async fn add_question(
+    db: &sea_orm::DbConn, 
+    category_id: IDType, 
+    label: String, 
+    paragraph: bool, 
+    required: bool
+) -> Result<IDType, Error> {
+    use model::ticket::question;
+    log_info!("Adding question to category {}", category_id);
+    let nb_questions = question::Entity::find()
+        .filter(question::Column::CategoryId.eq(category_id))
+        .count(db).await.map_err(Error::SeaORM)?;
+    if nb_questions as usize >= question::MAX_QUESTIONS {
+        return Err(Error::Custom(format!("A category can't have more than {} questions", question::MAX_QUESTIONS)));
+    }
+    let active_model = question::ActiveModel {
+        category_id: sea_orm::ActiveValue::Set(category_id),
+        position: sea_orm::ActiveValue::Set(nb_questions as i32),
+        label: sea_orm::ActiveValue::Set(label),
+        paragraph: sea_orm::ActiveValue::Set(paragraph),
+        required: sea_orm::ActiveValue::Set(required),
+        .. Default::default()
+    };
+    let res = question::Entity::insert(active_model).exec(db).await.map_err(Error::SeaORM)?;
+    log_info!("Question {} saved", res.last_insert_id);
+    Ok(res.last_insert_id)
+}
+
+pub async fn remove_question(db: &sea_orm::DbConn, category_id: IDType, position: i32) -> Result<(), Error> {
+    use model::ticket::question;
+    use sea_orm::QueryOrder;
+    log_info!("Removing question {} from category {}", position, category_id);
+    let questions = question::Entity::find()
+        .filter(question::Column::CategoryId.eq(category_id))
+        .order_by_asc(question::Column::Position)
+        .all(db).await.map_err(Error::SeaORM)?;
+    if !questions.iter().any(|q| q.position == position) {
+        return Err(Error::Custom("Question not found".to_string()));
+    }
+    let txn = db.begin().await.map_err(Error::SeaORM)?;
+    let mut new_position = 0;
+    for q in questions {
+        if q.position == position {
+            question::Entity::delete_by_id(q.id).exec(&txn).await.map_err(Error::SeaORM)?;
+            continue;
+        }
+        if q.position != new_position {
+            let mut active_model: question::ActiveModel = q.into();
+            active_model.position = sea_orm::ActiveValue::Set(new_position);
+            question::Entity::update(active_model).exec(&txn).await.map_err(Error::SeaORM)?;
+        }
+        new_position += 1;
+    }
+    txn.commit().await.map_err(Error::SeaORM)?;
+    log_info!("Question {} removed from category {}", position, category_id);
+    Ok(())
+}
+
+pub async fn save_answers(
+    db: &sea_orm::DbConn, 
+    channel_id: serenity::model::id::ChannelId, 
+    answers: Vec<(String, String)>
+) -> Result<(), Error> {
+    use model::ticket::answer;
+    if answers.is_empty() {
+        return Ok(());
+    }
+    log_info!("Saving {} answers for ticket {}", answers.len(), channel_id);
+    let ticket_id = channel_id.db_id()?;
+    let active_models = answers.into_iter().enumerate().map(|(position, (label, value))| answer::ActiveModel {
+        ticket_id: sea_orm::ActiveValue::Set(ticket_id),
+        position: sea_orm::ActiveValue::Set(position as i32),
+        label: sea_orm::ActiveValue::Set(label),
+        value: sea_orm::ActiveValue::Set(value),
+        .. Default::default()
+    });
+    answer::Entity::insert_many(active_models).exec(db).await.map_err(Error::SeaORM)?;
+    Ok(())
+}
+
+/// Statistiques des tickets ouverts sur une période
+#[derive(Debug, Default)]
+pub struct TicketStats {
+    /// Nombre de tickets ouverts par catégorie
+    pub per_category: Vec<(String, i64)>,
+    /// Durée médiane avant la première réponse du staff, en secondes
+    pub median_first_response: Option<i64>,
+    /// Durée médiane avant la fermeture du ticket, en secondes
+    pub median_resolution: Option<i64>,
+    /// Nombre de premières réponses par membre du staff
+    pub first_responses: Vec<(IDType, i64)>,
+    /// Nombre de tickets fermés par membre, hors propriétaire du ticket
+    pub closures: Vec<(IDType, i64)>,
+    /// Note moyenne et nombre de notes par catégorie
+    pub ratings_per_category: Vec<(String, f64, i64)>,
+    /// Note moyenne et nombre de notes par membre du staff ayant répondu en premier
+    pub ratings_per_staff: Vec<(IDType, f64, i64)>,
+}
+
+#[derive(Debug, sea_orm::FromQueryResult)]
+struct CountResult {
+    id: IDType,
+    count: i64,
+}
+
+#[derive(Debug, sea_orm::FromQueryResult)]
+struct RatingResult {
+    id: IDType,
+    total: i64,
+    count: i64,
+}
+
+impl RatingResult {
+    fn average(&self) -> f64 {
+        self.total as f64 / self.count.max(1) as f64
+    }
+}
+
+#[derive(Debug, sea_orm::FromQueryResult)]
+struct DurationResult {
+    duration: i64,
+}
+
+/// Médiane d'une durée calculée par la base de données sur les lignes sélectionnées
+async fn median<E: EntityTrait>(db: &sea_orm::DbConn, select: Select<E>, duration: sea_orm::sea_query::SimpleExpr) -> Result<Option<i64>, Error> 
+    where E::Model: Sync
+{
+    use sea_orm::{QueryOrder, QuerySelect};
+    let count = select.clone().count(db).await.map_err(Error::SeaORM)?;
+    if count == 0 {
+        return Ok(None);
+    }
+    let values = select
+        .select_only()
+        .column_as(duration.clone(), "duration")
+        .order_by(duration, sea_orm::Order::Asc)
+        .offset((count - 1) / 2)
+        .limit(if count % 2 == 0 { 2 } else { 1 })
+        .into_model::<DurationResult>()
+        .all(db).await.map_err(Error::SeaORM)?;
+    if values.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(values.iter().map(|v| v.duration).sum::<i64>() / values.len() as i64))
+}
+
+/// Statistiques des tickets d'un serveur ouverts depuis `since` (timestamp unix)
+pub async fn ticket_stats(db: &sea_orm::DbConn, guild_id: serenity::model::id::GuildId, since: i64) -> Result<TicketStats, Error> {
+    use sea_orm::{QuerySelect, sea_query::Expr};
+    use model::{ticket, archive};
+
+    let categories = ticket::category::Entity::find()
+        .filter(ticket::category::Column::GuildId.eq(guild_id.db_id()?))
+        .all(db).await.map_err(Error::SeaORM)?;
+    let opened_since = sea_orm::Condition::all()
+        .add(ticket::Column::OpenedAt.gte(since))
+        .add(ticket::Column::CategoryId.is_in(categories.iter().map(|c| c.id)));
+    let per_category = ticket::Ticket::find()
+        .select_only()
+        .column_as(ticket::Column::CategoryId, "id")
+        .column_as(ticket::Column::ChannelId.count(), "count")
+        .filter(opened_since.clone())
+        .group_by(ticket::Column::CategoryId)
+        .into_model::<CountResult>()
+        .all(db).await.map_err(Error::SeaORM)?
+        .into_iter()
+        .map(|c| {
+            let name = categories.iter()
+                .find(|cat| cat.id == c.id)
+                .map(|cat| cat.name.clone())
+                .unwrap_or_else(|| format!("Catégorie {}", c.id));
+            (name, c.count)
+        })
+        .collect();
+
+    let median_first_response = median(
+        db,
+        ticket::Ticket::find()
+            .filter(opened_since.clone())
+            .filter(ticket::Column::FirstStaffResponseAt.is_not_null()),
+        Expr::col(ticket::Column::FirstStaffResponseAt).sub(Expr::col(ticket::Column::OpenedAt))
+    ).await?;
+
+    let median_resolution = median(
+        db,
+        archive::Archive::find()
+            .inner_join(ticket::Ticket)
+            .filter(opened_since.clone())
+            .filter(archive::Column::ClosedAt.is_not_null()),
+        Expr::col((archive::Entity, archive::Column::ClosedAt)).sub(Expr::col((ticket::Entity, ticket::Column::OpenedAt)))
+    ).await?;
+
+    let first_responses = ticket::Ticket::find()
+        .select_only()
+        .column_as(ticket::Column::FirstStaffResponseBy, "id")
+        .column_as(ticket::Column::ChannelId.count(), "count")
+        .filter(opened_since.clone())
+        .filter(ticket::Column::FirstStaffResponseBy.is_not_null())
+        .group_by(ticket::Column::FirstStaffResponseBy)
+        .into_model::<CountResult>()
+        .all(db).await.map_err(Error::SeaORM)?
+        .into_iter()
+        .map(|c| (c.id, c.count))
+        .collect();
+
+    let closures = archive::Archive::find()
+        .select_only()
+        .column_as(archive::Column::ClosedBy, "id")
+        .column_as(archive::Column::Id.count(), "count")
+        .inner_join(ticket::Ticket)
+        .filter(opened_since.clone())
+        .filter(Expr::col((archive::Entity, archive::Column::ClosedBy)).ne(Expr::col((ticket::Entity, ticket::Column::OpenedBy))))
+        .group_by(archive::Column::ClosedBy)
+        .into_model::<CountResult>()
+        .all(db).await.map_err(Error::SeaORM)?
+        .into_iter()
+        .map(|c| (c.id, c.count))
+        .collect();
+
+    let ratings_per_category = ticket::Rating::find()
+        .select_only()
+        .column_as(Expr::col((ticket::Entity, ticket::Column::CategoryId)), "id")
+        .column_as(ticket::rating::Column::Rating.sum(), "total")
+        .column_as(ticket::rating::Column::Rating.count(), "count")
+        .inner_join(ticket::Ticket)
+        .filter(opened_since.clone())
+        .group_by(Expr::col((ticket::Entity, ticket::Column::CategoryId)))
+        .into_model::<RatingResult>()
+        .all(db).await.map_err(Error::SeaORM)?
+        .into_iter()
+        .map(|r| {
+            let name = categories.iter()
+                .find(|cat| cat.id == r.id)
+                .map(|cat| cat.name.clone())
+                .unwrap_or_else(|| format!("Catégorie {}", r.id));
+            (name, r.average(), r.count)
+        })
+        .collect();
+
+    let ratings_per_staff = ticket::Rating::find()
+        .select_only()
+        .column_as(Expr::col((ticket::Entity, ticket::Column::FirstStaffResponseBy)), "id")
+        .column_as(ticket::rating::Column::Rating.sum(), "total")
+        .column_as(ticket::rating::Column::Rating.count(), "count")
+        .inner_join(ticket::Ticket)
+        .filter(opened_since)
+        .filter(ticket::Column::FirstStaffResponseBy.is_not_null())
+        .group_by(Expr::col((ticket::Entity, ticket::Column::FirstStaffResponseBy)))
+        .into_model::<RatingResult>()
+        .all(db).await.map_err(Error::SeaORM)?
+        .into_iter()
+        .map(|r| (r.id, r.average(), r.count))
+        .collect();
+
+    Ok(TicketStats {
+        per_category,
+        median_first_response,
+        median_resolution,
+        first_responses,
+        closures,
+        ratings_per_category,
+        ratings_per_staff,
+    })
+}
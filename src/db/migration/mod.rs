@@ -0,0 +1,87 @@
+//! Migrations du schéma de la base de données
+//! 
+//! Chaque migration porte un numéro de version et décrit les modifications du schéma 
+//! à appliquer depuis la version précédente. Les versions appliquées sont enregistrées 
+//! dans la table `schema_version`.
+//! 
+//! Au démarrage, les migrations dont la version est supérieure à la dernière version 
+//! appliquée sont exécutées dans l'ordre, chacune dans une transaction.
+//! 
+//! Une migration publiée ne doit plus être modifiée : toute modification d'une entité 
+//! de [`model`](super::model) s'accompagne d'une nouvelle migration.
+
+mod schema_version;
+mod v1;
+mod v2;
+mod v3;
+
+use sea_orm::{entity::*, query::*, ConnectionTrait, DbBackend, DbConn, DbErr, Schema, Statement, TransactionTrait};
+use crate::log_info;
+
+/// Migration du schéma vers une version
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    /// Requêtes de la migration pour le type de base de données
+    statements: fn(DbBackend) -> Vec<Statement>,
+}
+
+impl Migration {
+    pub fn statements(&self, backend: DbBackend) -> Vec<Statement> {
+        (self.statements)(backend)
+    }
+}
+
+/// Migrations connues, par ordre de version
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "Schéma initial", statements: v1::statements },
+    Migration { version: 2, name: "Formulaires, notes, statistiques et historique des messages", statements: v2::statements },
+    Migration { version: 3, name: "Dernière activité des tickets", statements: v3::statements },
+];
+
+/// Crée la table des versions si elle n'existe pas
+async fn create_version_table(db: &DbConn) -> Result<(), DbErr> {
+    let backend = db.get_database_backend();
+    let schema = Schema::new(backend);
+    db.execute(backend.build(schema.create_table_from_entity(schema_version::Entity).if_not_exists())).await?;
+    Ok(())
+}
+
+/// Version du schéma décrite par la dernière migration connue
+pub fn latest_version() -> i32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or_default()
+}
+
+/// Dernière version appliquée, 0 pour une base de données vierge
+pub async fn current_version(db: &DbConn) -> Result<i32, DbErr> {
+    let last = schema_version::Entity::find()
+        .order_by_desc(schema_version::Column::Version)
+        .one(db).await?;
+    Ok(last.map(|v| v.version).unwrap_or_default())
+}
+
+/// Migrations qui n'ont pas encore été appliquées
+pub async fn pending(db: &DbConn) -> Result<Vec<&'static Migration>, DbErr> {
+    create_version_table(db).await?;
+    let version = current_version(db).await?;
+    Ok(MIGRATIONS.iter().filter(|m| m.version > version).collect())
+}
+
+/// Applique les migrations en attente
+pub async fn migrate(db: &DbConn) -> Result<(), DbErr> {
+    let backend = db.get_database_backend();
+    for migration in pending(db).await? {
+        log_info!("Migration du schéma vers la version {}: {}", migration.version, migration.name);
+        let txn = db.begin().await?;
+        for statement in migration.statements(backend) {
+            txn.execute(statement).await?;
+        }
+        schema_version::Entity::insert(schema_version::ActiveModel {
+            version: Set(migration.version),
+            name: Set(migration.name.to_string()),
+            applied_at: Set(chrono::Utc::now().timestamp()),
+        }).exec(&txn).await?;
+        txn.commit().await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use sea_orm::entity::prelude::*;
+
+/// Version du schéma appliquée à la base de données
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "schema_version")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub version: i32,
+    pub name: String,
+    /// Date d'application de la migration (timestamp unix)
+    pub applied_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel 
+{}
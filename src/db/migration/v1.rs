@@ -0,0 +1,102 @@
+//! Schéma initial
+//! 
+//! Tables existantes avant l'introduction des migrations. 
+//! Elles ne sont créées que si elles n'existent pas, pour reprendre les bases de données 
+//! créées avant les migrations.
+
+use sea_orm::{DbBackend, Statement, sea_query::{Alias, ColumnDef, ForeignKey, Table}};
+
+pub fn statements(backend: DbBackend) -> Vec<Statement> {
+    vec![
+        backend.build(Table::create()
+            .table(Alias::new("discord_user"))
+            .if_not_exists()
+            .col(ColumnDef::new(Alias::new("id")).big_integer().not_null().primary_key())
+            .col(ColumnDef::new(Alias::new("name")).string().not_null())
+            .col(ColumnDef::new(Alias::new("avatar")).string().not_null())
+        ),
+        backend.build(Table::create()
+            .table(Alias::new("discord_channel"))
+            .if_not_exists()
+            .col(ColumnDef::new(Alias::new("id")).big_integer().not_null().primary_key())
+            .col(ColumnDef::new(Alias::new("name")).string().not_null())
+        ),
+        backend.build(Table::create()
+            .table(Alias::new("discord_message"))
+            .if_not_exists()
+            .col(ColumnDef::new(Alias::new("id")).big_integer().not_null().primary_key())
+            .col(ColumnDef::new(Alias::new("channel_id")).big_integer().not_null())
+            .col(ColumnDef::new(Alias::new("user_id")).big_integer().not_null())
+            .col(ColumnDef::new(Alias::new("content")).string().not_null())
+            .col(ColumnDef::new(Alias::new("in_reply_to")).big_integer())
+            .col(ColumnDef::new(Alias::new("last_modified")).big_integer().not_null())
+            .foreign_key(ForeignKey::create()
+                .name("fk-discord_message-in_reply_to")
+                .from(Alias::new("discord_message"), Alias::new("in_reply_to"))
+                .to(Alias::new("discord_message"), Alias::new("id")))
+            .foreign_key(ForeignKey::create()
+                .name("fk-discord_message-channel_id")
+                .from(Alias::new("discord_message"), Alias::new("channel_id"))
+                .to(Alias::new("discord_channel"), Alias::new("id")))
+            .foreign_key(ForeignKey::create()
+                .name("fk-discord_message-user_id")
+                .from(Alias::new("discord_message"), Alias::new("user_id"))
+                .to(Alias::new("discord_user"), Alias::new("id")))
+        ),
+        backend.build(Table::create()
+            .table(Alias::new("discord_attachment"))
+            .if_not_exists()
+            .col(ColumnDef::new(Alias::new("id")).big_integer().not_null().primary_key())
+            .col(ColumnDef::new(Alias::new("message_id")).big_integer().not_null())
+            .col(ColumnDef::new(Alias::new("url")).string().not_null())
+            .foreign_key(ForeignKey::create()
+                .name("fk-discord_attachment-message_id")
+                .from(Alias::new("discord_attachment"), Alias::new("message_id"))
+                .to(Alias::new("discord_message"), Alias::new("id")))
+        ),
+        backend.build(Table::create()
+            .table(Alias::new("cdd_tickets_category"))
+            .if_not_exists()
+            .col(ColumnDef::new(Alias::new("id")).big_integer().not_null().auto_increment().primary_key())
+            .col(ColumnDef::new(Alias::new("discord_category_id")).big_integer().not_null())
+            .col(ColumnDef::new(Alias::new("name")).string().not_null())
+            .col(ColumnDef::new(Alias::new("prefix")).string().not_null())
+            .col(ColumnDef::new(Alias::new("description")).string())
+            .col(ColumnDef::new(Alias::new("hidden")).boolean().not_null())
+        ),
+        backend.build(Table::create()
+            .table(Alias::new("cdd_ticket"))
+            .if_not_exists()
+            .col(ColumnDef::new(Alias::new("channel_id")).big_integer().not_null().primary_key())
+            .col(ColumnDef::new(Alias::new("category_id")).big_integer().not_null())
+            .col(ColumnDef::new(Alias::new("opened_by")).big_integer().not_null())
+            .foreign_key(ForeignKey::create()
+                .name("fk-cdd_ticket-category_id")
+                .from(Alias::new("cdd_ticket"), Alias::new("category_id"))
+                .to(Alias::new("cdd_tickets_category"), Alias::new("id")))
+            .foreign_key(ForeignKey::create()
+                .name("fk-cdd_ticket-channel_id")
+                .from(Alias::new("cdd_ticket"), Alias::new("channel_id"))
+                .to(Alias::new("discord_channel"), Alias::new("id")))
+            .foreign_key(ForeignKey::create()
+                .name("fk-cdd_ticket-opened_by")
+                .from(Alias::new("cdd_ticket"), Alias::new("opened_by"))
+                .to(Alias::new("discord_user"), Alias::new("id")))
+        ),
+        backend.build(Table::create()
+            .table(Alias::new("cdd_archive"))
+            .if_not_exists()
+            .col(ColumnDef::new(Alias::new("id")).big_integer().not_null().auto_increment().primary_key())
+            .col(ColumnDef::new(Alias::new("ticket_id")).big_integer().not_null())
+            .col(ColumnDef::new(Alias::new("closed_by")).big_integer().not_null())
+            .foreign_key(ForeignKey::create()
+                .name("fk-cdd_archive-closed_by")
+                .from(Alias::new("cdd_archive"), Alias::new("closed_by"))
+                .to(Alias::new("discord_user"), Alias::new("id")))
+            .foreign_key(ForeignKey::create()
+                .name("fk-cdd_archive-ticket_id")
+                .from(Alias::new("cdd_archive"), Alias::new("ticket_id"))
+                .to(Alias::new("cdd_ticket"), Alias::new("channel_id")))
+        ),
+    ]
+}
@@ -0,0 +1,101 @@
+//! Formulaires, notes, statistiques et historique des messages
+//! 
+//! - formulaires d'ouverture des tickets (`cdd_tickets_question`, `cdd_ticket_answer`)
+//! - notes des tickets (`cdd_ticket_rating`)
+//! - dates d'ouverture, de première réponse et de fermeture des tickets
+//! - catégories par serveur et délai d'inactivité
+//! - modifications, suppressions, réactions et contenu enrichi des messages
+//! - stockage des pièces jointes
+
+use sea_orm::{DbBackend, Statement, sea_query::{Alias, ColumnDef, ForeignKey, Table}};
+
+/// Ajoute une colonne à une table. 
+/// 
+/// SQLite n'accepte qu'une colonne par `ALTER TABLE`.
+fn add_column(backend: DbBackend, table: &str, column: &mut ColumnDef) -> Statement {
+    backend.build(Table::alter()
+        .table(Alias::new(table))
+        .add_column(column)
+    )
+}
+
+pub fn statements(backend: DbBackend) -> Vec<Statement> {
+    vec![
+        add_column(backend, "cdd_tickets_category", ColumnDef::new(Alias::new("guild_id")).big_integer()),
+        add_column(backend, "cdd_tickets_category", ColumnDef::new(Alias::new("inactivity_timeout")).integer()),
+        add_column(backend, "cdd_ticket", ColumnDef::new(Alias::new("opened_at")).big_integer()),
+        add_column(backend, "cdd_ticket", ColumnDef::new(Alias::new("first_staff_response_at")).big_integer()),
+        add_column(backend, "cdd_ticket", ColumnDef::new(Alias::new("first_staff_response_by")).big_integer()),
+        add_column(backend, "cdd_archive", ColumnDef::new(Alias::new("closed_at")).big_integer()),
+        add_column(backend, "cdd_archive", ColumnDef::new(Alias::new("reason")).string()),
+        add_column(backend, "cdd_archive", ColumnDef::new(Alias::new("reopened_as")).big_integer()),
+        add_column(backend, "discord_message", ColumnDef::new(Alias::new("deleted_at")).big_integer()),
+        add_column(backend, "discord_message", ColumnDef::new(Alias::new("kind")).integer().not_null().default(0)),
+        add_column(backend, "discord_message", ColumnDef::new(Alias::new("embeds")).string()),
+        add_column(backend, "discord_message", ColumnDef::new(Alias::new("stickers")).string()),
+        add_column(backend, "discord_attachment", ColumnDef::new(Alias::new("filename")).string().not_null().default("")),
+        add_column(backend, "discord_attachment", ColumnDef::new(Alias::new("size")).big_integer().not_null().default(0)),
+        add_column(backend, "discord_attachment", ColumnDef::new(Alias::new("content_type")).string()),
+        add_column(backend, "discord_attachment", ColumnDef::new(Alias::new("hash")).string()),
+        add_column(backend, "discord_attachment", ColumnDef::new(Alias::new("path")).string()),
+        add_column(backend, "discord_attachment", ColumnDef::new(Alias::new("saved_at")).big_integer().not_null().default(0)),
+        backend.build(Table::create()
+            .table(Alias::new("cdd_tickets_question"))
+            .col(ColumnDef::new(Alias::new("id")).big_integer().not_null().auto_increment().primary_key())
+            .col(ColumnDef::new(Alias::new("category_id")).big_integer().not_null())
+            .col(ColumnDef::new(Alias::new("position")).integer().not_null())
+            .col(ColumnDef::new(Alias::new("label")).string().not_null())
+            .col(ColumnDef::new(Alias::new("paragraph")).boolean().not_null())
+            .col(ColumnDef::new(Alias::new("required")).boolean().not_null())
+            .foreign_key(ForeignKey::create()
+                .name("fk-cdd_tickets_question-category_id")
+                .from(Alias::new("cdd_tickets_question"), Alias::new("category_id"))
+                .to(Alias::new("cdd_tickets_category"), Alias::new("id")))
+        ),
+        backend.build(Table::create()
+            .table(Alias::new("cdd_ticket_answer"))
+            .col(ColumnDef::new(Alias::new("id")).big_integer().not_null().auto_increment().primary_key())
+            .col(ColumnDef::new(Alias::new("ticket_id")).big_integer().not_null())
+            .col(ColumnDef::new(Alias::new("position")).integer().not_null())
+            .col(ColumnDef::new(Alias::new("label")).string().not_null())
+            .col(ColumnDef::new(Alias::new("value")).string().not_null())
+            .foreign_key(ForeignKey::create()
+                .name("fk-cdd_ticket_answer-ticket_id")
+                .from(Alias::new("cdd_ticket_answer"), Alias::new("ticket_id"))
+                .to(Alias::new("cdd_ticket"), Alias::new("channel_id")))
+        ),
+        backend.build(Table::create()
+            .table(Alias::new("cdd_ticket_rating"))
+            .col(ColumnDef::new(Alias::new("ticket_id")).big_integer().not_null().primary_key())
+            .col(ColumnDef::new(Alias::new("rating")).integer().not_null())
+            .col(ColumnDef::new(Alias::new("comment")).string())
+            .col(ColumnDef::new(Alias::new("rated_at")).big_integer().not_null())
+            .foreign_key(ForeignKey::create()
+                .name("fk-cdd_ticket_rating-ticket_id")
+                .from(Alias::new("cdd_ticket_rating"), Alias::new("ticket_id"))
+                .to(Alias::new("cdd_ticket"), Alias::new("channel_id")))
+        ),
+        backend.build(Table::create()
+            .table(Alias::new("discord_message_edit"))
+            .col(ColumnDef::new(Alias::new("id")).big_integer().not_null().auto_increment().primary_key())
+            .col(ColumnDef::new(Alias::new("message_id")).big_integer().not_null())
+            .col(ColumnDef::new(Alias::new("content")).string().not_null())
+            .col(ColumnDef::new(Alias::new("edited_at")).big_integer().not_null())
+            .foreign_key(ForeignKey::create()
+                .name("fk-discord_message_edit-message_id")
+                .from(Alias::new("discord_message_edit"), Alias::new("message_id"))
+                .to(Alias::new("discord_message"), Alias::new("id")))
+        ),
+        backend.build(Table::create()
+            .table(Alias::new("discord_message_reaction"))
+            .col(ColumnDef::new(Alias::new("id")).big_integer().not_null().auto_increment().primary_key())
+            .col(ColumnDef::new(Alias::new("message_id")).big_integer().not_null())
+            .col(ColumnDef::new(Alias::new("emoji")).string().not_null())
+            .col(ColumnDef::new(Alias::new("count")).integer().not_null())
+            .foreign_key(ForeignKey::create()
+                .name("fk-discord_message_reaction-message_id")
+                .from(Alias::new("discord_message_reaction"), Alias::new("message_id"))
+                .to(Alias::new("discord_message"), Alias::new("id")))
+        ),
+    ]
+}
@@ -0,0 +1,14 @@
+//! Dernière activité des tickets
+//! 
+//! - date du dernier message d'un membre ou du dernier clic sur "Garder ouvert" (`cdd_ticket.last_activity_at`)
+
+use sea_orm::{DbBackend, Statement, sea_query::{Alias, ColumnDef, Table}};
+
+pub fn statements(backend: DbBackend) -> Vec<Statement> {
+    vec![
+        backend.build(Table::alter()
+            .table(Alias::new("cdd_ticket"))
+            .add_column(ColumnDef::new(Alias::new("last_activity_at")).big_integer())
+        ),
+    ]
+}
@@ -1,52 +1,75 @@
 pub mod model;
 pub mod controller;
-
-use crate::{log_info};
+pub mod migration;
+pub mod backup;
+#[cfg(test)]
+mod tests;
 
 pub type IDType = i64;
 
-use sea_orm::{Database, DbConn, DbErr, Schema, ConnectionTrait, TransactionTrait};
+/// Conversion d'un identifiant Discord (snowflake) en identifiant de la base de données.
+/// 
+/// Les bases de données ne gèrent que des entiers signés : un identifiant supérieur 
+/// à [`IDType::MAX`] est refusé plutôt que converti en valeur négative.
+pub trait DbId {
+    #[allow(clippy::result_large_err)]
+    fn db_id(&self) -> Result<IDType, controller::Error>;
+}
 
-pub async fn start_db(url: &str) -> Result<DbConn, DbErr> {
-    let db = Database::connect(url).await?;
-    check_tables(&db).await?;
-    Ok(db)
+impl DbId for u64 {
+    fn db_id(&self) -> Result<IDType, controller::Error> {
+        IDType::try_from(*self).map_err(|_| controller::Error::BadConversionId(*self))
+    }
 }
 
-macro_rules! create_tables_if_not_exists {
-    ($transaction:ident, $schema:ident, $builder:ident $(, $name:path)*) => {
+macro_rules! impl_db_id {
+    ($($id:ty),*) => {
         $(
-            log_info!("Creating table {}", stringify!($name));
-            let res = $transaction.execute(
-                $builder.build($schema.create_table_from_entity($name).if_not_exists())
-            ).await?;
-            log_info!("Table {} created: {:?}", stringify!($name), res);
+            impl DbId for $id {
+                fn db_id(&self) -> Result<IDType, controller::Error> {
+                    self.0.db_id()
+                }
+            }
         )*
     };
 }
 
-async fn check_tables(db: &DbConn) -> Result<(), DbErr> {
-    use model::*;
-    let builder = db.get_database_backend();
-    let schema = Schema::new(builder);
-    let transaction = db.begin().await?;
-
-    log_info!("Creating tables");
-
-    create_tables_if_not_exists!(transaction, schema, builder, 
-        archive::Archive,
-        discord::Attachment,
-        discord::Channel, 
-        discord::Message, 
-        discord::User, 
-        ticket::Category,
-        ticket::Ticket
-    );
-    match transaction.commit().await {
-        Ok(_) => {
-            log_info!("Tables created");
-            Ok(())
-        },
-        e => e,
+impl_db_id!(
+    serenity::model::id::AttachmentId,
+    serenity::model::id::ChannelId,
+    serenity::model::id::GuildId,
+    serenity::model::id::MessageId,
+    serenity::model::id::RoleId,
+    serenity::model::id::UserId
+);
+
+use sea_orm::{Database, DbConn, DbErr, ConnectionTrait};
+
+/// Se connecte à la base de données et applique les migrations en attente
+pub async fn start_db(url: &str) -> Result<DbConn, DbErr> {
+    let db = Database::connect(url).await?;
+    migration::migrate(&db).await?;
+    Ok(db)
+}
+
+/// Affiche les migrations en attente et les applique si `apply` est vrai, sans démarrer le bot.
+pub async fn migrate_only(url: &str, apply: bool) -> Result<(), DbErr> {
+    let db = Database::connect(url).await?;
+    let backend = db.get_database_backend();
+    let pending = migration::pending(&db).await?;
+    if pending.is_empty() {
+        println!("Le schéma de la base de données est à jour");
+        return Ok(());
+    }
+    for migration in &pending {
+        println!("-- Version {}: {}", migration.version, migration.name);
+        for statement in migration.statements(backend) {
+            println!("{};", statement);
+        }
     }
-}
\ No newline at end of file
+    if apply {
+        migration::migrate(&db).await?;
+        println!("{} migration(s) appliquée(s)", pending.len());
+    }
+    Ok(())
+}
@@ -14,6 +14,12 @@ pub struct Model {
     pub id: IDType,
     pub ticket_id: IDType,
     pub closed_by: IDType,
+    /// Date de fermeture du ticket (timestamp unix)
+    pub closed_at: Option<i64>,
+    /// Raison de la fermeture du ticket
+    pub reason: Option<String>,
+    /// Salon du ticket créé lors de la réouverture de ce ticket archivé
+    pub reopened_as: Option<IDType>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -32,6 +38,12 @@ pub enum Relation {
     Ticket
 }
 
+impl Related<ticket::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Ticket.def()
+    }
+}
+
 #[derive(Debug)]
 pub struct ClosedByUser;
 
@@ -8,7 +8,17 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: IDType,
     pub message_id: IDType,
+    /// Lien vers le CDN de Discord, qui finit par expirer
     pub url: String,
+    pub filename: String,
+    /// Taille en octets
+    pub size: i64,
+    pub content_type: Option<String>,
+    /// Empreinte SHA-256 du contenu, partagée par les pièces jointes identiques
+    pub hash: Option<String>,
+    /// Chemin du fichier enregistré. Vide si le fichier n'a pas été enregistré ou a été supprimé.
+    pub path: Option<String>,
+    pub saved_at: i64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
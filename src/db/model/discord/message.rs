@@ -1,5 +1,5 @@
 use sea_orm::entity::prelude::*;
-use super::{user, attachment, channel};
+use super::{user, attachment, channel, message_edit, message_reaction};
 use crate::db::IDType;
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
@@ -10,8 +10,17 @@ pub struct Model {
     pub channel_id: IDType,
     pub user_id: IDType,
     pub content: String,
+    /// Message auquel celui-ci répond, s'il est enregistré
     pub in_reply_to: Option<IDType>,
+    /// Type du message Discord (0 pour un message classique, 6 pour un message épinglé, 7 pour une arrivée...)
+    pub kind: i32,
+    /// Embeds du message au format JSON
+    pub embeds: Option<String>,
+    /// Stickers du message au format JSON
+    pub stickers: Option<String>,
     pub last_modified: i64,
+    /// Date de suppression du message (timestamp unix), si le message a été supprimé
+    pub deleted_at: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -24,6 +33,10 @@ pub enum Relation {
     InReplyTo,
     #[sea_orm(has_many = "attachment::Entity")]
     Attachments,
+    #[sea_orm(has_many = "message_edit::Entity")]
+    Edits,
+    #[sea_orm(has_many = "message_reaction::Entity")]
+    Reactions,
     #[sea_orm(
         belongs_to = "channel::Entity",
         from = "Column::ChannelId",
@@ -44,6 +57,18 @@ impl Related<attachment::Entity> for Entity {
     }
 }
 
+impl Related<message_edit::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Edits.def()
+    }
+}
+
+impl Related<message_reaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Reactions.def()
+    }
+}
+
 impl Related<channel::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Channel.def()
@@ -57,4 +82,14 @@ impl Model {
     pub fn attachments(&self) -> Select<attachment::Entity> {
         self.find_related(attachment::Entity)
     }
+    pub fn reactions(&self) -> Select<message_reaction::Entity> {
+        self.find_related(message_reaction::Entity)
+    }
+    /// Anciennes versions du message, de la plus ancienne à la plus récente
+    pub fn edits(&self) -> Select<message_edit::Entity> {
+        use sea_orm::QueryOrder;
+        self
+            .find_related(message_edit::Entity)
+            .order_by_asc(message_edit::Column::EditedAt)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,36 @@
+use sea_orm::entity::prelude::*;
+use super::message;
+use crate::db::IDType;
+
+/// Ancienne version d'un message modifié
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "discord_message_edit")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: IDType,
+    /// Message modifié
+    pub message_id: IDType,
+    /// Contenu du message avant la modification
+    pub content: String,
+    /// Date de la modification (timestamp unix)
+    pub edited_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "message::Entity",
+        from = "Column::MessageId",
+        to = "message::Column::Id"
+    )]
+    Message,
+}
+
+impl Related<message::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Message.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel 
+{}
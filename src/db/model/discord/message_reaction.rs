@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+use super::message;
+use crate::db::IDType;
+
+/// Réaction à un message
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "discord_message_reaction")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: IDType,
+    pub message_id: IDType,
+    /// Emoji unicode ou emoji personnalisé au format `<:nom:id>`
+    pub emoji: String,
+    /// Nombre de membres ayant réagi avec cet emoji
+    pub count: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "message::Entity",
+        from = "Column::MessageId",
+        to = "message::Column::Id"
+    )]
+    Message,
+}
+
+impl Related<message::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Message.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel 
+{}
@@ -1,9 +1,13 @@
 pub mod channel;
 pub mod message;
+pub mod message_edit;
+pub mod message_reaction;
 pub mod attachment;
 pub mod user;
 
 pub use channel::Entity as Channel;
 pub use message::Entity as Message;
+pub use message_edit::Entity as MessageEdit;
+pub use message_reaction::Entity as MessageReaction;
 pub use attachment::Entity as Attachment;
 pub use user::Entity as User;
\ No newline at end of file
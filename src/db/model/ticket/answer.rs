@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+
+use crate::db::IDType;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "cdd_ticket_answer")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: IDType,
+    /// Ticket auquel la réponse est rattachée
+    pub ticket_id: IDType,
+    /// Position de la question dans le formulaire
+    pub position: i32,
+    /// Intitulé de la question au moment de la création du ticket
+    pub label: String,
+    /// Réponse du membre
+    pub value: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::Entity",
+        from = "Column::TicketId",
+        to = "super::Column::ChannelId"
+    )]
+    Ticket,
+}
+
+impl Related<super::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Ticket.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel 
+{}
@@ -8,6 +8,11 @@ pub struct Model {
     /// Identifiant dans la base de données
     #[sea_orm(primary_key)]
     pub id: IDType,
+    /// Identifiant du serveur Discord de la catégorie
+    /// 
+    /// Non renseigné pour les catégories créées avant la gestion de plusieurs serveurs, 
+    /// elles sont assignées à leur serveur au démarrage du bot.
+    pub guild_id: Option<IDType>,
     /// Identifiant de la catégorie Discord
     pub discord_category_id: i64,
     /// Nom de la catégorie
@@ -20,14 +25,36 @@ pub struct Model {
     /// Description de la catégorie
     pub description: Option<String>,
     pub hidden: bool,
+    /// Nombre d'heures sans message avant la fermeture automatique d'un ticket
+    /// 
+    /// Aucune fermeture automatique si non renseigné
+    pub inactivity_timeout: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation 
 {
     #[sea_orm(has_many = "super::Entity")]
-    Tickets
+    Tickets,
+    #[sea_orm(has_many = "super::question::Entity")]
+    Questions,
+}
+
+impl Related<super::question::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Questions.def()
+    }
 }
 
 impl ActiveModelBehavior for ActiveModel 
-{}
\ No newline at end of file
+{}
+
+impl Model {
+    /// Questions du formulaire d'ouverture de ticket, dans l'ordre
+    pub fn questions(&self) -> Select<super::question::Entity> {
+        use sea_orm::QueryOrder;
+        self
+            .find_related(super::question::Entity)
+            .order_by_asc(super::question::Column::Position)
+    }
+}
\ No newline at end of file
@@ -1,13 +1,19 @@
 pub mod category;
+pub mod question;
+pub mod answer;
+pub mod rating;
 
 pub use category::Entity as Category;
+pub use question::Entity as Question;
+pub use answer::Entity as Answer;
+pub use rating::Entity as Rating;
 pub use Entity as Ticket;
 
 
 use sea_orm::entity::prelude::*;
 use crate::db::{
     IDType,
-    model::discord
+    model::{discord, archive}
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
@@ -18,6 +24,15 @@ pub struct Model {
 
     pub category_id: IDType,
     pub opened_by: IDType,
+    /// Date d'ouverture du ticket (timestamp unix)
+    pub opened_at: Option<i64>,
+    /// Date du premier message du staff dans le ticket (timestamp unix)
+    pub first_staff_response_at: Option<i64>,
+    /// Membre du staff ayant répondu en premier
+    pub first_staff_response_by: Option<IDType>,
+    /// Date du dernier message d'un membre dans le ticket (timestamp unix), 
+    /// utilisée pour la fermeture automatique des tickets inactifs
+    pub last_activity_at: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -40,6 +55,10 @@ pub enum Relation {
         to = "discord::user::Column::Id"
     )]
     OpenedBy,
+    #[sea_orm(has_many = "answer::Entity")]
+    Answers,
+    #[sea_orm(has_one = "archive::Entity")]
+    Archive,
 }
 
 impl Related<discord::channel::Entity> for Entity {
@@ -58,5 +77,26 @@ impl Related<discord::user::Entity> for Entity {
     }
 }
 
+impl Related<answer::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Answers.def()
+    }
+}
+
+impl Related<archive::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Archive.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel 
-{}
\ No newline at end of file
+{}
+
+impl Model {
+    pub fn answers(&self) -> Select<answer::Entity> {
+        use sea_orm::QueryOrder;
+        self
+            .find_related(answer::Entity)
+            .order_by_asc(answer::Column::Position)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,57 @@
+use sea_orm::entity::prelude::*;
+use serenity::model::application::component::InputTextStyle;
+
+use crate::db::IDType;
+use super::category;
+
+/// Nombre maximum de questions par catégorie. 
+/// 
+/// Limité par Discord au nombre de champs d'un modal.
+pub const MAX_QUESTIONS: usize = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "cdd_tickets_question")]
+pub struct Model {
+    /// Identifiant dans la base de données
+    #[sea_orm(primary_key)]
+    pub id: IDType,
+    /// Catégorie de ticket à laquelle la question est rattachée
+    pub category_id: IDType,
+    /// Position de la question dans le formulaire
+    pub position: i32,
+    /// Intitulé de la question
+    pub label: String,
+    /// Réponse sur plusieurs lignes
+    pub paragraph: bool,
+    /// Réponse obligatoire
+    pub required: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "category::Entity",
+        from = "Column::CategoryId",
+        to = "category::Column::Id"
+    )]
+    Category,
+}
+
+impl Related<category::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Category.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel 
+{}
+
+impl Model {
+    pub fn input_style(&self) -> InputTextStyle {
+        if self.paragraph {
+            InputTextStyle::Paragraph
+        } else {
+            InputTextStyle::Short
+        }
+    }
+}
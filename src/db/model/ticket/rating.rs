@@ -0,0 +1,41 @@
+use sea_orm::entity::prelude::*;
+
+use crate::db::IDType;
+
+/// Note minimale d'un ticket
+pub const MIN_RATING: i32 = 1;
+/// Note maximale d'un ticket
+pub const MAX_RATING: i32 = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "cdd_ticket_rating")]
+pub struct Model {
+    /// Ticket noté
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub ticket_id: IDType,
+    /// Note donnée par le membre, de 1 à 5 étoiles
+    pub rating: i32,
+    /// Commentaire facultatif du membre
+    pub comment: Option<String>,
+    /// Date de la notation (timestamp unix)
+    pub rated_at: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::Entity",
+        from = "Column::TicketId",
+        to = "super::Column::ChannelId"
+    )]
+    Ticket,
+}
+
+impl Related<super::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Ticket.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel 
+{}
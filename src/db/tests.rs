@@ -0,0 +1,201 @@
+//! Tests des contrôleurs de la base de données.
+//!
+//! Chaque test est exécuté sur SQLite en mémoire et, avec la feature `postgres`, sur la base
+//! PostgreSQL désignée par la variable d'environnement `CDDIO_TEST_POSTGRES_URL`.
+//! Le schéma `public` de cette base est recréé à chaque test : elle doit être dédiée aux tests.
+
+use std::future::Future;
+
+use sea_orm::{entity::*, Database, DbConn};
+use serenity::model::id::{ChannelId, GuildId, MessageId, UserId};
+
+use super::{controller, migration, model, DbId, IDType};
+
+const GUILD: GuildId = GuildId(800_000_000_000_000_001);
+const CHANNEL: ChannelId = ChannelId(800_000_000_000_000_002);
+const OWNER: UserId = UserId(800_000_000_000_000_003);
+const STAFF: UserId = UserId(800_000_000_000_000_004);
+
+/// Exécute un test sur chaque moteur de base de données disponible
+async fn on_each_backend<F, Fut>(test: F)
+    where F: Fn(DbConn) -> Fut, Fut: Future<Output = ()>
+{
+    let db = Database::connect("sqlite::memory:").await.expect("Connexion à SQLite impossible");
+    migration::migrate(&db).await.expect("Migration SQLite impossible");
+    test(db).await;
+    #[cfg(feature = "postgres")]
+    if let Ok(url) = std::env::var("CDDIO_TEST_POSTGRES_URL") {
+        use sea_orm::{ConnectionTrait, Statement};
+        lazy_static::lazy_static! {
+            static ref POSTGRES: tokio::sync::Mutex<()> = tokio::sync::Mutex::new(());
+        }
+        let _lock = POSTGRES.lock().await;
+        let db = Database::connect(url).await.expect("Connexion à PostgreSQL impossible");
+        for sql in ["DROP SCHEMA IF EXISTS public CASCADE", "CREATE SCHEMA public"] {
+            db.execute(Statement::from_string(db.get_database_backend(), sql.to_string())).await.expect("Réinitialisation du schéma impossible");
+        }
+        migration::migrate(&db).await.expect("Migration PostgreSQL impossible");
+        test(db).await;
+    }
+}
+
+fn category() -> controller::ticket::NewCategory {
+    controller::ticket::NewCategory {
+        guild_id: GUILD,
+        name: "Support".to_string(),
+        prefix: "support".to_string(),
+        discord_category_id: ChannelId(1),
+        description: None,
+        hidden: false,
+        inactivity_timeout: Some(24),
+    }
+}
+
+fn user(id: UserId) -> serenity::model::user::User {
+    let mut user = serenity::model::user::User::default();
+    user.id = id;
+    user.name = format!("user{}", id.0 % 10);
+    user
+}
+
+fn message(id: u64, author: UserId, content: &str) -> serenity::model::channel::Message {
+    serde_json::from_value(serde_json::json!({
+        "id": id.to_string(),
+        "channel_id": CHANNEL.0.to_string(),
+        "author": {"id": author.0.to_string(), "username": "user", "discriminator": "0001", "avatar": null},
+        "content": content,
+        "timestamp": "2023-01-01T00:00:00+00:00",
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "embeds": [],
+        "pinned": false,
+        "type": 0,
+    })).expect("Message invalide")
+}
+
+/// Enregistre une catégorie et un ticket ouvert par [`OWNER`] dans [`CHANNEL`]
+async fn open_ticket(db: &DbConn) -> IDType {
+    let category_id = controller::ticket::add_category(db, category()).await.unwrap();
+    model::discord::Channel::insert(model::discord::channel::ActiveModel {
+        id: Set(CHANNEL.db_id().unwrap()),
+        name: Set("support-user".to_string()),
+    }).exec(db).await.unwrap();
+    controller::discord::save_user(db, user(OWNER)).await.unwrap();
+    controller::discord::save_user(db, user(STAFF)).await.unwrap();
+    model::ticket::Ticket::insert(model::ticket::ActiveModel {
+        channel_id: Set(CHANNEL.db_id().unwrap()),
+        category_id: Set(category_id),
+        opened_by: Set(OWNER.db_id().unwrap()),
+        opened_at: Set(Some(chrono::Utc::now().timestamp())),
+        ..Default::default()
+    }).exec(db).await.unwrap();
+    category_id
+}
+
+#[test]
+fn db_id_rejects_out_of_range_snowflakes() {
+    assert_eq!(UserId(42).db_id().unwrap(), 42);
+    assert!(matches!(UserId(u64::MAX).db_id(), Err(controller::Error::BadConversionId(u64::MAX))));
+}
+
+#[tokio::test]
+async fn migrations_reach_latest_version() {
+    on_each_backend(|db| async move {
+        assert_eq!(migration::current_version(&db).await.unwrap(), migration::latest_version());
+        assert!(migration::pending(&db).await.unwrap().is_empty());
+    }).await;
+}
+
+#[tokio::test]
+async fn questions_are_renumbered_on_removal() {
+    on_each_backend(|db| async move {
+        use model::ticket::question;
+        use sea_orm::{QueryFilter, QueryOrder, ColumnTrait};
+        let category_id = controller::ticket::add_category(&db, category()).await.unwrap();
+        for label in ["Pseudo", "Problème", "Captures"] {
+            controller::ticket::add_question(&db, category_id, label.to_string(), false, true).await.unwrap();
+        }
+        controller::ticket::remove_question(&db, category_id, 1).await.unwrap();
+        assert!(controller::ticket::remove_question(&db, category_id, 5).await.is_err());
+        let questions = question::Entity::find()
+            .filter(question::Column::CategoryId.eq(category_id))
+            .order_by_asc(question::Column::Position)
+            .all(&db).await.unwrap()
+            .into_iter()
+            .map(|q| (q.position, q.label))
+            .collect::<Vec<_>>();
+        assert_eq!(questions, vec![(0, "Pseudo".to_string()), (1, "Captures".to_string())]);
+    }).await;
+}
+
+#[tokio::test]
+async fn messages_keep_edits_reactions_and_deletions() {
+    on_each_backend(|db| async move {
+        open_ticket(&db).await;
+        controller::discord::save_message(&db, &message(900_000_000_000_000_001, OWNER, "Bonjour")).await.unwrap();
+        controller::discord::save_message(&db, &message(900_000_000_000_000_002, STAFF, "Bonjour !")).await.unwrap();
+        // Un message déjà enregistré est ignoré
+        controller::discord::save_message(&db, &message(900_000_000_000_000_001, OWNER, "Doublon")).await.unwrap();
+        let first = MessageId(900_000_000_000_000_001);
+        controller::discord::update_message(&db, first, "Bonjour, j'ai un problème".to_string(), 10).await.unwrap();
+        controller::discord::add_reaction(&db, first, "👍".to_string()).await.unwrap();
+        controller::discord::add_reaction(&db, first, "👍".to_string()).await.unwrap();
+        controller::discord::remove_reaction(&db, first, "👍".to_string()).await.unwrap();
+        controller::discord::mark_messages_deleted(&db, &[MessageId(900_000_000_000_000_002)], 20).await.unwrap();
+
+        let messages = controller::ticket::archived_messages(&db, CHANNEL).await.unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].message.content, "Bonjour, j'ai un problème");
+        assert_eq!(messages[0].reactions, vec![("👍".to_string(), 1)]);
+        assert!(messages[0].author.as_deref().unwrap_or_default().starts_with("user3#"));
+        assert_eq!(messages[1].message.deleted_at, Some(20));
+        let edits = model::discord::MessageEdit::find().all(&db).await.unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].content, "Bonjour");
+    }).await;
+}
+
+#[tokio::test]
+async fn staff_response_and_rating_are_recorded() {
+    on_each_backend(|db| async move {
+        open_ticket(&db).await;
+        // Le propriétaire du ticket n'est pas compté comme une réponse du staff
+        controller::ticket::record_staff_response(&db, CHANNEL, OWNER, 100).await.unwrap();
+        controller::ticket::record_staff_response(&db, CHANNEL, STAFF, 200).await.unwrap();
+        controller::ticket::record_staff_response(&db, CHANNEL, STAFF, 300).await.unwrap();
+        let ticket = model::ticket::Ticket::find_by_id(CHANNEL.db_id().unwrap()).one(&db).await.unwrap().unwrap();
+        assert_eq!(ticket.first_staff_response_at, Some(200));
+        assert_eq!(ticket.first_staff_response_by, Some(STAFF.db_id().unwrap()));
+
+        assert!(controller::ticket::save_rating(&db, CHANNEL, 0).await.is_err());
+        assert!(controller::ticket::save_rating_comment(&db, CHANNEL, "Merci".to_string()).await.is_err());
+        controller::ticket::save_rating(&db, CHANNEL, 3).await.unwrap();
+        controller::ticket::save_rating(&db, CHANNEL, 5).await.unwrap();
+        controller::ticket::save_rating_comment(&db, CHANNEL, "Merci".to_string()).await.unwrap();
+        let rating = model::ticket::Rating::find_by_id(CHANNEL.db_id().unwrap()).one(&db).await.unwrap().unwrap();
+        assert_eq!((rating.rating, rating.comment.as_deref()), (5, Some("Merci")));
+    }).await;
+}
+
+#[tokio::test]
+async fn ticket_stats_are_computed_by_the_database() {
+    on_each_backend(|db| async move {
+        let category_id = open_ticket(&db).await;
+        controller::ticket::record_staff_response(&db, CHANNEL, STAFF, chrono::Utc::now().timestamp() + 60).await.unwrap();
+        controller::ticket::save_rating(&db, CHANNEL, 4).await.unwrap();
+        assert_eq!(controller::ticket::opened_tickets(&db, GUILD, OWNER).await.unwrap().len(), 1);
+        assert!(controller::ticket::opened_tickets(&db, GuildId(1), OWNER).await.unwrap().is_empty());
+        assert_eq!(controller::ticket::opened_tickets_in_category(&db, category_id).await.unwrap().len(), 1);
+
+        let stats = controller::ticket::ticket_stats(&db, GUILD, 0).await.unwrap();
+        assert_eq!(stats.per_category, vec![("Support".to_string(), 1)]);
+        assert!(stats.median_first_response.is_some());
+        assert_eq!(stats.median_resolution, None);
+        assert_eq!(stats.first_responses, vec![(STAFF.db_id().unwrap(), 1)]);
+        assert_eq!(stats.ratings_per_category, vec![("Support".to_string(), 4.0, 1)]);
+    }).await;
+}
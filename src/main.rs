@@ -53,6 +53,18 @@ async fn main() {
         panic!("Unable to set logger: {}", e);
     }
     let config = config::Config::load("./config.yaml").expect_log("Could not load the configuration file");
+    let args = std::env::args().collect::<Vec<_>>();
+    if let Some(position) = args.iter().position(|arg| arg == "--restore") {
+        let file = args.get(position + 1).ok_or("missing backup file").expect_log("Usage: cddio --restore <file>");
+        if let Some(previous) = db::backup::restore(&config.bot.database, std::path::Path::new(file)).await.expect_log("Unable to restore the backup") {
+            println!("Previous database moved to {}", previous.display());
+        }
+    }
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    if dry_run || args.iter().any(|arg| arg == "--migrate-only") {
+        db::migrate_only(&config.bot.database, !dry_run).await.expect_log("Unable to migrate the database");
+        return;
+    }
     let database = db::start_db(&config.bot.database).await.expect_log("Unable to start the database");
     let mut bot = bot::Bot::new(config, database).await
         .or_else(|e|Err(e.to_string()))